@@ -6,7 +6,7 @@ use minimal_windows_gui as win;
 
 const CLASS_NAME: &str = "myWindowClass";
 
-const IDB_BALL: u16 = 101;
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
 
 thread_local! {
     static BALL: Cell<Option<win::bitmap::Bitmap>> = Cell::new(None);
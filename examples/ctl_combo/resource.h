@@ -0,0 +1,10 @@
+#define IDC_STATIC    -1
+#define IDD_MAIN      101
+
+#define IDC_TEXT      1000
+#define IDC_NUMBER    1001
+#define IDC_COMBO     1002
+#define IDC_ADD       1003
+#define IDC_CLEAR     1004
+#define IDC_REMOVE    1005
+#define IDC_SHOWCOUNT 1006
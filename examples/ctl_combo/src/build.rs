@@ -0,0 +1,6 @@
+use minimal_windows_gui as win;
+
+fn main() {
+    win::resource::compile("ctl_combo.rc");
+    win::resource::generate_ids("resource.h");
+}
@@ -0,0 +1,98 @@
+//! The same dialog as `ctl_one`, but using a drop-down list instead of a list box.
+//! Only one item can be selected at a time, so that's the one removed.
+use std::process::exit;
+use minimal_windows_gui as win;
+use win::control::ComboBox;
+
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
+
+fn main() -> win::Result<()> {
+    exit(win::dialog::show(IDD_MAIN, dialog_callback).unwrap() as i32)
+}
+
+fn dialog_callback(dialog: &win::window::Window, message: win::message::Message) -> isize {
+    use win::message::Message;
+
+    match message {
+        Message::InitDialog => {
+            let text_ctl = dialog.get_dialog_item(IDC_TEXT).unwrap();
+            let number_ctl = dialog.get_dialog_item(IDC_NUMBER).unwrap();
+
+            text_ctl.set_text("This is a string");
+            number_ctl.set_text("5");
+        }
+        Message::Command(info) => {
+            if let Some(control) = info.control_data() {
+                handle_command(dialog, control).unwrap();
+            }
+        }
+        Message::Close => {
+            dialog.end_dialog(0).unwrap();
+        }
+        _ => return 0,
+    }
+
+    1
+}
+
+// Separate function to avoid a bit of rightwards drift.
+fn handle_command(
+    dialog: &win::window::Window,
+    control: win::message::ControlData,
+) -> win::Result<()> {
+    let number_ctl = dialog.get_dialog_item(IDC_NUMBER)?;
+    let text_ctl = dialog.get_dialog_item(IDC_TEXT)?;
+    // Each item remembers how many times it was added.
+    let combo_ctl: ComboBox<u32> = dialog.get_dialog_item(IDC_COMBO)?.into();
+    let show_count_ctl = dialog.get_dialog_item(IDC_SHOWCOUNT)?;
+
+    match control.id {
+        IDC_ADD => match number_ctl.get_text().parse::<u32>() {
+            Ok(n_times) => {
+                let len = text_ctl.get_text_len();
+                if len > 0 {
+                    let text = text_ctl.get_text();
+                    for _ in 0..n_times {
+                        combo_ctl.add_with_data(&text, n_times)?;
+                    }
+                } else {
+                    win::messagebox::message_box("Warning", "You didn't enter anything!", &[])?;
+                }
+            }
+            Err(_) => {
+                win::messagebox::message_box("Warning", "Couldn't translate that number :(", &[])?;
+            }
+        },
+        IDC_REMOVE => match combo_ctl.selected() {
+            Some(index) => {
+                combo_ctl.delete(index)?;
+                show_count_ctl.set_text("-");
+            }
+            None => {
+                win::messagebox::message_box("Warning", "No item selected.", &[])?;
+            }
+        },
+        IDC_COMBO
+            if matches!(
+                control.combo_box_code(),
+                win::message::ComboBoxMessage::SelectionChange
+            ) =>
+        {
+            match combo_ctl.selected() {
+                Some(index) => {
+                    let data = combo_ctl.item_data(index)?.unwrap_or(0);
+                    show_count_ctl.set_text(&data.to_string());
+                }
+                None => {
+                    show_count_ctl.set_text("-");
+                }
+            }
+        }
+        IDC_CLEAR => {
+            combo_ctl.clear();
+            show_count_ctl.set_text("-");
+        }
+        _ => {}
+    }
+    Ok(())
+}
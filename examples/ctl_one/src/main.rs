@@ -2,16 +2,9 @@
 //! http://winprog.org/tutorial/dialogs.html
 use std::process::exit;
 use minimal_windows_gui as win;
+use win::control::list_box::{ListBox, Multiple};
 
-const IDD_MAIN: u16 = 101;
-
-const IDC_TEXT: u16 = 1000;
-const IDC_NUMBER: u16 = 1001;
-const IDC_LIST: u16 = 1002;
-const IDC_ADD: u16 = 1003;
-const IDC_CLEAR: u16 = 1004;
-const IDC_REMOVE: u16 = 1005;
-const IDC_SHOWCOUNT: u16 = 1006;
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
 
 fn main() -> win::Result<()> {
     exit(win::dialog::show(IDD_MAIN, dialog_callback).unwrap() as i32)
@@ -49,7 +42,8 @@ fn handle_command(
 ) -> win::Result<()> {
     let number_ctl = dialog.get_dialog_item(IDC_NUMBER)?;
     let text_ctl = dialog.get_dialog_item(IDC_TEXT)?;
-    let list_ctl = dialog.get_dialog_item(IDC_LIST)?;
+    // Each item remembers how many times it was added.
+    let list_ctl: ListBox<u32, Multiple> = dialog.get_dialog_item(IDC_LIST)?.into();
     let show_count_ctl = dialog.get_dialog_item(IDC_SHOWCOUNT)?;
 
     match control.id {
@@ -59,8 +53,7 @@ fn handle_command(
                 if len > 0 {
                     let text = text_ctl.get_text();
                     for _ in 0..n_times {
-                        let index = list_ctl.add_string_item(&text).unwrap();
-                        list_ctl.set_item_data(index, n_times as isize).unwrap();
+                        list_ctl.add_with_data(&text, n_times)?;
                     }
                 } else {
                     win::messagebox::message_box("Warning", "You didn't enter anything!", &[])?;
@@ -70,13 +63,13 @@ fn handle_command(
                 win::messagebox::message_box("Warning", "Couldn't translate that number :(", &[])?;
             }
         },
-        IDC_REMOVE => match list_ctl.get_selection_count() {
+        IDC_REMOVE => match list_ctl.selected_count() {
             Ok(0) => {
                 win::messagebox::message_box("Warning", "No items selected.", &[])?;
             }
             Ok(_) => {
-                for index in list_ctl.get_selected_items().unwrap().into_iter().rev() {
-                    list_ctl.delete_string_item(index).unwrap();
+                for index in list_ctl.selected_items()?.into_iter().rev() {
+                    list_ctl.delete(index)?;
                 }
             }
             Err(_) => {
@@ -89,10 +82,10 @@ fn handle_command(
                 win::message::ListBoxMessage::SelectionChange
             ) =>
         {
-            match list_ctl.get_selection_count() {
-                Ok(1) => match list_ctl.get_selected_items() {
+            match list_ctl.selected_count() {
+                Ok(1) => match list_ctl.selected_items() {
                     Ok(items) => {
-                        let data = list_ctl.get_item_data(items[0] as usize).unwrap();
+                        let data = list_ctl.item_data(items[0])?.unwrap_or(0);
                         show_count_ctl.set_text(&data.to_string());
                     }
                     Err(_) => {
@@ -112,7 +105,7 @@ fn handle_command(
             }
         }
         IDC_CLEAR => {
-            list_ctl.clear_content();
+            list_ctl.clear();
         }
         _ => {}
     }
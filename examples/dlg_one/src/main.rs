@@ -5,11 +5,7 @@ use minimal_windows_gui as win;
 
 const CLASS_NAME: &str = "myWindowClass";
 
-const IDR_MYMENU: u16 = 101;
-const IDD_ABOUT: u16 = 102;
-
-const ID_FILE_EXIT: u16 = 40001;
-const ID_HELP_ABOUT: u16 = 40002;
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
 
 const DLG_OK: isize = 1;
 const DLG_CANCEL: isize = 2;
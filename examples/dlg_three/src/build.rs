@@ -1,5 +1,6 @@
-use embed_resource;
+use minimal_windows_gui as win;
 
 fn main() {
-    embed_resource::compile("dlg_three.rc");
+    win::resource::compile("dlg_three.rc");
+    win::resource::generate_ids("resource.h");
 }
@@ -1,28 +1,29 @@
 //! Additional dialog messages without using a main window.
 //! http://winprog.org/tutorial/dlgfaq.html
-use std::cell::Cell;
 use std::process::exit;
 use minimal_windows_gui as win;
 
-const IDD_MAIN: u16 = 101;
-
-thread_local! {
-    static BACKGROUND_BRUSH: Cell<Option<win::gdi::brush::Brush>> = Cell::new(None);
-}
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
 
 fn main() -> win::Result<()> {
-    exit(win::dialog::show(IDD_MAIN, dialog_callback).unwrap() as i32)
+    // The brush is owned by the dialog's callback, and dropped along with it.
+    let brush = win::gdi::brush::Brush::new_solid_rgb(0, 0, 0).unwrap();
+    exit(win::dialog::show(IDD_MAIN, move |dialog, message| {
+        dialog_callback(&brush, dialog, message)
+    })
+    .unwrap() as i32)
 }
 
-fn dialog_callback(dialog: &win::window::Window, message: win::message::Message) -> isize {
+fn dialog_callback(
+    brush: &win::gdi::brush::Brush,
+    dialog: &win::window::Window,
+    message: win::message::Message,
+) -> isize {
     use win::message::Message;
     use win::messagebox::Button;
 
     match message {
         Message::InitDialog => {
-            let brush = win::gdi::brush::Brush::new_solid_rgb(0, 0, 0).unwrap();
-            BACKGROUND_BRUSH.with(|cell| cell.set(Some(brush)));
-
             dialog.set_icon(win::icon::Icon::Application).unwrap();
             dialog.set_small_icon(win::icon::Icon::Application).unwrap();
         }
@@ -30,33 +31,17 @@ fn dialog_callback(dialog: &win::window::Window, message: win::message::Message)
             dialog.end_dialog(0).unwrap();
         }
         Message::ControlColorDialog(_info) => {
-            let mut result = 0;
-            BACKGROUND_BRUSH.with(|cell| {
-                let brush = cell.take();
-                result = brush.as_ref().unwrap().dlg_proc_value();
-                cell.set(brush);
-            });
-            return result;
+            return brush.dlg_proc_value();
         }
         Message::ControlColorStatic(info) => {
             info.set_text_color(255, 255, 255).unwrap();
             info.set_background_transparency(true).unwrap();
-
-            let mut result = 0;
-            BACKGROUND_BRUSH.with(|cell| {
-                let brush = cell.take();
-                result = brush.as_ref().unwrap().dlg_proc_value();
-                cell.set(brush);
-            });
-            return result;
+            return brush.dlg_proc_value();
         }
         Message::Command(info) => match info.control_data().map(|c| c.std_button()).flatten() {
             Some(Button::Ok) => dialog.end_dialog(0).unwrap(),
             _ => {}
         },
-        Message::Destroy => {
-            BACKGROUND_BRUSH.with(|cell| drop(cell.take().take()));
-        }
         _ => return 0,
     }
 
@@ -7,19 +7,7 @@ use minimal_windows_gui as win;
 
 const CLASS_NAME: &str = "myWindowClass";
 
-const IDR_MYMENU: u16 = 101;
-const IDD_TOOLBAR: u16 = 101;
-
-const IDC_PRESS: u16 = 1000;
-const IDC_OTHER: u16 = 1001;
-
-const ID_FILE_EXIT: u16 = 40001;
-const ID_DIALOG_SHOW: u16 = 40002;
-const ID_DIALOG_HIDE: u16 = 40003;
-
-thread_local! {
-    static TOOLBAR_HANDLE: Cell<Option<win::window::Window<'static>>> = Cell::new(None);
-}
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
 
 fn main() -> win::Result<()> {
     let class = &win::class::build()
@@ -32,7 +20,11 @@ fn main() -> win::Result<()> {
         .expect("window registration failed");
 
     let window = win::window::build()
-        .set_message_callback(main_window_callback)
+        .set_message_callback({
+            // Each main window keeps the handle to its own toolbar dialog.
+            let toolbar = Cell::new(None);
+            move |window, message| main_window_callback(&toolbar, window, message)
+        })
         .add_extended_style(win::window::ExtendedStyle::ClientEdge)
         .add_style(win::window::Style::OverlappedWindow)
         .size(240, 120)
@@ -46,6 +38,7 @@ fn main() -> win::Result<()> {
 }
 
 fn main_window_callback(
+    toolbar: &Cell<Option<win::window::Window<'static>>>,
     window: &win::window::Window,
     message: win::message::Message,
 ) -> Option<isize> {
@@ -56,7 +49,7 @@ fn main_window_callback(
             match window.create_dialog(IDD_TOOLBAR, tool_dialog_callback) {
                 Ok(dialog) => {
                     dialog.show();
-                    TOOLBAR_HANDLE.with(|cell| cell.set(Some(dialog)));
+                    toolbar.set(Some(dialog));
                 }
                 Err(_) => {
                     win::messagebox::message_box(
@@ -75,28 +68,21 @@ fn main_window_callback(
                         window.close().unwrap();
                     }
                     ID_DIALOG_SHOW => {
-                        TOOLBAR_HANDLE.with(|cell| {
-                            let handle = cell.take();
-                            handle.as_ref().unwrap().show();
-                            cell.set(handle);
-                        });
+                        let handle = toolbar.take();
+                        handle.as_ref().unwrap().show();
+                        toolbar.set(handle);
                     }
                     ID_DIALOG_HIDE => {
-                        TOOLBAR_HANDLE.with(|cell| {
-                            let handle = cell.take();
-                            handle.as_ref().unwrap().hide();
-                            cell.set(handle);
-                        });
+                        let handle = toolbar.take();
+                        handle.as_ref().unwrap().hide();
+                        toolbar.set(handle);
                     }
                     _ => {}
                 }
             }
         }
         Message::Close => {
-            TOOLBAR_HANDLE.with(|cell| {
-                let handle = cell.take();
-                handle.unwrap().destroy().unwrap();
-            });
+            toolbar.take().unwrap().destroy().unwrap();
             window.destroy().unwrap();
         }
         Message::Destroy => {
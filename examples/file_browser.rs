@@ -0,0 +1,246 @@
+//! A file browser using a list view in report view.
+//! Activate a folder to open it, click on a column header to sort by it, and press F2 (or click
+//! on a selected item) to rename it.
+use minimal_windows_gui as win;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::PathBuf;
+use std::process::exit;
+use win::control::list_view::{self, ListView};
+
+const CLASS_NAME: &str = "myWindowClass";
+
+const IDC_MAIN_LIST: u16 = 101;
+
+const COLUMN_NAME: usize = 0;
+const COLUMN_SIZE: usize = 1;
+const COLUMN_TYPE: usize = 2;
+
+const PARENT_NAME: &str = "..";
+
+// The value attached to each item of the list view.
+#[derive(Clone)]
+struct Entry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+struct Browser {
+    path: RefCell<PathBuf>,
+    // Column to sort by, and whether the order is ascending.
+    sort: Cell<(usize, bool)>,
+}
+
+fn main() -> win::Result<()> {
+    win::init_common_controls();
+
+    let class = &win::class::build()
+        .load_icon(win::icon::Icon::Application)?
+        .load_cursor(win::cursor::Cursor::Arrow)?
+        .background(win::class::Background::Window)
+        .load_small_icon(win::icon::Icon::Application)?
+        .register(CLASS_NAME)
+        .expect("window registration failed");
+
+    let browser = Browser {
+        path: RefCell::new(std::env::current_dir().expect("no current directory")),
+        sort: Cell::new((COLUMN_NAME, true)),
+    };
+
+    let window = win::window::build()
+        .set_message_callback(move |window, message| {
+            main_window_callback(window, message, &browser)
+        })
+        .add_style(win::window::Style::OverlappedWindow)
+        .size(560, 400)
+        .create(class, "File Browser")
+        .expect("window creation failed");
+
+    window.show_default();
+    window.update().unwrap();
+
+    exit(win::message_loop())
+}
+
+fn main_window_callback(
+    window: &win::window::Window,
+    message: win::message::Message,
+    browser: &Browser,
+) -> Option<isize> {
+    use win::message::Message;
+    use win::notify::{ListViewNotification, Notification};
+
+    match message {
+        Message::Create => {
+            let list_ctl: ListView<Entry> = ListView::create(
+                win::window::build()
+                    .add_extended_style(win::window::ExtendedStyle::ClientEdge)
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(list_view::Style::Report)
+                    .add_control_style(list_view::Style::EditLabels)
+                    .add_control_style(list_view::Style::ShowSelectionAlways)
+                    .pos(0, 0)
+                    .size(100, 100)
+                    .parent(window)
+                    .set_child_id(IDC_MAIN_LIST),
+            )
+            .expect("list view creation failed");
+
+            list_ctl.set_font(win::font::get_default().unwrap());
+            list_ctl.set_extended_style(list_view::ExtendedStyle::FullRowSelect, true);
+            list_ctl.set_extended_style(list_view::ExtendedStyle::DoubleBuffer, true);
+
+            use list_view::Alignment;
+            list_ctl.add_column("Name", 280, Alignment::Left).unwrap();
+            list_ctl.add_column("Size", 100, Alignment::Right).unwrap();
+            list_ctl.add_column("Type", 100, Alignment::Left).unwrap();
+
+            refresh(window, browser).unwrap();
+        }
+        Message::Size(_info) => {
+            let list_ctl = window.get_dialog_item(IDC_MAIN_LIST).unwrap();
+            list_ctl.set_rect(window.get_rect().unwrap()).unwrap();
+        }
+        Message::Notify(info) => {
+            let list_ctl: ListView<Entry> = window.get_dialog_item(IDC_MAIN_LIST).unwrap().into();
+            match info.notification() {
+                Notification::ListView(ListViewNotification::ItemActivate(item)) => {
+                    let entry = item
+                        .item()
+                        .and_then(|index| list_ctl.item_data(index).ok().flatten());
+                    if let Some(entry) = entry.filter(|entry| entry.is_dir) {
+                        if entry.name == PARENT_NAME {
+                            browser.path.borrow_mut().pop();
+                        } else {
+                            browser.path.borrow_mut().push(&entry.name);
+                        }
+                        refresh(window, browser).unwrap();
+                    }
+                }
+                Notification::ListView(ListViewNotification::ColumnClick(column)) => {
+                    let (sort_column, ascending) = browser.sort.get();
+                    let column = column.sub_item();
+                    browser
+                        .sort
+                        .set((column, column != sort_column || !ascending));
+                    sort(&list_ctl, browser).unwrap();
+                }
+                Notification::ListView(ListViewNotification::BeginLabelEdit(label)) => {
+                    // The parent folder can't be renamed.
+                    let entry = list_ctl.item_data(label.item()).ok().flatten();
+                    if !matches!(entry, Some(entry) if entry.name != PARENT_NAME) {
+                        return Some(1);
+                    }
+                }
+                Notification::ListView(ListViewNotification::EndLabelEdit(label)) => {
+                    return Some(match (label.text(), list_ctl.item_data(label.item())) {
+                        (Some(name), Ok(Some(entry))) if !name.is_empty() => {
+                            let path = browser.path.borrow();
+                            match fs::rename(path.join(&entry.name), path.join(&name)) {
+                                Ok(()) => {
+                                    let entry = Entry { name, ..entry };
+                                    list_ctl.set_item_data(label.item(), entry).unwrap();
+                                    1
+                                }
+                                Err(e) => {
+                                    win::messagebox::message_box(
+                                        "Error",
+                                        &format!("Could not rename the file: {}", e),
+                                        &[],
+                                    )
+                                    .unwrap();
+                                    0
+                                }
+                            }
+                        }
+                        _ => 0,
+                    });
+                }
+                _ => return None,
+            }
+        }
+        Message::Close => {
+            window.destroy().unwrap();
+        }
+        Message::Destroy => {
+            win::post_quit_message(0);
+        }
+        _ => return None,
+    }
+
+    Some(0)
+}
+
+// Fills the list view with the contents of the current folder.
+fn refresh(window: &win::window::Window, browser: &Browser) -> win::Result<()> {
+    let list_ctl: ListView<Entry> = window.get_dialog_item(IDC_MAIN_LIST)?.into();
+    list_ctl.clear();
+
+    let path = browser.path.borrow();
+    window.set_text(&path.display().to_string());
+
+    if path.parent().is_some() {
+        let entry = Entry {
+            name: PARENT_NAME.to_string(),
+            is_dir: true,
+            size: 0,
+        };
+        add_entry(&list_ctl, entry)?;
+    }
+
+    // Entries that can't be read are skipped.
+    if let Ok(entries) = fs::read_dir(&*path) {
+        for entry in entries.flatten() {
+            if let Ok(metadata) = entry.metadata() {
+                let entry = Entry {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    is_dir: metadata.is_dir(),
+                    size: metadata.len(),
+                };
+                add_entry(&list_ctl, entry)?;
+            }
+        }
+    }
+
+    sort(&list_ctl, browser)?;
+    if list_ctl.count() != 0 {
+        list_ctl.set_focused(0)?;
+    }
+    Ok(())
+}
+
+fn add_entry(list_ctl: &ListView<Entry>, entry: Entry) -> win::Result<()> {
+    let index = list_ctl.add(&entry.name)?;
+    if !entry.is_dir {
+        list_ctl.set_item_text(index, COLUMN_SIZE, &format_size(entry.size))?;
+    }
+    let kind = if entry.is_dir { "Folder" } else { "File" };
+    list_ctl.set_item_text(index, COLUMN_TYPE, kind)?;
+    list_ctl.set_item_data(index, entry)
+}
+
+// Folders always go first, and the parent folder before anything else.
+fn sort(list_ctl: &ListView<Entry>, browser: &Browser) -> win::Result<()> {
+    let (column, ascending) = browser.sort.get();
+    list_ctl.sort_by(|a, b| {
+        let order = match column {
+            COLUMN_SIZE => a.size.cmp(&b.size),
+            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        };
+        let order = if ascending { order } else { order.reverse() };
+
+        (b.name == PARENT_NAME)
+            .cmp(&(a.name == PARENT_NAME))
+            .then(b.is_dir.cmp(&a.is_dir))
+            .then(order)
+    })
+}
+
+fn format_size(size: u64) -> String {
+    match size {
+        0..=1023 => format!("{} B", size),
+        1024..=1048575 => format!("{} KB", size / 1024),
+        _ => format!("{} MB", size / 1048576),
+    }
+}
@@ -0,0 +1,175 @@
+//! A tree of the folders in the current directory, whose children are only read when expanded.
+//! Select a folder to show its path in the title, and press F2 (or click on a selected folder) to
+//! rename it.
+use std::fs;
+use std::path::PathBuf;
+use std::process::exit;
+use minimal_windows_gui as win;
+use win::control::tree_view::{self, Node, TreeView};
+
+const CLASS_NAME: &str = "myWindowClass";
+
+const IDC_MAIN_TREE: u16 = 101;
+
+fn main() -> win::Result<()> {
+    win::init_common_controls();
+
+    let class = &win::class::build()
+        .load_icon(win::icon::Icon::Application)?
+        .load_cursor(win::cursor::Cursor::Arrow)?
+        .background(win::class::Background::Window)
+        .load_small_icon(win::icon::Icon::Application)?
+        .register(CLASS_NAME)
+        .expect("window registration failed");
+
+    let window = win::window::build()
+        .set_message_callback(main_window_callback)
+        .add_style(win::window::Style::OverlappedWindow)
+        .size(400, 480)
+        .create(class, "Folder Tree")
+        .expect("window creation failed");
+
+    window.show_default();
+    window.update().unwrap();
+
+    exit(win::message_loop())
+}
+
+fn main_window_callback(
+    window: &win::window::Window,
+    message: win::message::Message,
+) -> Option<isize> {
+    use win::message::Message;
+    use win::notify::{Notification, TreeViewNotification};
+
+    match message {
+        Message::Create => {
+            let tree_ctl: TreeView<PathBuf> = TreeView::create(
+                win::window::build()
+                    .add_extended_style(win::window::ExtendedStyle::ClientEdge)
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(tree_view::Style::HasButtons)
+                    .add_control_style(tree_view::Style::HasLines)
+                    .add_control_style(tree_view::Style::LinesAtRoot)
+                    .add_control_style(tree_view::Style::EditLabels)
+                    .add_control_style(tree_view::Style::ShowSelectionAlways)
+                    .pos(0, 0)
+                    .size(100, 100)
+                    .parent(window)
+                    .set_child_id(IDC_MAIN_TREE),
+            )
+            .expect("tree view creation failed");
+
+            tree_ctl.set_font(win::font::get_default().unwrap());
+            tree_ctl.set_children_loader(|tree_ctl, node| {
+                if let Ok(Some(path)) = tree_ctl.item_data(node) {
+                    add_folders(tree_ctl, Some(node), path).unwrap();
+                }
+            });
+
+            let root = std::env::current_dir().expect("no current directory");
+            let node = tree_ctl
+                .add_with_data(None, &root.display().to_string(), root)
+                .unwrap();
+            tree_ctl.set_has_children(node, true).unwrap();
+            tree_ctl.set_expanded(node, true);
+        }
+        Message::Size(_info) => {
+            let tree_ctl = window.get_dialog_item(IDC_MAIN_TREE).unwrap();
+            tree_ctl.set_rect(window.get_rect().unwrap()).unwrap();
+        }
+        Message::Notify(info) => {
+            let tree_ctl: TreeView<PathBuf> = window.get_dialog_item(IDC_MAIN_TREE).unwrap().into();
+            match info.notification() {
+                Notification::TreeView(TreeViewNotification::SelectionChanged(change)) => {
+                    let path = change
+                        .new_item()
+                        .and_then(|node| tree_ctl.item_data(node).ok().flatten());
+                    if let Some(path) = path {
+                        window.set_text(&path.display().to_string());
+                    }
+                }
+                Notification::TreeView(TreeViewNotification::BeginLabelEdit(label)) => {
+                    // The folder at the root can't be renamed.
+                    let is_root = label.item().map(|node| tree_ctl.parent(node).is_none());
+                    if is_root != Some(false) {
+                        return Some(1);
+                    }
+                }
+                Notification::TreeView(TreeViewNotification::EndLabelEdit(label)) => {
+                    return Some(match (label.text(), label.item()) {
+                        (Some(name), Some(node)) if !name.is_empty() => {
+                            rename(&tree_ctl, node, &name)
+                        }
+                        _ => 0,
+                    });
+                }
+                _ => return None,
+            }
+        }
+        Message::Close => {
+            window.destroy().unwrap();
+        }
+        Message::Destroy => {
+            win::post_quit_message(0);
+        }
+        _ => return None,
+    }
+
+    Some(0)
+}
+
+// Adds the folders inside `path` as children of `parent`, sorted by name.
+fn add_folders(
+    tree_ctl: &TreeView<PathBuf>,
+    parent: Option<Node>,
+    path: PathBuf,
+) -> win::Result<()> {
+    // Folders that can't be read are left empty.
+    let mut folders = match fs::read_dir(&path) {
+        Ok(entries) => entries
+            .flatten()
+            .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        Err(_) => Vec::new(),
+    };
+    folders.sort_by_key(|name| name.to_lowercase());
+
+    for name in folders {
+        let node = tree_ctl.add_with_data(parent, &name, path.join(&name))?;
+        // Whether it has children is only known once it's expanded.
+        tree_ctl.set_has_children(node, true)?;
+    }
+    Ok(())
+}
+
+// Renames the folder of the node, returning whether the new name should be accepted.
+fn rename(tree_ctl: &TreeView<PathBuf>, node: Node, name: &str) -> isize {
+    let path = match tree_ctl.item_data(node) {
+        Ok(Some(path)) => path,
+        _ => return 0,
+    };
+    let new_path = path.with_file_name(name);
+    match fs::rename(&path, &new_path) {
+        Ok(()) => {
+            tree_ctl.set_item_data(node, new_path).unwrap();
+            // The children hold paths inside the old folder, so they're read again.
+            for child in tree_ctl.children(Some(node)) {
+                tree_ctl.delete(child).unwrap();
+            }
+            tree_ctl.set_expanded(node, false);
+            tree_ctl.set_has_children(node, true).unwrap();
+            1
+        }
+        Err(e) => {
+            win::messagebox::message_box(
+                "Error",
+                &format!("Could not rename the folder: {}", e),
+                &[],
+            )
+            .unwrap();
+            0
+        }
+    }
+}
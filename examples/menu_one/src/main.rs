@@ -6,11 +6,7 @@ use minimal_windows_gui as win;
 
 const CLASS_NAME: &str = "myWindowClass";
 
-const IDR_MYMENU: u16 = 101;
-const IDI_MYICON: u16 = 102;
-
-const ID_FILE_EXIT: u16 = 9001;
-const ID_STUFF_GO: u16 = 9002;
+include!(concat!(env!("OUT_DIR"), "/resource.rs"));
 
 fn main() -> win::Result<()> {
     let class = &win::class::build()
@@ -0,0 +1,167 @@
+//! A trackbar and an up-down control that both set the position of a progress bar.
+//! The progress bar turns red past 90, and a second progress bar shows a marquee animation.
+use std::process::exit;
+use minimal_windows_gui as win;
+use win::control::{edit, progress_bar, trackbar, up_down, Edit, ProgressBar, Trackbar, UpDown};
+
+const CLASS_NAME: &str = "myWindowClass";
+
+const IDC_PROGRESS: u16 = 101;
+const IDC_MARQUEE: u16 = 102;
+const IDC_TRACKBAR: u16 = 103;
+const IDC_EDIT: u16 = 104;
+const IDC_UP_DOWN: u16 = 105;
+
+fn main() -> win::Result<()> {
+    win::init_common_controls();
+
+    let class = &win::class::build()
+        .load_icon(win::icon::Icon::Application)?
+        .load_cursor(win::cursor::Cursor::Arrow)?
+        .background(win::class::Background::Window)
+        .load_small_icon(win::icon::Icon::Application)?
+        .register(CLASS_NAME)
+        .expect("window registration failed");
+
+    let window = win::window::build()
+        .set_message_callback(main_window_callback)
+        .add_style(win::window::Style::OverlappedWindow)
+        .size(360, 220)
+        .create(class, "Progress Controls")
+        .expect("window creation failed");
+
+    window.show_default();
+    window.update().unwrap();
+
+    exit(win::message_loop())
+}
+
+fn main_window_callback(
+    window: &win::window::Window,
+    message: win::message::Message,
+) -> Option<isize> {
+    use win::message::Message;
+
+    match message {
+        Message::Create => {
+            ProgressBar::create(
+                win::window::build()
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(progress_bar::Style::SmoothReverse)
+                    .pos(10, 10)
+                    .size(320, 20)
+                    .parent(window)
+                    .set_child_id(IDC_PROGRESS),
+            )
+            .expect("progress bar creation failed");
+
+            let marquee = ProgressBar::create(
+                win::window::build()
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(progress_bar::Style::Marquee)
+                    .pos(10, 40)
+                    .size(320, 20)
+                    .parent(window)
+                    .set_child_id(IDC_MARQUEE),
+            )
+            .expect("progress bar creation failed");
+            marquee.start_marquee(None);
+
+            let trackbar = Trackbar::create(
+                win::window::build()
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(trackbar::Style::AutoTicks)
+                    .pos(10, 70)
+                    .size(320, 40)
+                    .parent(window)
+                    .set_child_id(IDC_TRACKBAR),
+            )
+            .expect("trackbar creation failed");
+            trackbar.set_tick_frequency(10);
+            trackbar.set_page_size(10);
+
+            let edit = Edit::create(
+                win::window::build()
+                    .add_extended_style(win::window::ExtendedStyle::ClientEdge)
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(edit::Style::Number)
+                    .pos(10, 120)
+                    .size(80, 24)
+                    .parent(window)
+                    .set_child_id(IDC_EDIT),
+                "",
+            )
+            .expect("edit creation failed");
+            edit.set_font(win::font::get_default().unwrap());
+
+            let up_down = UpDown::create(
+                win::window::build()
+                    .add_style(win::window::Style::Visible)
+                    .add_control_style(up_down::Style::SetBuddyInt)
+                    .add_control_style(up_down::Style::AlignRight)
+                    .add_control_style(up_down::Style::ArrowKeys)
+                    .pos(0, 0)
+                    .size(20, 24)
+                    .parent(window)
+                    .set_child_id(IDC_UP_DOWN),
+                &edit,
+            )
+            .expect("up-down creation failed");
+            // Holding an arrow down goes faster after a couple of seconds.
+            up_down
+                .set_acceleration(&[
+                    up_down::Acceleration {
+                        seconds: 0,
+                        increment: 1,
+                    },
+                    up_down::Acceleration {
+                        seconds: 2,
+                        increment: 5,
+                    },
+                ])
+                .unwrap();
+            up_down.set_position(0);
+        }
+        Message::HorizontalScroll(info) => {
+            let change = info.trackbar_change()?;
+            let up_down: UpDown = window.get_dialog_item(IDC_UP_DOWN).unwrap().into();
+            // Setting the position updates the text of the edit, which updates the progress.
+            up_down.set_position(change.position);
+        }
+        Message::Command(info) => {
+            let data = info.control_data()?;
+            let change = matches!(data.edit_code(), win::message::EditMessage::Change);
+            if data.id != IDC_EDIT || !change {
+                return None;
+            }
+            let up_down: UpDown = window.get_dialog_item(IDC_UP_DOWN).unwrap().into();
+            if let Ok(position) = up_down.position() {
+                set_progress(window, position);
+            }
+        }
+        Message::Close => {
+            window.destroy().unwrap();
+        }
+        Message::Destroy => {
+            win::post_quit_message(0);
+        }
+        _ => return None,
+    }
+
+    Some(0)
+}
+
+fn set_progress(window: &win::window::Window, position: i32) {
+    let progress: ProgressBar = window.get_dialog_item(IDC_PROGRESS).unwrap().into();
+    progress.set_position(position);
+    progress.set_state(if position > 90 {
+        progress_bar::State::Error
+    } else {
+        progress_bar::State::Normal
+    });
+
+    let trackbar: Trackbar = window.get_dialog_item(IDC_TRACKBAR).unwrap().into();
+    if trackbar.position() != position {
+        trackbar.set_position(position);
+    }
+}
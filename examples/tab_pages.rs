@@ -0,0 +1,158 @@
+//! A window with a tab control, whose pages are shown by a tab host as tabs are selected.
+//! Press the button on the last page to add a new page, which can be closed with its own button.
+use std::cell::{Cell, RefCell};
+use std::process::exit;
+use minimal_windows_gui as win;
+use win::control::{button, edit, tab_control, TabControl, TabHost};
+
+const CLASS_NAME: &str = "myWindowClass";
+
+const IDC_MAIN_TABS: u16 = 101;
+const IDC_NOTES: u16 = 102;
+const IDC_ADD_PAGE: u16 = 103;
+const IDC_CLOSE_PAGE: u16 = 104;
+
+struct Pages {
+    host: RefCell<Option<TabHost<'static>>>,
+    // Number of pages added with the button, to name the next one.
+    added: Cell<usize>,
+}
+
+fn main() -> win::Result<()> {
+    win::init_common_controls();
+
+    let class = &win::class::build()
+        .load_icon(win::icon::Icon::Application)?
+        .load_cursor(win::cursor::Cursor::Arrow)?
+        .background(win::class::Background::Window)
+        .load_small_icon(win::icon::Icon::Application)?
+        .register(CLASS_NAME)
+        .expect("window registration failed");
+
+    let pages = Pages {
+        host: RefCell::new(None),
+        added: Cell::new(0),
+    };
+
+    let window = win::window::build()
+        .set_message_callback(move |window, message| main_window_callback(window, message, &pages))
+        .add_style(win::window::Style::OverlappedWindow)
+        .size(480, 320)
+        .create(class, "Tab Pages")
+        .expect("window creation failed");
+
+    window.show_default();
+    window.update().unwrap();
+
+    exit(win::message_loop())
+}
+
+fn main_window_callback(
+    window: &win::window::Window,
+    message: win::message::Message,
+    pages: &Pages,
+) -> Option<isize> {
+    use win::message::Message;
+
+    match message {
+        Message::Create => {
+            let tabs = TabControl::create(
+                win::window::build()
+                    .add_style(win::window::Style::Visible)
+                    .add_style(win::window::Style::ClipSiblings)
+                    .add_control_style(tab_control::Style::HotTrack)
+                    .pos(0, 0)
+                    .size(100, 100)
+                    .parent(window)
+                    .set_child_id(IDC_MAIN_TABS),
+            )
+            .expect("tab control creation failed");
+            tabs.set_font(win::font::get_default().unwrap());
+
+            let host = TabHost::new(tabs);
+
+            let notes = win::window::build()
+                .add_extended_style(win::window::ExtendedStyle::ClientEdge)
+                .add_style(win::window::Style::VerticalScroll)
+                .add_control_style(edit::Style::Multiline)
+                .add_control_style(edit::Style::AutoVerticalScroll)
+                .pos(0, 0)
+                .size(100, 100)
+                .parent(window)
+                .set_child_id(IDC_NOTES)
+                .create(win::class::edit_control(), "Write anything here.")
+                .expect("could not create edit control");
+            notes.set_font(win::font::get_default().unwrap());
+            host.add_page("Notes", notes).unwrap();
+
+            let add = create_button(window, IDC_ADD_PAGE, "Add page");
+            host.add_page("More", add).unwrap();
+
+            *pages.host.borrow_mut() = Some(host);
+        }
+        Message::Size(_) => {
+            if let Some(host) = &*pages.host.borrow() {
+                host.tab_control()
+                    .set_rect(window.get_rect().unwrap())
+                    .unwrap();
+                host.handle_message(&message);
+            }
+        }
+        Message::Notify(_) => {
+            if let Some(host) = &*pages.host.borrow() {
+                host.handle_message(&message);
+            }
+            return None;
+        }
+        Message::Command(info) => {
+            let host = pages.host.borrow();
+            let host = host.as_ref()?;
+            match info.control_data().map(|data| data.id) {
+                Some(IDC_ADD_PAGE) => {
+                    pages.added.set(pages.added.get() + 1);
+                    let title = format!("Page {}", pages.added.get());
+                    let close = create_button(window, IDC_CLOSE_PAGE, &format!("Close {}", title));
+                    // New pages go before the last one, which has the button to add them.
+                    let index = host.tab_control().count() - 1;
+                    let index = host.insert_page(index, &title, close).unwrap();
+                    host.select(index).unwrap();
+                }
+                Some(IDC_CLOSE_PAGE) => {
+                    if let Some(index) = host.tab_control().selected() {
+                        host.remove_page(index).unwrap();
+                    }
+                }
+                _ => return None,
+            }
+        }
+        Message::Close => {
+            // Destroys the pages along with the window.
+            pages.host.borrow_mut().take();
+            window.destroy().unwrap();
+        }
+        Message::Destroy => {
+            win::post_quit_message(0);
+        }
+        _ => return None,
+    }
+
+    Some(0)
+}
+
+// Pages fill the whole display area of the tab control, so the button is as big as the page.
+fn create_button(
+    window: &win::window::Window,
+    id: u16,
+    text: &str,
+) -> win::window::Window<'static> {
+    let button = win::window::build()
+        .add_control_style(button::Kind::Push)
+        .pos(0, 0)
+        .size(100, 30)
+        .parent(window)
+        .set_child_id(id)
+        .create(win::class::button(), text)
+        .expect("could not create button");
+    button.set_font(win::font::get_default().unwrap());
+    button
+}
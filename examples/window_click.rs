@@ -39,7 +39,7 @@ fn main_window_callback(
             let file_name = win::module_file_name().unwrap();
             win::messagebox::message_box(
                 "This program is:",
-                &file_name.into_string().unwrap(),
+                &file_name.display().to_string(),
                 &[win::messagebox::Config::IconInformation],
             )
             .unwrap();
@@ -0,0 +1,96 @@
+//! Keyboard accelerators https://docs.microsoft.com/en-us/windows/win32/menurc/keyboard-accelerators.
+//!
+//! An accelerator table maps key combinations to command identifiers. When the message loop
+//! translates a keystroke through the table, the window receives a `Command` message with the
+//! identifier as its `accelerator_id`.
+use crate::{base_instance, non_null_or_err, Error, Result};
+use std::ptr::NonNull;
+use std::str::FromStr;
+use winapi::shared::windef::{HACCEL, HACCEL__};
+use winapi::um::winuser::{
+    CreateAcceleratorTableW, DestroyAcceleratorTable, LoadAcceleratorsW, ACCEL, MAKEINTRESOURCEW,
+};
+
+pub use crate::key::Chord;
+
+pub struct Table {
+    accel: NonNull<HACCEL__>,
+    // Tables loaded from resources are destroyed by the system when the application closes.
+    owned: bool,
+}
+
+impl Table {
+    pub(crate) fn as_ptr(&self) -> HACCEL {
+        self.accel.as_ptr()
+    }
+
+    /// Creates an accelerator table from pairs of key combination and command identifier.
+    pub fn new(accelerators: &[(Chord, u16)]) -> Result<Self> {
+        let mut accelerators = accelerators
+            .iter()
+            .map(|(chord, id)| chord.to_accel(*id))
+            .collect::<Vec<_>>();
+
+        let result = unsafe {
+            CreateAcceleratorTableW(accelerators.as_mut_ptr(), accelerators.len() as i32)
+        };
+        non_null_or_err(result).map(|accel| Table { accel, owned: true })
+    }
+
+    /// Creates an accelerator table from pairs of key combination and command identifier, where
+    /// the combinations are parsed from strings such as `"Ctrl+Shift+S"` (see `Chord`).
+    pub fn from_chords(accelerators: &[(&str, u16)]) -> Result<Self> {
+        let accelerators = accelerators
+            .iter()
+            .map(|(chord, id)| chord.parse().map(|chord| (chord, *id)))
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::new(&accelerators)
+    }
+
+    /// Loads the specified accelerator table from the resource file `.rc`.
+    pub fn load(id: u16) -> Result<Self> {
+        let result = unsafe { LoadAcceleratorsW(base_instance(), MAKEINTRESOURCEW(id)) };
+        non_null_or_err(result).map(|accel| Table {
+            accel,
+            owned: false,
+        })
+    }
+}
+
+impl Drop for Table {
+    fn drop(&mut self) {
+        if !self.owned {
+            return;
+        }
+
+        let result = unsafe { DestroyAcceleratorTable(self.accel.as_ptr()) };
+        debug_assert!(result != 0, "failed to destroy accelerator table");
+    }
+}
+
+impl Chord {
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-accel
+    pub(crate) fn to_accel(self, id: u16) -> ACCEL {
+        ACCEL {
+            fVirt: self.accel_flags(),
+            key: self.key.code() as u16,
+            cmd: id,
+        }
+    }
+}
+
+/// Parses a key combination made of any number of modifiers (`Ctrl`, `Shift` or `Alt`) and a key,
+/// separated by `+`. Names are case-insensitive.
+///
+/// Keys can be letters, digits, punctuation (such as `,` or `+`), function keys (`F1` to `F24`),
+/// numeric keypad digits (`Num0` to `Num9`) or the name of a special key, such as `Enter`,
+/// `Esc`, `Tab`, `Space`, `Backspace`, `Insert`, `Delete`, `Home`, `End`, `PageUp`, `PageDown`
+/// or the arrow keys (`Left`, `Up`, `Right` and `Down`).
+impl FromStr for Chord {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Chord::parse(s).ok_or_else(|| Error::InvalidChord(s.to_owned()))
+    }
+}
@@ -1,23 +1,26 @@
 //! Window classes https://docs.microsoft.com/en-us/windows/win32/winmsg/about-window-classes.
 //! Additionally contains methods to reference system classes.
-use crate::{base_instance, cursor, icon, message, window, Error, Result};
-use std::ffi::CString;
+use crate::{
+    ascii_to_wide, base_instance, control, cursor, icon, message, to_wide, window, Error,
+    MessageCallback, Result,
+};
 use std::num::NonZeroU16;
 use std::ptr::{self, NonNull};
+use widestring::U16CString;
 use winapi::shared::minwindef::{LPARAM, LRESULT, UINT, WPARAM};
 use winapi::shared::windef::{HBRUSH, HCURSOR, HICON, HWND};
-use winapi::um::winnt::{LPCSTR, LPSTR};
+use winapi::um::winnt::{LPCWSTR, LPWSTR};
 use winapi::um::winuser::{
-    DefWindowProcA, RegisterClassExA, UnregisterClassA, COLOR_3DDKSHADOW, COLOR_3DFACE,
+    DefWindowProcW, RegisterClassExW, UnregisterClassW, COLOR_3DDKSHADOW, COLOR_3DFACE,
     COLOR_3DHIGHLIGHT, COLOR_3DLIGHT, COLOR_3DSHADOW, COLOR_ACTIVEBORDER, COLOR_ACTIVECAPTION,
     COLOR_APPWORKSPACE, COLOR_BTNTEXT, COLOR_CAPTIONTEXT, COLOR_DESKTOP,
     COLOR_GRADIENTACTIVECAPTION, COLOR_GRADIENTINACTIVECAPTION, COLOR_GRAYTEXT, COLOR_HIGHLIGHT,
     COLOR_HIGHLIGHTTEXT, COLOR_HOTLIGHT, COLOR_INACTIVEBORDER, COLOR_INACTIVECAPTION,
     COLOR_INACTIVECAPTIONTEXT, COLOR_INFOBK, COLOR_INFOTEXT, COLOR_MENU, COLOR_MENUBAR,
     COLOR_MENUHILIGHT, COLOR_MENUTEXT, COLOR_SCROLLBAR, COLOR_WINDOW, COLOR_WINDOWFRAME,
-    COLOR_WINDOWTEXT, CS_BYTEALIGNCLIENT, CS_BYTEALIGNWINDOW, CS_CLASSDC, CS_DBLCLKS,
-    CS_DROPSHADOW, CS_GLOBALCLASS, CS_HREDRAW, CS_NOCLOSE, CS_OWNDC, CS_PARENTDC, CS_SAVEBITS,
-    CS_VREDRAW, MAKEINTRESOURCEA, WNDCLASSEXA,
+    COLOR_WINDOWTEXT, CREATESTRUCTW, CS_BYTEALIGNCLIENT, CS_BYTEALIGNWINDOW, CS_CLASSDC,
+    CS_DBLCLKS, CS_DROPSHADOW, CS_GLOBALCLASS, CS_HREDRAW, CS_NOCLOSE, CS_OWNDC, CS_PARENTDC,
+    CS_SAVEBITS, CS_VREDRAW, MAKEINTRESOURCEW, WM_NCCREATE, WM_NCDESTROY, WNDCLASSEXW,
 };
 
 /// Class styles as defined in https://docs.microsoft.com/en-us/windows/win32/winmsg/window-class-styles.
@@ -172,51 +175,69 @@ pub struct Builder {
     icon: HICON,
     cursor: HCURSOR,
     background: HBRUSH,
-    menu: LPSTR,
+    menu: LPWSTR,
     icon_small: HICON,
 }
 
 pub enum Class {
     Owned {
-        class_name: CString,
+        class_name: U16CString,
         atom: NonZeroU16,
     },
     Static {
-        class_name: &'static [u8],
+        class_name: &'static [u16],
     },
 }
 
 static BUTTON: Class = Class::Static {
-    class_name: b"Button\0",
+    class_name: &ascii_to_wide(b"Button\0"),
 };
 static COMBO_BOX: Class = Class::Static {
-    class_name: b"ComboBox\0",
+    class_name: &ascii_to_wide(b"ComboBox\0"),
 };
 static EDIT_CONTROL: Class = Class::Static {
-    class_name: b"Edit\0",
+    class_name: &ascii_to_wide(b"Edit\0"),
 };
 static LIST_BOX: Class = Class::Static {
-    class_name: b"ListBox\0",
+    class_name: &ascii_to_wide(b"ListBox\0"),
 };
 static MDI_CLIENT: Class = Class::Static {
-    class_name: b"MDIClient\0",
+    class_name: &ascii_to_wide(b"MDIClient\0"),
 };
 static SCROLL_BAR: Class = Class::Static {
-    class_name: b"ScrollBar\0",
+    class_name: &ascii_to_wide(b"ScrollBar\0"),
 };
 static STATIC: Class = Class::Static {
-    class_name: b"Static\0",
+    class_name: &ascii_to_wide(b"Static\0"),
 };
 
 // um/CommCtrl.h
 static TOOLBAR: Class = Class::Static {
-    class_name: b"ToolbarWindow32\0",
+    class_name: &ascii_to_wide(b"ToolbarWindow32\0"),
 };
 static RE_BAR: Class = Class::Static {
-    class_name: b"ReBarWindow32\0",
+    class_name: &ascii_to_wide(b"ReBarWindow32\0"),
 };
 static STATUS: Class = Class::Static {
-    class_name: b"msctls_statusbar32\0",
+    class_name: &ascii_to_wide(b"msctls_statusbar32\0"),
+};
+static LIST_VIEW: Class = Class::Static {
+    class_name: &ascii_to_wide(b"SysListView32\0"),
+};
+static TREE_VIEW: Class = Class::Static {
+    class_name: &ascii_to_wide(b"SysTreeView32\0"),
+};
+static TAB_CONTROL: Class = Class::Static {
+    class_name: &ascii_to_wide(b"SysTabControl32\0"),
+};
+static PROGRESS_BAR: Class = Class::Static {
+    class_name: &ascii_to_wide(b"msctls_progress32\0"),
+};
+static TRACKBAR: Class = Class::Static {
+    class_name: &ascii_to_wide(b"msctls_trackbar32\0"),
+};
+static UP_DOWN: Class = Class::Static {
+    class_name: &ascii_to_wide(b"msctls_updown32\0"),
 };
 
 pub unsafe extern "system" fn wnd_proc_wrapper(
@@ -225,22 +246,38 @@ pub unsafe extern "system" fn wnd_proc_wrapper(
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
-    if let Some(hwnd) = NonNull::new(handle) {
-        let lock = crate::HWND_TO_CALLBACK.lock().unwrap();
+    if msg == WM_NCCREATE {
+        // `window::Builder::create` passes the callback as the creation data.
+        let create = &*(lparam as *const CREATESTRUCTW);
+        if !create.lpCreateParams.is_null() {
+            crate::attach_callback(handle, create.lpCreateParams as *const MessageCallback);
+        }
+    }
 
-        if let Some(&callback) = lock.get(&(handle as usize)).or_else(|| lock.get(&0)) {
+    // Requests of virtual list views and lazy tree views are answered without involving the
+    // callback.
+    if let Some(result) = control::intercept_parent_message(msg, wparam, lparam) {
+        return result;
+    }
+
+    if let Some(hwnd) = NonNull::new(handle) {
+        if let Some(callback) = crate::window_callback::<MessageCallback>(handle) {
             let window = window::Window::Borrowed { hwnd };
             let message = message::Message::from_raw(msg, wparam, lparam);
+            let result = callback(&window, message);
+
+            // No more messages will be sent to the callback, de-register it.
+            if msg == WM_NCDESTROY {
+                crate::detach_callback::<MessageCallback>(handle);
+            }
 
-            // Callback may cause additional messages, leading to a deadlock unless dropped.
-            drop(lock);
-            if let Some(result) = callback(&window, message) {
+            if let Some(result) = result {
                 return result;
             }
         }
     }
 
-    DefWindowProcA(handle, msg, wparam, lparam)
+    DefWindowProcW(handle, msg, wparam, lparam)
 }
 
 impl Builder {
@@ -275,7 +312,7 @@ impl Builder {
     /// Sets the menu resource constant to use. This should be the same value as the one used
     /// in the resource file `.rc`.
     pub fn menu(mut self, menu: u16) -> Self {
-        self.menu = MAKEINTRESOURCEA(menu);
+        self.menu = MAKEINTRESOURCEW(menu);
         self
     }
 
@@ -288,16 +325,16 @@ impl Builder {
 
     /// Registers a new window class.
     pub fn register(self, name: &str) -> Result<Class> {
-        let class_name = CString::new(name)?;
+        let class_name = to_wide(name)?;
 
         let atom = unsafe {
-            // CreateWindowExA without a class will fail with 0x57f (ERROR_CANNOT_FIND_WND_CLASS).
+            // CreateWindowExW without a class will fail with 0x57f (ERROR_CANNOT_FIND_WND_CLASS).
             // https://docs.microsoft.com/en-us/windows/win32/debug/system-error-codes--1300-1699-
             //
             // For the method itself see:
-            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerclassexa
-            RegisterClassExA(&WNDCLASSEXA {
-                cbSize: std::mem::size_of::<WNDCLASSEXA>() as u32,
+            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerclassexw
+            RegisterClassExW(&WNDCLASSEXW {
+                cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
                 style: self.style,
                 lpfnWndProc: Some(wnd_proc_wrapper),
                 cbClsExtra: 0,
@@ -307,7 +344,7 @@ impl Builder {
                 hCursor: self.cursor,
                 hbrBackground: self.background,
                 lpszMenuName: self.menu,
-                lpszClassName: class_name.as_ptr() as LPCSTR,
+                lpszClassName: class_name.as_ptr(),
                 hIconSm: self.icon_small,
             })
         };
@@ -321,13 +358,13 @@ impl Builder {
 }
 
 impl Class {
-    pub(crate) fn class_name_ptr(&self) -> LPCSTR {
+    pub(crate) fn class_name_ptr(&self) -> LPCWSTR {
         match self {
             Class::Owned { atom, .. } => {
                 // The atom must be in the low-order word of lpClassName; the high-order word must be zero.
-                atom.get() as usize as LPCSTR
+                atom.get() as usize as LPCWSTR
             }
-            Class::Static { class_name } => class_name.as_ptr() as LPCSTR,
+            Class::Static { class_name } => class_name.as_ptr(),
         }
     }
 }
@@ -337,14 +374,13 @@ impl Drop for Class {
         match self {
             Class::Owned { .. } => {
                 let result =
-                    unsafe { UnregisterClassA(self.class_name_ptr(), std::ptr::null_mut()) };
-
-                if result == 0 {
-                    panic!(
-                        "class deleted by other means or some window still alive: {}",
-                        Error::last_os_error()
-                    )
-                }
+                    unsafe { UnregisterClassW(self.class_name_ptr(), std::ptr::null_mut()) };
+
+                debug_assert!(
+                    result != 0,
+                    "class deleted by other means or some window still alive: {}",
+                    Error::last_os_error()
+                );
             }
             Class::Static { .. } => {}
         }
@@ -412,3 +448,33 @@ pub fn re_bar() -> &'static Class {
 pub fn status_bar() -> &'static Class {
     &STATUS
 }
+
+/// The common control class for a list view.
+pub fn list_view() -> &'static Class {
+    &LIST_VIEW
+}
+
+/// The common control class for a tree view.
+pub fn tree_view() -> &'static Class {
+    &TREE_VIEW
+}
+
+/// The common control class for a tab control.
+pub fn tab_control() -> &'static Class {
+    &TAB_CONTROL
+}
+
+/// The common control class for a progress bar.
+pub fn progress_bar() -> &'static Class {
+    &PROGRESS_BAR
+}
+
+/// The common control class for a trackbar.
+pub fn trackbar() -> &'static Class {
+    &TRACKBAR
+}
+
+/// The common control class for an up-down control.
+pub fn up_down() -> &'static Class {
+    &UP_DOWN
+}
@@ -0,0 +1,331 @@
+//! Buttons https://docs.microsoft.com/en-us/windows/win32/controls/buttons.
+//!
+//! This includes push buttons, check boxes, radio buttons, group boxes, split buttons and command
+//! links, which are all the same class of control with a different `Kind`.
+use super::ControlStyle;
+use crate::error::ControlError;
+use crate::window::{self, Window};
+use crate::{class, gdi, icon, ok_or_last_err, to_wide, Error, Result};
+use std::ops::Deref;
+use winapi::shared::minwindef::{DWORD, UINT};
+use winapi::um::commctrl::{
+    BCM_SETNOTE, BS_COMMANDLINK, BS_DEFCOMMANDLINK, BS_DEFSPLITBUTTON, BS_SPLITBUTTON,
+};
+use winapi::um::winuser::{
+    CheckRadioButton, IsDlgButtonChecked, SendMessageW, BM_CLICK, BM_GETCHECK, BM_SETCHECK,
+    BM_SETIMAGE, BST_CHECKED, BST_INDETERMINATE, BST_UNCHECKED, BS_3STATE, BS_AUTO3STATE,
+    BS_AUTOCHECKBOX, BS_AUTORADIOBUTTON, BS_BITMAP, BS_BOTTOM, BS_CENTER, BS_CHECKBOX,
+    BS_DEFPUSHBUTTON, BS_FLAT, BS_GROUPBOX, BS_ICON, BS_LEFT, BS_LEFTTEXT, BS_MULTILINE, BS_NOTIFY,
+    BS_PUSHBUTTON, BS_PUSHLIKE, BS_RADIOBUTTON, BS_RIGHT, BS_TOP, BS_VCENTER, IMAGE_BITMAP,
+    IMAGE_ICON,
+};
+
+/// The kind of button, as defined in https://docs.microsoft.com/en-us/windows/win32/controls/button-styles.
+/// A button can only be of one kind.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A push button that posts a `Command` message to the owner window when the user selects
+    /// the button.
+    Push = BS_PUSHBUTTON,
+
+    /// A push button that behaves like a `Push` button, but has a distinct appearance. If the
+    /// button is in a dialog box, the user can select it by pressing the ENTER key.
+    DefaultPush = BS_DEFPUSHBUTTON,
+
+    /// A check box, whose check state the application must change itself.
+    CheckBox = BS_CHECKBOX,
+
+    /// A check box whose check state switches each time the user selects it.
+    AutoCheckBox = BS_AUTOCHECKBOX,
+
+    /// A check box that can be grayed (indeterminate) as well as checked or cleared, whose check
+    /// state the application must change itself.
+    ThreeState = BS_3STATE,
+
+    /// A three-state check box whose state cycles through checked, indeterminate, and cleared
+    /// each time the user selects it.
+    AutoThreeState = BS_AUTO3STATE,
+
+    /// A radio button, whose check state the application must change itself (for example, with
+    /// `check_radio_button`).
+    RadioButton = BS_RADIOBUTTON,
+
+    /// A radio button that is checked when the user selects it, clearing all other radio buttons
+    /// in the same group.
+    AutoRadioButton = BS_AUTORADIOBUTTON,
+
+    /// A rectangle in which other controls can be grouped, with its text in the upper left
+    /// corner.
+    GroupBox = BS_GROUPBOX,
+
+    /// A push button with a drop-down arrow, which sends a `SplitButtonDropDown` notification
+    /// when clicked.
+    Split = BS_SPLITBUTTON,
+
+    /// A split button that behaves like a `DefaultPush` button.
+    DefaultSplit = BS_DEFSPLITBUTTON,
+
+    /// A command link button, which shows an arrow icon, its text and a note below.
+    CommandLink = BS_COMMANDLINK,
+
+    /// A command link button that behaves like a `DefaultPush` button.
+    DefaultCommandLink = BS_DEFCOMMANDLINK,
+}
+
+/// Additional button styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/button-styles.
+#[repr(u32)]
+pub enum Style {
+    /// The button displays a bitmap instead of text.
+    Bitmap = BS_BITMAP,
+
+    /// Places text at the bottom of the button rectangle.
+    Bottom = BS_BOTTOM,
+
+    /// Centers text horizontally in the button rectangle.
+    Center = BS_CENTER,
+
+    /// The button is two-dimensional; it does not use the default shading to create a 3-D image.
+    Flat = BS_FLAT,
+
+    /// The button displays an icon instead of text.
+    Icon = BS_ICON,
+
+    /// Left-justifies the text in the button rectangle.
+    Left = BS_LEFT,
+
+    /// Places the text on the left side of a radio button or check box.
+    LeftText = BS_LEFTTEXT,
+
+    /// Wraps the button text to multiple lines if the text string is too long to fit on a single
+    /// line in the button rectangle.
+    Multiline = BS_MULTILINE,
+
+    /// Enables the button to send `SetFocus` and `KillFocus` notifications, and `DoubleClicked`
+    /// for push buttons.
+    Notify = BS_NOTIFY,
+
+    /// Makes a check box or radio button look and act like a push button.
+    PushLike = BS_PUSHLIKE,
+
+    /// Right-justifies text in the button rectangle.
+    Right = BS_RIGHT,
+
+    /// Places text at the top of the button rectangle.
+    Top = BS_TOP,
+
+    /// Places text in the middle (vertically) of the button rectangle.
+    VerticalCenter = BS_VCENTER,
+}
+
+/// The check state of a check box or radio button.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    /// Only three-state check boxes can be in this state.
+    Indeterminate,
+}
+
+/// An image displayed by a button. The button does not take ownership of the image, so it must
+/// be kept alive for as long as the button uses it.
+pub enum Image<'a> {
+    Bitmap(&'a gdi::Bitmap),
+    Icon(&'a icon::Icon),
+}
+
+pub struct Builder<'a> {
+    kind: Kind,
+    style: DWORD,
+    first_in_group: bool,
+    note: Option<&'a str>,
+    image: Option<Image<'a>>,
+}
+
+#[derive(Debug)]
+pub struct Button<'a> {
+    window: Window<'a>,
+}
+
+// Used by the builder to add all the styles at once.
+struct Bits(DWORD);
+
+impl ControlStyle for Kind {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl ControlStyle for Bits {
+    fn bits(self) -> DWORD {
+        self.0
+    }
+}
+
+impl<'a> Builder<'a> {
+    /// Adds a new button style.
+    pub fn add_style(mut self, style: Style) -> Self {
+        self.style |= style as DWORD;
+        self
+    }
+
+    /// Makes the button the first of a group of radio buttons. Automatic radio buttons only
+    /// clear the other buttons in their group, which extends up to the next button that is the
+    /// first of a group.
+    pub fn first_in_group(mut self) -> Self {
+        self.first_in_group = true;
+        self
+    }
+
+    /// The note displayed below the text of a command link. Other kinds of buttons fail to be
+    /// created if they have a note.
+    pub fn note(mut self, note: &'a str) -> Self {
+        self.note = Some(note);
+        self
+    }
+
+    /// The image displayed by the button. Unless the button has the `Bitmap` or `Icon` style, the
+    /// image is displayed along with the text (this requires version 6 of the common controls).
+    pub fn image(mut self, image: Image<'a>) -> Self {
+        self.image = Some(image);
+        self
+    }
+
+    /// Creates the button with the given text. The builder should at least set the parent window
+    /// and the child identifier of the control.
+    pub fn create(self, builder: window::Builder, text: &str) -> Result<Button<'static>> {
+        let mut builder = builder.add_control_style(Bits(self.kind as DWORD | self.style));
+        if self.first_in_group {
+            builder = builder.add_style(window::Style::Group);
+        }
+
+        let button = Button {
+            window: builder.create(class::button(), text)?,
+        };
+        if let Some(note) = self.note {
+            button.set_note(note)?;
+        }
+        if let Some(image) = self.image {
+            button.set_image(image)?;
+        }
+        Ok(button)
+    }
+}
+
+impl Button<'_> {
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the check state of a check box or radio button. Other kinds of buttons are always
+    /// unchecked.
+    pub fn check_state(&self) -> CheckState {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/bm-getcheck
+        match self.send(BM_GETCHECK, 0, 0) as usize {
+            BST_CHECKED => CheckState::Checked,
+            BST_INDETERMINATE => CheckState::Indeterminate,
+            _ => CheckState::Unchecked,
+        }
+    }
+
+    /// Sets the check state of a check box or radio button. Setting a radio button doesn't
+    /// clear the other buttons in its group.
+    pub fn set_check_state(&self, state: CheckState) {
+        let state = match state {
+            CheckState::Unchecked => BST_UNCHECKED,
+            CheckState::Checked => BST_CHECKED,
+            CheckState::Indeterminate => BST_INDETERMINATE,
+        };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/bm-setcheck
+        self.send(BM_SETCHECK, state, 0);
+    }
+
+    /// Whether the check box or radio button is checked.
+    pub fn is_checked(&self) -> bool {
+        self.check_state() == CheckState::Checked
+    }
+
+    /// Checks or clears the check box or radio button.
+    pub fn set_checked(&self, checked: bool) {
+        self.set_check_state(if checked {
+            CheckState::Checked
+        } else {
+            CheckState::Unchecked
+        });
+    }
+
+    /// Simulates the user clicking the button.
+    pub fn click(&self) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/bm-click
+        self.send(BM_CLICK, 0, 0);
+    }
+
+    /// Sets the image displayed by the button. The button does not take ownership of the image.
+    /// Icons are loaded with the standard size, and those from resources or files stay loaded.
+    pub fn set_image(&self, image: Image) -> Result<()> {
+        let (kind, handle) = match image {
+            Image::Bitmap(bitmap) => (IMAGE_BITMAP, bitmap.bitmap.as_ptr() as isize),
+            Image::Icon(icon) => (IMAGE_ICON, icon.load_large()?.as_ptr() as isize),
+        };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/bm-setimage
+        self.send(BM_SETIMAGE, kind as usize, handle);
+        Ok(())
+    }
+
+    /// Sets the note displayed below the text of a command link. Fails for other kinds of
+    /// buttons.
+    pub fn set_note(&self, note: &str) -> Result<()> {
+        let note = to_wide(note)?;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/bcm-setnote
+        match self.send(BCM_SETNOTE, 0, note.as_ptr() as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Creates a builder for a button of the given kind.
+pub fn build<'a>(kind: Kind) -> Builder<'a> {
+    Builder {
+        kind,
+        style: 0,
+        first_in_group: false,
+        note: None,
+        image: None,
+    }
+}
+
+/// Checks the radio button with the identifier `checked` and clears all others with an
+/// identifier from `first` to `last`, both included.
+pub fn check_radio_button(parent: &Window, first: u16, last: u16, checked: u16) -> Result<()> {
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-checkradiobutton
+    let result =
+        unsafe { CheckRadioButton(parent.hwnd_ptr(), first as i32, last as i32, checked as i32) };
+    ok_or_last_err(result)
+}
+
+/// Finds which radio button with an identifier from `first` to `last` (both included) is checked.
+pub fn checked_radio_button(parent: &Window, first: u16, last: u16) -> Option<u16> {
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-isdlgbuttonchecked
+    (first..=last).find(|&id| unsafe { IsDlgButtonChecked(parent.hwnd_ptr(), id as i32) } != 0)
+}
+
+impl<'a> From<Window<'a>> for Button<'a> {
+    fn from(window: Window<'a>) -> Self {
+        Button { window }
+    }
+}
+
+impl<'a> Deref for Button<'a> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
@@ -0,0 +1,296 @@
+//! Combo boxes https://docs.microsoft.com/en-us/windows/win32/controls/combo-boxes.
+use super::{free_item_data, item_value, store_item_data, ControlStyle};
+use crate::error::{control_result, ControlError};
+use crate::window::{self, Window};
+use crate::{class, from_wide, to_wide, Error, Result};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use winapi::shared::minwindef::{DWORD, FALSE, TRUE, UINT};
+use winapi::um::commctrl::CB_SETCUEBANNER;
+use winapi::um::winuser::{
+    SendMessageW, CBS_AUTOHSCROLL, CBS_DISABLENOSCROLL, CBS_DROPDOWN, CBS_DROPDOWNLIST,
+    CBS_LOWERCASE, CBS_NOINTEGRALHEIGHT, CBS_OEMCONVERT, CBS_SIMPLE, CBS_SORT, CBS_UPPERCASE,
+    CB_ADDSTRING, CB_DELETESTRING, CB_ERR, CB_FINDSTRING, CB_FINDSTRINGEXACT, CB_GETCOUNT,
+    CB_GETCURSEL, CB_GETDROPPEDSTATE, CB_GETITEMDATA, CB_GETLBTEXT, CB_GETLBTEXTLEN,
+    CB_INSERTSTRING, CB_LIMITTEXT, CB_RESETCONTENT, CB_SETCURSEL, CB_SETITEMDATA, CB_SHOWDROPDOWN,
+};
+
+/// Combo box styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/combo-box-styles.
+///
+/// A combo box should have exactly one of `Simple`, `DropDown` or `DropDownList`.
+#[repr(u32)]
+pub enum Style {
+    /// Displays the list box at all times. The current selection in the list box is displayed in
+    /// the edit control.
+    Simple = CBS_SIMPLE,
+
+    /// Similar to `Simple`, except that the list box is not displayed unless the user selects an
+    /// icon next to the edit control.
+    DropDown = CBS_DROPDOWN,
+
+    /// Similar to `DropDown`, except that the edit control is replaced by a static text item
+    /// that displays the current selection in the list box.
+    DropDownList = CBS_DROPDOWNLIST,
+
+    /// Automatically scrolls the text in an edit control to the right when the user types a
+    /// character at the end of the line.
+    AutoHorizontalScroll = CBS_AUTOHSCROLL,
+
+    /// Shows a disabled vertical scroll bar in the list box when the box does not contain enough
+    /// items to scroll, instead of hiding it.
+    DisableNoScroll = CBS_DISABLENOSCROLL,
+
+    /// Converts to lowercase all text in both the selection field and the list.
+    Lowercase = CBS_LOWERCASE,
+
+    /// Specifies that the size of the combo box is exactly the size specified by the application
+    /// when it created the combo box, instead of a size that doesn't show partial items.
+    NoIntegralHeight = CBS_NOINTEGRALHEIGHT,
+
+    /// Converts text entered in the combo box edit control from the Windows character set to the
+    /// OEM character set and then back to the Windows character set.
+    OemConvert = CBS_OEMCONVERT,
+
+    /// Automatically sorts strings added to the list box.
+    Sort = CBS_SORT,
+
+    /// Converts to uppercase all text in both the selection field and the list.
+    Uppercase = CBS_UPPERCASE,
+}
+
+/// A combo box whose items can hold a value of type `T`.
+///
+/// The text of the selection field (the edit control, unless it's a drop-down list) is that of
+/// the window, so it can be read and changed with `get_text` and `set_text`.
+///
+/// As with `ListBox`, the values are owned by the combo box, and dropped when their item is
+/// deleted, the combo box is cleared or the control is destroyed.
+#[derive(Debug)]
+pub struct ComboBox<'a, T = ()> {
+    window: Window<'a>,
+    _data: PhantomData<T>,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl<T: 'static> ComboBox<'_, T> {
+    /// Creates a new combo box. The builder should at least set the parent window and the child
+    /// identifier of the control. The height includes that of the drop-down list.
+    pub fn create(builder: window::Builder) -> Result<ComboBox<'static, T>> {
+        builder
+            .create(class::combo_box(), "")
+            .map(|window| ComboBox {
+                window,
+                _data: PhantomData,
+            })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the number of items in the list box of the combo box.
+    pub fn count(&self) -> Result<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-getcount
+        control_result(self.send(CB_GETCOUNT, 0, 0))
+    }
+
+    /// Adds a string to the list box of the combo box, returning its index. If the combo box
+    /// does not have the `Sort` style, the string is added to the end of the list. Otherwise,
+    /// the string is inserted into the list and the list is sorted.
+    pub fn add(&self, text: &str) -> Result<usize> {
+        let text = to_wide(text)?;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-addstring
+        control_result(self.send(CB_ADDSTRING, 0, text.as_ptr() as isize))
+    }
+
+    /// Adds a string to the combo box like `add`, and attaches the value to the new item.
+    pub fn add_with_data(&self, text: &str, data: T) -> Result<usize> {
+        let index = self.add(text)?;
+        self.set_item_data(index, data)?;
+        Ok(index)
+    }
+
+    /// Inserts a string at the given index, or at the end if it's `None`, returning its final
+    /// index. Unlike `add`, the list is not sorted even if it has the `Sort` style.
+    pub fn insert(&self, index: Option<usize>, text: &str) -> Result<usize> {
+        let text = to_wide(text)?;
+        let index = index.map_or(-1, |index| index as isize);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-insertstring
+        control_result(self.send(CB_INSERTSTRING, index as usize, text.as_ptr() as isize))
+    }
+
+    /// Deletes the item at the given index, dropping its value. Returns the number of items
+    /// that remain in the list.
+    pub fn delete(&self, index: usize) -> Result<usize> {
+        let key = self.send(CB_GETITEMDATA, index, 0);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-deletestring
+        let remaining = control_result(self.send(CB_DELETESTRING, index, 0))?;
+        free_item_data(self.window.hwnd_ptr(), key);
+        Ok(remaining)
+    }
+
+    /// Removes all items from the list box and the edit control of the combo box, dropping their
+    /// values.
+    pub fn clear(&self) {
+        for index in 0..self.count().unwrap_or(0) {
+            free_item_data(self.window.hwnd_ptr(), self.send(CB_GETITEMDATA, index, 0));
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-resetcontent
+        self.send(CB_RESETCONTENT, 0, 0);
+    }
+
+    /// Finds the first item that begins with the given text, without regard to case, returning
+    /// its index. The search starts after the item at `after` (or from the beginning if it's
+    /// `None`), and wraps around to the beginning.
+    pub fn find(&self, text: &str, after: Option<usize>) -> Result<Option<usize>> {
+        self.find_string(CB_FINDSTRING, text, after)
+    }
+
+    /// Like `find`, but the text of the item must match exactly (still without regard to case).
+    pub fn find_exact(&self, text: &str, after: Option<usize>) -> Result<Option<usize>> {
+        self.find_string(CB_FINDSTRINGEXACT, text, after)
+    }
+
+    // https://docs.microsoft.com/en-us/windows/win32/controls/cb-findstring
+    // https://docs.microsoft.com/en-us/windows/win32/controls/cb-findstringexact
+    fn find_string(&self, msg: UINT, text: &str, after: Option<usize>) -> Result<Option<usize>> {
+        let text = to_wide(text)?;
+        let after = after.map_or(-1, |index| index as isize);
+        let result = self.send(msg, after as usize, text.as_ptr() as isize);
+        Ok(match result {
+            CB_ERR => None,
+            index => Some(index as usize),
+        })
+    }
+
+    /// Gets the text of the item at the given index.
+    pub fn item_text(&self, index: usize) -> Result<String> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-getlbtextlen
+        let len = control_result(self.send(CB_GETLBTEXTLEN, index, 0))?;
+        // +1 for the NUL character
+        let mut buffer = vec![0u16; len + 1];
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-getlbtext
+        let len = control_result(self.send(CB_GETLBTEXT, index, buffer.as_mut_ptr() as isize))?;
+        buffer.truncate(len);
+        Ok(from_wide(&buffer))
+    }
+
+    /// Calls `f` with the value attached to the item at the given index, if any, and returns
+    /// its result.
+    pub fn with_item_data<R, F>(&self, index: usize, f: F) -> Result<Option<R>>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-getitemdata
+        match self.send(CB_GETITEMDATA, index, 0) {
+            CB_ERR => Err(Error::Control(ControlError::Failed)),
+            key => Ok(item_value(self.window.hwnd_ptr(), key).map(|value| f(&value))),
+        }
+    }
+
+    /// Attaches the value to the item at the given index, dropping the previous one.
+    pub fn set_item_data(&self, index: usize, data: T) -> Result<()> {
+        let old_key = self.send(CB_GETITEMDATA, index, 0);
+        if old_key == CB_ERR {
+            return Err(Error::Control(ControlError::Failed));
+        }
+
+        let key = store_item_data(self.window.hwnd_ptr(), data);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-setitemdata
+        match control_result(self.send(CB_SETITEMDATA, index, key)) {
+            Ok(_) => {
+                free_item_data(self.window.hwnd_ptr(), old_key);
+                Ok(())
+            }
+            Err(e) => {
+                free_item_data(self.window.hwnd_ptr(), key);
+                Err(e)
+            }
+        }
+    }
+
+    /// Gets the index of the selected item, if any. If the user typed text that doesn't match
+    /// any item, there is no selection.
+    pub fn selected(&self) -> Option<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-getcursel
+        match self.send(CB_GETCURSEL, 0, 0) {
+            CB_ERR => None,
+            index => Some(index as usize),
+        }
+    }
+
+    /// Selects the item at the given index and shows its text in the selection field, or clears
+    /// the selection and the field if it's `None`.
+    pub fn set_selected(&self, index: Option<usize>) -> Result<()> {
+        let index = index.map_or(-1, |index| index as isize);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-setcursel
+        match self.send(CB_SETCURSEL, index as usize, 0) {
+            // Removing the selection also "fails".
+            CB_ERR if index != -1 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Limits the number of characters the user can type into the edit control of the combo box.
+    /// A limit of 0 restores the default limit.
+    pub fn set_edit_limit(&self, limit: usize) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-limittext
+        self.send(CB_LIMITTEXT, limit, 0);
+    }
+
+    /// Whether the list box of the combo box is dropped down.
+    pub fn is_dropped_down(&self) -> bool {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-getdroppedstate
+        self.send(CB_GETDROPPEDSTATE, 0, 0) != 0
+    }
+
+    /// Shows or hides the list box of a combo box that has the `DropDown` or `DropDownList`
+    /// style.
+    pub fn show_dropdown(&self, show: bool) {
+        let show = if show { TRUE } else { FALSE };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-showdropdown
+        self.send(CB_SHOWDROPDOWN, show as usize, 0);
+    }
+
+    /// Sets the textual cue, or tip, that is displayed in the edit control of the combo box
+    /// while it's empty.
+    ///
+    /// The cue banner requires version 6 of the common controls.
+    pub fn set_cue_banner(&self, text: &str) -> Result<()> {
+        let text = to_wide(text)?;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/cb-setcuebanner
+        match self.send(CB_SETCUEBANNER, 0, text.as_ptr() as isize) {
+            1 => Ok(()),
+            _ => Err(Error::Control(ControlError::Failed)),
+        }
+    }
+}
+
+impl<T: Clone + 'static> ComboBox<'_, T> {
+    /// Gets a clone of the value attached to the item at the given index, if any.
+    pub fn item_data(&self, index: usize) -> Result<Option<T>> {
+        self.with_item_data(index, T::clone)
+    }
+}
+
+impl<'a, T> From<Window<'a>> for ComboBox<'a, T> {
+    fn from(window: Window<'a>) -> Self {
+        ComboBox {
+            window,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Deref for ComboBox<'a, T> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
@@ -0,0 +1,275 @@
+//! Edit controls https://docs.microsoft.com/en-us/windows/win32/controls/edit-controls.
+use super::ControlStyle;
+use crate::error::ControlError;
+use crate::window::{self, Window};
+use crate::{class, from_wide, to_wide, Error, Result};
+use std::ops::Deref;
+use winapi::shared::minwindef::{DWORD, FALSE, HIWORD, LOWORD, MAKELONG, TRUE, UINT};
+use winapi::um::commctrl::EM_SETCUEBANNER;
+use winapi::um::winuser::{
+    GetWindowLongPtrW, SendMessageW, EC_LEFTMARGIN, EC_RIGHTMARGIN, EC_USEFONTINFO, EM_CANUNDO,
+    EM_EMPTYUNDOBUFFER, EM_GETLIMITTEXT, EM_GETLINE, EM_GETLINECOUNT, EM_GETMARGINS, EM_GETSEL,
+    EM_LINEINDEX, EM_LINELENGTH, EM_REPLACESEL, EM_SCROLLCARET, EM_SETLIMITTEXT, EM_SETMARGINS,
+    EM_SETREADONLY, EM_SETSEL, EM_UNDO, ES_AUTOHSCROLL, ES_AUTOVSCROLL, ES_CENTER, ES_LOWERCASE,
+    ES_MULTILINE, ES_NOHIDESEL, ES_NUMBER, ES_OEMCONVERT, ES_PASSWORD, ES_READONLY, ES_RIGHT,
+    ES_UPPERCASE, ES_WANTRETURN, GWL_STYLE,
+};
+
+/// Edit control styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/edit-control-styles.
+#[repr(u32)]
+pub enum Style {
+    /// Automatically scrolls text to the right by 10 characters when the user types a character
+    /// at the end of the line.
+    AutoHorizontalScroll = ES_AUTOHSCROLL,
+
+    /// Automatically scrolls text up one page when the user presses the ENTER key on the last line.
+    AutoVerticalScroll = ES_AUTOVSCROLL,
+
+    /// Centers text in a single-line or multiline edit control.
+    Center = ES_CENTER,
+
+    /// Converts all characters to lowercase as they are typed into the edit control.
+    Lowercase = ES_LOWERCASE,
+
+    /// Designates a multiline edit control. The default is single-line edit control.
+    Multiline = ES_MULTILINE,
+
+    /// Shows the selection even when the control does not have the input focus.
+    NoHideSelection = ES_NOHIDESEL,
+
+    /// Allows only digits to be entered into the edit control. It is still possible to paste
+    /// non-digits into the edit control.
+    Number = ES_NUMBER,
+
+    /// Converts text entered in the edit control from the Windows character set to the OEM
+    /// character set and then back to the Windows character set.
+    OemConvert = ES_OEMCONVERT,
+
+    /// Displays an asterisk (*) for each character typed into the edit control. This style is
+    /// valid only for single-line edit controls.
+    Password = ES_PASSWORD,
+
+    /// Prevents the user from typing or editing text in the edit control. To change this style
+    /// after the control has been created, use `Edit::set_readonly`.
+    Readonly = ES_READONLY,
+
+    /// Right-aligns text in a single-line or multiline edit control.
+    Right = ES_RIGHT,
+
+    /// Converts all characters to uppercase as they are typed into the edit control.
+    Uppercase = ES_UPPERCASE,
+
+    /// Inserts a carriage return when the user presses the ENTER key while entering text into a
+    /// multiline edit control in a dialog box, instead of pressing the default push button.
+    WantReturn = ES_WANTRETURN,
+}
+
+#[derive(Debug)]
+pub struct Edit<'a> {
+    window: Window<'a>,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl Edit<'_> {
+    /// Creates a new edit control with the given initial text. The builder should at least set
+    /// the parent window and the child identifier of the control.
+    pub fn create(builder: window::Builder, text: &str) -> Result<Edit<'static>> {
+        builder
+            .create(class::edit_control(), text)
+            .map(|window| Edit { window })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the starting and ending character positions of the current selection. The ending
+    /// position is that of the first character after the selection.
+    pub fn selection(&self) -> (usize, usize) {
+        let mut start: DWORD = 0;
+        let mut end: DWORD = 0;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-getsel
+        self.send(
+            EM_GETSEL as UINT,
+            &mut start as *mut DWORD as usize,
+            &mut end as *mut DWORD as isize,
+        );
+        (start as usize, end as usize)
+    }
+
+    /// Selects a range of characters. If `start` is equal to `end`, the selection is removed and
+    /// the caret is placed at that position.
+    pub fn set_selection(&self, start: usize, end: usize) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-setsel
+        self.send(EM_SETSEL as UINT, start, end as isize);
+    }
+
+    /// Selects all the text in the control.
+    pub fn select_all(&self) {
+        self.send(EM_SETSEL as UINT, 0, -1);
+    }
+
+    /// Replaces the current selection with the given text, or inserts it at the caret if there
+    /// is no selection. If `can_undo` is `true`, the replacement can be undone.
+    pub fn replace_selection(&self, text: &str, can_undo: bool) -> Result<()> {
+        let text = to_wide(text)?;
+        let can_undo = if can_undo { TRUE } else { FALSE };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-replacesel
+        self.send(
+            EM_REPLACESEL as UINT,
+            can_undo as usize,
+            text.as_ptr() as isize,
+        );
+        Ok(())
+    }
+
+    /// Gets the number of lines in a multiline edit control. It's at least 1, even if the
+    /// control has no text.
+    pub fn line_count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-getlinecount
+        self.send(EM_GETLINECOUNT as UINT, 0, 0) as usize
+    }
+
+    /// Gets the text of the given line, or `None` if there is no such line. Single-line edit
+    /// controls only have the line at index 0.
+    pub fn line(&self, line: usize) -> Option<String> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-lineindex
+        let start = self.send(EM_LINEINDEX as UINT, line, 0);
+        if start < 0 {
+            return None;
+        }
+
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-linelength
+        let len = self.send(EM_LINELENGTH as UINT, start as usize, 0) as usize;
+
+        // The first word of the buffer holds its size, and the line copied into it is not
+        // null-terminated.
+        let mut buffer = vec![0u16; len.max(1)];
+        buffer[0] = len.min(u16::MAX as usize) as u16;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-getline
+        let copied = self.send(EM_GETLINE as UINT, line, buffer.as_mut_ptr() as isize);
+        buffer.truncate(copied as usize);
+        Some(from_wide(&buffer))
+    }
+
+    /// Gets the maximum number of characters the user can type into the control.
+    pub fn limit(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-getlimittext
+        self.send(EM_GETLIMITTEXT as UINT, 0, 0) as usize
+    }
+
+    /// Sets the maximum number of characters the user can type into the control. This doesn't
+    /// affect text already in the control, nor text set with `set_text`. A limit of 0 restores
+    /// the default limit.
+    pub fn set_limit(&self, limit: usize) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-setlimittext
+        self.send(EM_SETLIMITTEXT as UINT, limit, 0);
+    }
+
+    /// Whether the user can't type or edit text in the control.
+    pub fn is_readonly(&self) -> bool {
+        let style = unsafe { GetWindowLongPtrW(self.window.hwnd_ptr(), GWL_STYLE) };
+        style as DWORD & ES_READONLY != 0
+    }
+
+    /// Sets or removes the read-only style of the control.
+    pub fn set_readonly(&self, readonly: bool) -> Result<()> {
+        let readonly = if readonly { TRUE } else { FALSE };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-setreadonly
+        match self.send(EM_SETREADONLY as UINT, readonly as usize, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether there is an action in the undo queue of the control.
+    pub fn can_undo(&self) -> bool {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-canundo
+        self.send(EM_CANUNDO as UINT, 0, 0) != 0
+    }
+
+    /// Undoes the last operation in the undo queue of the control.
+    pub fn undo(&self) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-undo
+        match self.send(EM_UNDO as UINT, 0, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Clears the undo queue of the control, so that the last operation can't be undone.
+    pub fn clear_undo(&self) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-emptyundobuffer
+        self.send(EM_EMPTYUNDOBUFFER as UINT, 0, 0);
+    }
+
+    /// Scrolls the caret into view.
+    pub fn scroll_caret(&self) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-scrollcaret
+        self.send(EM_SCROLLCARET as UINT, 0, 0);
+    }
+
+    /// Gets the widths of the left and right margins, in pixels.
+    pub fn margins(&self) -> (u16, u16) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-getmargins
+        let result = self.send(EM_GETMARGINS as UINT, 0, 0) as DWORD;
+        (LOWORD(result), HIWORD(result))
+    }
+
+    /// Sets the widths of the left and right margins, in pixels.
+    pub fn set_margins(&self, left: u16, right: u16) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-setmargins
+        self.send(
+            EM_SETMARGINS as UINT,
+            (EC_LEFTMARGIN | EC_RIGHTMARGIN) as usize,
+            MAKELONG(left, right) as isize,
+        );
+    }
+
+    /// Sets the left and right margins to a narrow width calculated from the font of the control.
+    pub fn set_margins_from_font(&self) {
+        self.send(
+            EM_SETMARGINS as UINT,
+            (EC_LEFTMARGIN | EC_RIGHTMARGIN) as usize,
+            MAKELONG(EC_USEFONTINFO, EC_USEFONTINFO) as isize,
+        );
+    }
+
+    /// Sets the textual cue, or tip, that is displayed to prompt the user for information while
+    /// the control is empty. If `show_when_focused` is `false`, the cue is hidden as soon as the
+    /// control gets the focus. Fails for multiline edit controls.
+    ///
+    /// The cue banner requires version 6 of the common controls.
+    pub fn set_cue_banner(&self, text: &str, show_when_focused: bool) -> Result<()> {
+        let text = to_wide(text)?;
+        let show_when_focused = if show_when_focused { TRUE } else { FALSE };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/em-setcuebanner
+        match self.send(
+            EM_SETCUEBANNER,
+            show_when_focused as usize,
+            text.as_ptr() as isize,
+        ) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'a> From<Window<'a>> for Edit<'a> {
+    fn from(window: Window<'a>) -> Self {
+        Edit { window }
+    }
+}
+
+impl<'a> Deref for Edit<'a> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
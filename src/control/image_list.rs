@@ -0,0 +1,77 @@
+//! Image lists https://docs.microsoft.com/en-us/windows/win32/controls/image-lists.
+use crate::error::ControlError;
+use crate::{gdi, icon, Error, Result};
+use std::ptr::{self, NonNull};
+use winapi::um::commctrl::{
+    ImageList_Add, ImageList_Create, ImageList_Destroy, ImageList_GetIconSize,
+    ImageList_GetImageCount, ImageList_ReplaceIcon, ILC_COLOR32, ILC_MASK, IMAGELIST,
+};
+use winapi::um::winuser::DestroyIcon;
+
+/// A collection of images of the same size, used by controls such as list views to show an
+/// image next to each item. Images are referred to by their index in the list.
+#[derive(Debug)]
+pub struct ImageList {
+    pub(crate) list: NonNull<IMAGELIST>,
+}
+
+/// Creates a new, empty image list, whose images are all `width` by `height` pixels.
+pub fn new(width: i32, height: i32) -> Result<ImageList> {
+    // https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-imagelist_create
+    let result = unsafe { ImageList_Create(width, height, ILC_COLOR32 | ILC_MASK, 0, 4) };
+    NonNull::new(result)
+        .map(|list| ImageList { list })
+        .ok_or(Error::Control(ControlError::Failed))
+}
+
+impl ImageList {
+    /// Adds one or more images from the bitmap, returning the index of the first one. The
+    /// bitmap is split in as many images as fit its width. The optional mask determines which
+    /// pixels are transparent. The image list makes its own copy of the bitmaps.
+    pub fn add_bitmap(&self, bitmap: &gdi::Bitmap, mask: Option<&gdi::Bitmap>) -> Result<usize> {
+        let mask = mask.map_or(ptr::null_mut(), |mask| mask.bitmap.as_ptr());
+        // https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-imagelist_add
+        match unsafe { ImageList_Add(self.list.as_ptr(), bitmap.bitmap.as_ptr(), mask) } {
+            -1 => Err(Error::Control(ControlError::Failed)),
+            index => Ok(index as usize),
+        }
+    }
+
+    /// Adds the icon as a new image, returning its index. Icons from resources or files are
+    /// loaded with the size of the images. The image list makes its own copy of the icon.
+    pub fn add_icon(&self, icon: &icon::Icon) -> Result<usize> {
+        let (mut width, mut height) = (0, 0);
+        // https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-imagelist_geticonsize
+        if unsafe { ImageList_GetIconSize(self.list.as_ptr(), &mut width, &mut height) } == 0 {
+            return Err(Error::Control(ControlError::Failed));
+        }
+        let handle = icon.load_size(width, height)?;
+        // https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-imagelist_addicon
+        let result = unsafe { ImageList_ReplaceIcon(self.list.as_ptr(), -1, handle.as_ptr()) };
+        if icon.is_owned() {
+            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-destroyicon
+            let destroyed = unsafe { DestroyIcon(handle.as_ptr()) };
+            debug_assert!(destroyed != 0, "failed to destroy copied icon");
+        }
+        match result {
+            -1 => Err(Error::Control(ControlError::Failed)),
+            index => Ok(index as usize),
+        }
+    }
+
+    /// Gets the number of images in the image list.
+    pub fn count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-imagelist_getimagecount
+        unsafe { ImageList_GetImageCount(self.list.as_ptr()) as usize }
+    }
+}
+
+impl Drop for ImageList {
+    fn drop(&mut self) {
+        let result = unsafe { ImageList_Destroy(self.list.as_ptr()) };
+        debug_assert!(
+            result != 0,
+            "failed to destroy image list, it might have been destroyed by other means"
+        );
+    }
+}
@@ -0,0 +1,341 @@
+//! List boxes https://docs.microsoft.com/en-us/windows/win32/controls/list-boxes.
+use super::{free_item_data, item_value, store_item_data, ControlStyle};
+use crate::error::{control_result, ControlError};
+use crate::window::{self, Window};
+use crate::{class, from_wide, to_wide, Error, Result};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use winapi::shared::minwindef::{DWORD, FALSE, TRUE, UINT};
+use winapi::um::winuser::{
+    SendMessageW, LBS_DISABLENOSCROLL, LBS_EXTENDEDSEL, LBS_MULTICOLUMN, LBS_MULTIPLESEL,
+    LBS_NOINTEGRALHEIGHT, LBS_NOSEL, LBS_NOTIFY, LBS_SORT, LBS_USETABSTOPS, LBS_WANTKEYBOARDINPUT,
+    LB_ADDSTRING, LB_DELETESTRING, LB_ERR, LB_FINDSTRING, LB_FINDSTRINGEXACT, LB_GETCOUNT,
+    LB_GETCURSEL, LB_GETITEMDATA, LB_GETITEMHEIGHT, LB_GETSEL, LB_GETSELCOUNT, LB_GETSELITEMS,
+    LB_GETTEXT, LB_GETTEXTLEN, LB_GETTOPINDEX, LB_INSERTSTRING, LB_RESETCONTENT, LB_SELITEMRANGEEX,
+    LB_SETCURSEL, LB_SETITEMDATA, LB_SETITEMHEIGHT, LB_SETSEL, LB_SETTOPINDEX,
+};
+
+/// List box styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/list-box-styles.
+#[repr(u32)]
+pub enum Style {
+    /// Shows a disabled vertical scroll bar for the list box when the box does not contain
+    /// enough items to scroll, instead of hiding it.
+    DisableNoScroll = LBS_DISABLENOSCROLL,
+
+    /// Allows multiple items to be selected by using the SHIFT key and the mouse or special key
+    /// combinations. List boxes with this style should be used as a `ListBox` with `Multiple`
+    /// selection.
+    ExtendedSelection = LBS_EXTENDEDSEL,
+
+    /// Specifies a multi-column list box that is scrolled horizontally.
+    MultiColumn = LBS_MULTICOLUMN,
+
+    /// Turns string selection on or off each time the user clicks or double-clicks a string in
+    /// the list box. The user can select any number of strings. List boxes with this style
+    /// should be used as a `ListBox` with `Multiple` selection.
+    MultipleSelection = LBS_MULTIPLESEL,
+
+    /// Specifies that the size of the list box is exactly the size specified by the application
+    /// when it created the list box, instead of a size that doesn't show partial items.
+    NoIntegralHeight = LBS_NOINTEGRALHEIGHT,
+
+    /// Specifies that the list box contains items that can be viewed but not selected.
+    NoSelection = LBS_NOSEL,
+
+    /// Causes the list box to send a notification to its parent window whenever the user clicks
+    /// a list box item, double-clicks an item, or types within the list box.
+    Notify = LBS_NOTIFY,
+
+    /// Sorts strings in the list box alphabetically.
+    Sort = LBS_SORT,
+
+    /// Enables a list box to recognize and expand tab characters when drawing its strings.
+    UseTabStops = LBS_USETABSTOPS,
+
+    /// Specifies that the owner of the list box receives `WM_VKEYTOITEM` messages whenever the
+    /// user presses a key and the list box has the input focus.
+    WantKeyboardInput = LBS_WANTKEYBOARDINPUT,
+}
+
+/// A list box whose items can hold a value of type `T`.
+///
+/// The values are owned by the list box: deleting an item (or clearing the list box) drops its
+/// value, and the values that are still attached are dropped when the control is destroyed.
+///
+/// The methods to get and change the selection depend on the selection mode `S`, which must
+/// match the style of the control: `Single` (the default) or `Multiple`.
+#[derive(Debug)]
+pub struct ListBox<'a, T = (), S = Single> {
+    window: Window<'a>,
+    _data: PhantomData<(T, S)>,
+}
+
+/// Selection mode of list boxes where at most one item can be selected at a time.
+#[derive(Debug)]
+pub enum Single {}
+
+/// Selection mode of list boxes with the `MultipleSelection` or `ExtendedSelection` style, where
+/// any number of items can be selected.
+#[derive(Debug)]
+pub enum Multiple {}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl<T: 'static, S> ListBox<'_, T, S> {
+    /// Creates a new list box. The builder should at least set the parent window and the child
+    /// identifier of the control, and for `Multiple` selection, one of the styles that allow it.
+    pub fn create(builder: window::Builder) -> Result<ListBox<'static, T, S>> {
+        builder.create(class::list_box(), "").map(|window| ListBox {
+            window,
+            _data: PhantomData,
+        })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the number of items in the list box.
+    pub fn count(&self) -> Result<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getcount
+        control_result(self.send(LB_GETCOUNT, 0, 0))
+    }
+
+    /// Adds a string to the list box, returning its index. If the list box does not have the
+    /// `Sort` style, the string is added to the end of the list. Otherwise, the string is
+    /// inserted into the list and the list is sorted.
+    pub fn add(&self, text: &str) -> Result<usize> {
+        let text = to_wide(text)?;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-addstring
+        control_result(self.send(LB_ADDSTRING, 0, text.as_ptr() as isize))
+    }
+
+    /// Adds a string to the list box like `add`, and attaches the value to the new item.
+    pub fn add_with_data(&self, text: &str, data: T) -> Result<usize> {
+        let index = self.add(text)?;
+        self.set_item_data(index, data)?;
+        Ok(index)
+    }
+
+    /// Inserts a string at the given index, or at the end if it's `None`, returning its final
+    /// index. Unlike `add`, the list is not sorted even if it has the `Sort` style.
+    pub fn insert(&self, index: Option<usize>, text: &str) -> Result<usize> {
+        let text = to_wide(text)?;
+        let index = index.map_or(-1, |index| index as isize);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-insertstring
+        control_result(self.send(LB_INSERTSTRING, index as usize, text.as_ptr() as isize))
+    }
+
+    /// Deletes the item at the given index, dropping its value. Returns the number of items
+    /// that remain in the list.
+    pub fn delete(&self, index: usize) -> Result<usize> {
+        let key = self.send(LB_GETITEMDATA, index, 0);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-deletestring
+        let remaining = control_result(self.send(LB_DELETESTRING, index, 0))?;
+        free_item_data(self.window.hwnd_ptr(), key);
+        Ok(remaining)
+    }
+
+    /// Removes all items from the list box, dropping their values.
+    pub fn clear(&self) {
+        for index in 0..self.count().unwrap_or(0) {
+            free_item_data(self.window.hwnd_ptr(), self.send(LB_GETITEMDATA, index, 0));
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-resetcontent
+        self.send(LB_RESETCONTENT, 0, 0);
+    }
+
+    /// Finds the first item that begins with the given text, without regard to case, returning
+    /// its index. The search starts after the item at `after` (or from the beginning if it's
+    /// `None`), and wraps around to the beginning.
+    pub fn find(&self, text: &str, after: Option<usize>) -> Result<Option<usize>> {
+        self.find_string(LB_FINDSTRING, text, after)
+    }
+
+    /// Like `find`, but the text of the item must match exactly (still without regard to case).
+    pub fn find_exact(&self, text: &str, after: Option<usize>) -> Result<Option<usize>> {
+        self.find_string(LB_FINDSTRINGEXACT, text, after)
+    }
+
+    // https://docs.microsoft.com/en-us/windows/win32/controls/lb-findstring
+    // https://docs.microsoft.com/en-us/windows/win32/controls/lb-findstringexact
+    fn find_string(&self, msg: UINT, text: &str, after: Option<usize>) -> Result<Option<usize>> {
+        let text = to_wide(text)?;
+        let after = after.map_or(-1, |index| index as isize);
+        let result = self.send(msg, after as usize, text.as_ptr() as isize);
+        Ok(match result {
+            LB_ERR => None,
+            index => Some(index as usize),
+        })
+    }
+
+    /// Gets the text of the item at the given index.
+    pub fn item_text(&self, index: usize) -> Result<String> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-gettextlen
+        let len = control_result(self.send(LB_GETTEXTLEN, index, 0))?;
+        // +1 for the NUL character
+        let mut buffer = vec![0u16; len + 1];
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-gettext
+        let len = control_result(self.send(LB_GETTEXT, index, buffer.as_mut_ptr() as isize))?;
+        buffer.truncate(len);
+        Ok(from_wide(&buffer))
+    }
+
+    /// Calls `f` with the value attached to the item at the given index, if any, and returns
+    /// its result.
+    pub fn with_item_data<R, F>(&self, index: usize, f: F) -> Result<Option<R>>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getitemdata
+        match self.send(LB_GETITEMDATA, index, 0) {
+            LB_ERR => Err(Error::Control(ControlError::Failed)),
+            key => Ok(item_value(self.window.hwnd_ptr(), key).map(|value| f(&value))),
+        }
+    }
+
+    /// Attaches the value to the item at the given index, dropping the previous one.
+    pub fn set_item_data(&self, index: usize, data: T) -> Result<()> {
+        let old_key = self.send(LB_GETITEMDATA, index, 0);
+        if old_key == LB_ERR {
+            return Err(Error::Control(ControlError::Failed));
+        }
+
+        let key = store_item_data(self.window.hwnd_ptr(), data);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-setitemdata
+        match control_result(self.send(LB_SETITEMDATA, index, key)) {
+            Ok(_) => {
+                free_item_data(self.window.hwnd_ptr(), old_key);
+                Ok(())
+            }
+            Err(e) => {
+                free_item_data(self.window.hwnd_ptr(), key);
+                Err(e)
+            }
+        }
+    }
+
+    /// Gets the index of the first visible item in the list box.
+    pub fn top_index(&self) -> Result<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-gettopindex
+        control_result(self.send(LB_GETTOPINDEX, 0, 0))
+    }
+
+    /// Scrolls the list box so that the item at the given index is visible, at the top if
+    /// possible.
+    pub fn set_top_index(&self, index: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-settopindex
+        control_result(self.send(LB_SETTOPINDEX, index, 0)).map(drop)
+    }
+
+    /// Gets the height, in pixels, of the items in the list box.
+    pub fn item_height(&self) -> Result<u32> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getitemheight
+        control_result(self.send(LB_GETITEMHEIGHT, 0, 0)).map(|height| height as u32)
+    }
+
+    /// Sets the height, in pixels, of the items in the list box.
+    pub fn set_item_height(&self, height: u8) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-setitemheight
+        control_result(self.send(LB_SETITEMHEIGHT, 0, height as isize)).map(drop)
+    }
+}
+
+impl<T: Clone + 'static, S> ListBox<'_, T, S> {
+    /// Gets a clone of the value attached to the item at the given index, if any.
+    pub fn item_data(&self, index: usize) -> Result<Option<T>> {
+        self.with_item_data(index, T::clone)
+    }
+}
+
+impl<T: 'static> ListBox<'_, T, Single> {
+    /// Gets the index of the selected item, if any.
+    pub fn selected(&self) -> Option<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getcursel
+        match self.send(LB_GETCURSEL, 0, 0) {
+            LB_ERR => None,
+            index => Some(index as usize),
+        }
+    }
+
+    /// Selects the item at the given index and scrolls it into view, or removes the selection
+    /// if it's `None`.
+    pub fn set_selected(&self, index: Option<usize>) -> Result<()> {
+        let index = index.map_or(-1, |index| index as isize);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-setcursel
+        match self.send(LB_SETCURSEL, index as usize, 0) {
+            // Removing the selection also "fails".
+            LB_ERR if index != -1 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T: 'static> ListBox<'_, T, Multiple> {
+    /// Gets the number of selected items.
+    pub fn selected_count(&self) -> Result<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getselcount
+        control_result(self.send(LB_GETSELCOUNT, 0, 0))
+    }
+
+    /// Gets the indices of the selected items, in ascending order.
+    pub fn selected_items(&self) -> Result<Vec<usize>> {
+        let mut buffer = vec![0i32; self.selected_count()?];
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getselitems
+        let len =
+            control_result(self.send(LB_GETSELITEMS, buffer.len(), buffer.as_mut_ptr() as isize))?;
+        buffer.truncate(len);
+        Ok(buffer.into_iter().map(|index| index as usize).collect())
+    }
+
+    /// Whether the item at the given index is selected.
+    pub fn is_selected(&self, index: usize) -> Result<bool> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getsel
+        control_result(self.send(LB_GETSEL, index, 0)).map(|selected| selected != 0)
+    }
+
+    /// Selects or deselects the item at the given index, or all items if it's `None`.
+    pub fn set_item_selected(&self, index: Option<usize>, selected: bool) -> Result<()> {
+        let index = index.map_or(-1, |index| index as isize);
+        let selected = if selected { TRUE } else { FALSE };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-setsel
+        control_result(self.send(LB_SETSEL, selected as usize, index)).map(drop)
+    }
+
+    /// Selects or deselects the items from `first` to `last`, both included.
+    pub fn set_range_selected(&self, first: usize, last: usize, selected: bool) -> Result<()> {
+        if !selected && first == last {
+            return self.set_item_selected(Some(first), false);
+        }
+
+        // The range is reversed to deselect it.
+        let (first, last) = if selected {
+            (first, last)
+        } else {
+            (last, first)
+        };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lb-selitemrangeex
+        control_result(self.send(LB_SELITEMRANGEEX, first, last as isize)).map(drop)
+    }
+}
+
+impl<'a, T, S> From<Window<'a>> for ListBox<'a, T, S> {
+    fn from(window: Window<'a>) -> Self {
+        ListBox {
+            window,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, S> Deref for ListBox<'a, T, S> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
@@ -0,0 +1,949 @@
+//! List views https://docs.microsoft.com/en-us/windows/win32/controls/list-view-controls-overview.
+use super::image_list::ImageList;
+use super::{
+    free_item_data, item_value, read_text, release_on_destroy, store_item_data, ControlStyle,
+};
+use crate::error::ControlError;
+use crate::notify::encode_wide_prefix;
+use crate::window::{self, Window};
+use crate::{class, to_wide, Error, Result};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+use std::ops::Deref;
+use std::ptr::NonNull;
+use std::rc::Rc;
+use std::slice;
+use widestring::U16CStr;
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{DWORD, FALSE, LPARAM, UINT, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::commctrl::{
+    HDM_GETITEMCOUNT, LVCFMT_CENTER, LVCFMT_LEFT, LVCFMT_RIGHT, LVCF_FMT, LVCF_SUBITEM, LVCF_TEXT,
+    LVCF_WIDTH, LVCOLUMNW, LVFI_PARTIAL, LVFI_STRING, LVFI_SUBSTRING, LVIF_IMAGE, LVIF_PARAM,
+    LVIF_TEXT, LVIS_FOCUSED, LVIS_SELECTED, LVITEMW, LVM_DELETEALLITEMS, LVM_DELETECOLUMN,
+    LVM_DELETEITEM, LVM_EDITLABELW, LVM_ENSUREVISIBLE, LVM_GETCOLUMNWIDTH,
+    LVM_GETEXTENDEDLISTVIEWSTYLE, LVM_GETHEADER, LVM_GETITEMCOUNT, LVM_GETITEMSTATE,
+    LVM_GETITEMTEXTW, LVM_GETITEMW, LVM_GETNEXTITEM, LVM_GETSELECTEDCOUNT, LVM_INSERTCOLUMNW,
+    LVM_INSERTITEMW, LVM_SETCOLUMNW, LVM_SETCOLUMNWIDTH, LVM_SETEXTENDEDLISTVIEWSTYLE,
+    LVM_SETIMAGELIST, LVM_SETITEMCOUNT, LVM_SETITEMSTATE, LVM_SETITEMTEXTW, LVM_SETITEMW,
+    LVM_SETVIEW, LVM_SORTITEMS, LVNI_FOCUSED, LVNI_SELECTED, LVN_GETDISPINFOW, LVN_ODCACHEHINT,
+    LVN_ODFINDITEMW, LVSCW_AUTOSIZE, LVSCW_AUTOSIZE_USEHEADER, LVSIL_NORMAL, LVSIL_SMALL,
+    LVSIL_STATE, LVS_ALIGNLEFT, LVS_AUTOARRANGE, LVS_EDITLABELS, LVS_EX_CHECKBOXES,
+    LVS_EX_DOUBLEBUFFER, LVS_EX_FULLROWSELECT, LVS_EX_GRIDLINES, LVS_EX_HEADERDRAGDROP,
+    LVS_EX_LABELTIP, LVS_EX_ONECLICKACTIVATE, LVS_EX_TRACKSELECT, LVS_ICON, LVS_LIST,
+    LVS_NOCOLUMNHEADER, LVS_NOLABELWRAP, LVS_NOSCROLL, LVS_NOSORTHEADER, LVS_OWNERDATA, LVS_REPORT,
+    LVS_SHAREIMAGELISTS, LVS_SHOWSELALWAYS, LVS_SINGLESEL, LVS_SMALLICON, LVS_SORTASCENDING,
+    LVS_SORTDESCENDING, LV_VIEW_DETAILS, LV_VIEW_ICON, LV_VIEW_LIST, LV_VIEW_SMALLICON,
+    LV_VIEW_TILE, NMLVCACHEHINT, NMLVDISPINFOW, NMLVFINDITEMW,
+};
+use winapi::um::winuser::{GetWindowLongPtrW, SendMessageW, GWL_STYLE, NMHDR, WM_NOTIFY};
+
+/// List view styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/list-view-window-styles.
+///
+/// A list view should have one of `Icon`, `Report`, `SmallIcon` or `List`, which determine its
+/// initial view. The view can be changed later with `ListView::set_view`.
+#[repr(u32)]
+pub enum Style {
+    /// Each item appears as a full-sized icon with a label below it.
+    Icon = LVS_ICON,
+
+    /// Each item appears on its own line, with information arranged in columns.
+    Report = LVS_REPORT,
+
+    /// Each item appears as a small icon with a label to the right of it.
+    SmallIcon = LVS_SMALLICON,
+
+    /// Each item appears as a small icon with a label to the right of it, arranged in columns.
+    List = LVS_LIST,
+
+    /// Items are left-aligned in icon and small icon view, instead of aligned with the top of the
+    /// control.
+    AlignLeft = LVS_ALIGNLEFT,
+
+    /// Icons are automatically kept arranged in icon and small icon view.
+    AutoArrange = LVS_AUTOARRANGE,
+
+    /// Item text can be edited in place.
+    EditLabels = LVS_EDITLABELS,
+
+    /// Column headers are not displayed in report view.
+    NoColumnHeader = LVS_NOCOLUMNHEADER,
+
+    /// Item text is displayed on a single line in icon view.
+    NoLabelWrap = LVS_NOLABELWRAP,
+
+    /// Scrolling is disabled. All items must be within the client area.
+    NoScroll = LVS_NOSCROLL,
+
+    /// Column headers do not work like buttons.
+    NoSortHeader = LVS_NOSORTHEADER,
+
+    /// The image lists are not destroyed along with the control, so that they can be shared by
+    /// multiple controls.
+    ShareImageLists = LVS_SHAREIMAGELISTS,
+
+    /// The selection, if any, is always shown, even if the control does not have the focus.
+    ShowSelectionAlways = LVS_SHOWSELALWAYS,
+
+    /// Only one item at a time can be selected.
+    SingleSelection = LVS_SINGLESEL,
+
+    /// Item indices are sorted based on item text in ascending order.
+    SortAscending = LVS_SORTASCENDING,
+
+    /// Item indices are sorted based on item text in descending order.
+    SortDescending = LVS_SORTDESCENDING,
+
+    /// Creates a virtual list view, which doesn't store its items. Instead, it asks the
+    /// `VirtualData` set with `ListView::set_virtual_data` for their text when needed.
+    OwnerData = LVS_OWNERDATA,
+}
+
+/// Extended list view styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/extended-list-view-styles.
+///
+/// Unlike the other styles, these are changed after the control is created, with
+/// `ListView::set_extended_style`.
+#[repr(u32)]
+pub enum ExtendedStyle {
+    /// Enables check boxes for items.
+    CheckBoxes = LVS_EX_CHECKBOXES,
+
+    /// Paints via double-buffering, which reduces flicker.
+    DoubleBuffer = LVS_EX_DOUBLEBUFFER,
+
+    /// When an item is selected, the item and all its sub-items are highlighted. Only for
+    /// report view.
+    FullRowSelect = LVS_EX_FULLROWSELECT,
+
+    /// Displays gridlines around items and sub-items. Only for report view.
+    GridLines = LVS_EX_GRIDLINES,
+
+    /// Enables drag-and-drop reordering of columns. Only for report view.
+    HeaderDragDrop = LVS_EX_HEADERDRAGDROP,
+
+    /// Unfolds partly hidden labels in a tooltip.
+    LabelTip = LVS_EX_LABELTIP,
+
+    /// Items are activated with a single click, instead of a double click.
+    OneClickActivate = LVS_EX_ONECLICKACTIVATE,
+
+    /// Items are selected when the mouse hovers over them.
+    TrackSelect = LVS_EX_TRACKSELECT,
+}
+
+/// How the items of a list view are displayed https://docs.microsoft.com/en-us/windows/win32/controls/list-view-controls-overview#list-view-views.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Icon = LV_VIEW_ICON,
+    /// Also known as report view.
+    Details = LV_VIEW_DETAILS,
+    SmallIcon = LV_VIEW_SMALLICON,
+    List = LV_VIEW_LIST,
+    /// Requires version 6 of the common controls.
+    Tile = LV_VIEW_TILE,
+}
+
+/// Alignment of the text in a column.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Alignment {
+    Left = LVCFMT_LEFT,
+    Right = LVCFMT_RIGHT,
+    Center = LVCFMT_CENTER,
+}
+
+/// Which of the image lists of a list view to use.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageListKind {
+    /// Large icons, for the icon view.
+    Normal = LVSIL_NORMAL,
+    /// Small icons, for every other view.
+    Small = LVSIL_SMALL,
+    /// State images, shown next to the icon.
+    State = LVSIL_STATE,
+}
+
+/// The source of the items of a virtual list view (one with the `OwnerData` style). The list
+/// view only asks for the items it needs to display, so the memory it uses doesn't depend on
+/// the number of items.
+pub trait VirtualData {
+    /// The text of the item at the given row (if `column` is 0), or that of one of its sub-items.
+    fn text(&self, row: usize, column: usize) -> Cow<'_, str>;
+
+    /// The rows from `from` to `to` (both included) are about to be displayed, so they can be
+    /// loaded in advance. Does nothing by default.
+    fn cache_hint(&self, _from: usize, _to: usize) {}
+
+    /// Finds the row of the item whose text begins with the given text (or is equal to it, if
+    /// `prefix` is `false`), without regard to case. The search starts at `start`, and wraps
+    /// around to the beginning. The list view uses this to search by typing. Finds nothing by
+    /// default.
+    fn find(&self, _text: &str, _start: usize, _prefix: bool) -> Option<usize> {
+        None
+    }
+}
+
+/// A list view whose items can hold a value of type `T`.
+///
+/// Each item has a text, shown in the first column, and the text of its sub-items, shown in the
+/// remaining columns of the report view. Columns, items and sub-items are all referred to by
+/// their index.
+///
+/// As with `ListBox`, the values are owned by the list view, and dropped when their item is
+/// deleted, the list view is cleared or the control is destroyed.
+#[derive(Debug)]
+pub struct ListView<'a, T = ()> {
+    window: Window<'a>,
+    _data: PhantomData<T>,
+}
+
+thread_local! {
+    // Sources of the virtual list views, by the address of their window. They are dropped once
+    // their list view is destroyed.
+    static VIRTUAL_DATA: RefCell<HashMap<usize, Rc<dyn VirtualData>>> =
+        RefCell::new(HashMap::new());
+}
+
+struct SortContext<T, F> {
+    values: HashMap<isize, Rc<T>>,
+    compare: F,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl<T: 'static> ListView<'_, T> {
+    /// Creates a new list view. The builder should at least set the parent window and the child
+    /// identifier of the control.
+    pub fn create(builder: window::Builder) -> Result<ListView<'static, T>> {
+        builder
+            .create(class::list_view(), "")
+            .map(|window| ListView {
+                window,
+                _data: PhantomData,
+            })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    fn send_item(&self, msg: UINT, wparam: usize, item: &mut LVITEMW) -> isize {
+        self.send(msg, wparam, item as *mut LVITEMW as isize)
+    }
+
+    /// Changes how the items are displayed.
+    pub fn set_view(&self, view: View) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setview
+        match self.send(LVM_SETVIEW, view as usize, 0) {
+            1 => Ok(()),
+            _ => Err(Error::Control(ControlError::Failed)),
+        }
+    }
+
+    /// Whether the extended style is enabled.
+    pub fn has_extended_style(&self, style: ExtendedStyle) -> bool {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getextendedlistviewstyle
+        let styles = self.send(LVM_GETEXTENDEDLISTVIEWSTYLE, 0, 0) as DWORD;
+        (styles & style as DWORD) != 0
+    }
+
+    /// Enables or disables the extended style.
+    pub fn set_extended_style(&self, style: ExtendedStyle, enabled: bool) {
+        let mask = style as DWORD;
+        let value = if enabled { mask } else { 0 };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setextendedlistviewstyle
+        self.send(LVM_SETEXTENDEDLISTVIEWSTYLE, mask as usize, value as isize);
+    }
+
+    /// Sets the image list used for the given kind of images, returning the previous one.
+    ///
+    /// The list view takes ownership of the image list, and destroys it along with itself
+    /// unless it has the `ShareImageLists` style. The previous image list is no longer used by
+    /// the control, so it's returned to be dropped or reused. With the `ShareImageLists` style,
+    /// nothing is returned, because the previous image list might still be in use elsewhere.
+    pub fn set_image_list(&self, list: ImageList, kind: ImageListKind) -> Option<ImageList> {
+        let list = ManuallyDrop::new(list);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setimagelist
+        let previous = self.send(LVM_SETIMAGELIST, kind as usize, list.list.as_ptr() as isize);
+        let style = unsafe { GetWindowLongPtrW(self.window.hwnd_ptr(), GWL_STYLE) };
+        if style as DWORD & LVS_SHAREIMAGELISTS != 0 {
+            return None;
+        }
+        NonNull::new(previous as *mut _).map(|list| ImageList { list })
+    }
+
+    /// Sets the source of the items of a virtual list view, along with the number of items,
+    /// dropping the previous source.
+    ///
+    /// The list view sends its requests to its parent, where they are answered before reaching
+    /// the message callback. The source is dropped when the list view is destroyed.
+    pub fn set_virtual_data<D: VirtualData + 'static>(&self, data: D, count: usize) -> Result<()> {
+        release_on_destroy(self.window.hwnd_ptr());
+        let key = self.window.hwnd_ptr() as usize;
+        let previous = VIRTUAL_DATA.with(|sources| sources.borrow_mut().insert(key, Rc::new(data)));
+        drop(previous);
+        self.set_virtual_count(count)
+    }
+
+    /// Sets the number of items in a virtual list view, and redraws it.
+    pub fn set_virtual_count(&self, count: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setitemcount
+        match self.send(LVM_SETITEMCOUNT, count, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    // Columns.
+
+    /// Gets the number of columns in report view.
+    pub fn column_count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getheader
+        let header = self.send(LVM_GETHEADER, 0, 0);
+        if header == 0 {
+            return 0;
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/hdm-getitemcount
+        match unsafe { SendMessageW(header as _, HDM_GETITEMCOUNT, 0, 0) } {
+            -1 => 0,
+            count => count as usize,
+        }
+    }
+
+    /// Adds a column to the end, returning its index. The first column is always left-aligned.
+    pub fn add_column(&self, text: &str, width: u32, alignment: Alignment) -> Result<usize> {
+        let index = self.column_count();
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut column: LVCOLUMNW = unsafe { mem::zeroed() };
+        column.mask = LVCF_FMT | LVCF_WIDTH | LVCF_TEXT | LVCF_SUBITEM;
+        column.fmt = alignment as c_int;
+        column.cx = width as c_int;
+        column.pszText = text.as_mut_ptr();
+        column.iSubItem = index as c_int;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-insertcolumn
+        match self.send(LVM_INSERTCOLUMNW, index, &mut column as *mut _ as isize) {
+            -1 => Err(Error::Control(ControlError::Failed)),
+            index => Ok(index as usize),
+        }
+    }
+
+    /// Deletes the column at the given index. The text of the items is shifted to the
+    /// remaining columns.
+    pub fn delete_column(&self, index: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-deletecolumn
+        match self.send(LVM_DELETECOLUMN, index, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets the width, in pixels, of the column at the given index.
+    pub fn column_width(&self, index: usize) -> Result<u32> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getcolumnwidth
+        // Zero is also returned on failure, but it's a valid width, so it can't be told apart.
+        Ok(self.send(LVM_GETCOLUMNWIDTH, index, 0) as u32)
+    }
+
+    /// Sets the width, in pixels, of the column at the given index.
+    pub fn set_column_width(&self, index: usize, width: u32) -> Result<()> {
+        self.set_column_width_raw(index, width as c_int)
+    }
+
+    /// Resizes the column at the given index to fit its contents, and also its header if
+    /// `include_header` is `true`. If the column is the last one, that instead makes it fill the
+    /// remaining width of the control.
+    pub fn auto_size_column(&self, index: usize, include_header: bool) -> Result<()> {
+        self.set_column_width_raw(
+            index,
+            if include_header {
+                LVSCW_AUTOSIZE_USEHEADER
+            } else {
+                LVSCW_AUTOSIZE
+            },
+        )
+    }
+
+    fn set_column_width_raw(&self, index: usize, width: c_int) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setcolumnwidth
+        match self.send(LVM_SETCOLUMNWIDTH, index, width as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Changes the alignment of the column at the given index. The first column is always
+    /// left-aligned.
+    pub fn set_column_alignment(&self, index: usize, alignment: Alignment) -> Result<()> {
+        let mut column: LVCOLUMNW = unsafe { mem::zeroed() };
+        column.mask = LVCF_FMT;
+        column.fmt = alignment as c_int;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setcolumn
+        match self.send(LVM_SETCOLUMNW, index, &mut column as *mut _ as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    // Items.
+
+    /// Gets the number of items in the list view.
+    pub fn count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getitemcount
+        self.send(LVM_GETITEMCOUNT, 0, 0) as usize
+    }
+
+    /// Adds an item with the given text to the end, returning its index. If the list view has
+    /// one of the sort styles, the item is instead inserted in order.
+    pub fn add(&self, text: &str) -> Result<usize> {
+        self.insert(None, text)
+    }
+
+    /// Adds an item like `add`, and attaches the value to it.
+    pub fn add_with_data(&self, text: &str, data: T) -> Result<usize> {
+        let index = self.add(text)?;
+        self.set_item_data(index, data)?;
+        Ok(index)
+    }
+
+    /// Inserts an item with the given text at the given index, or at the end if it's `None`,
+    /// returning its final index.
+    pub fn insert(&self, index: Option<usize>, text: &str) -> Result<usize> {
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.mask = LVIF_TEXT;
+        item.iItem = index.unwrap_or_else(|| self.count()) as c_int;
+        item.pszText = text.as_mut_ptr();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-insertitem
+        match self.send_item(LVM_INSERTITEMW, 0, &mut item) {
+            -1 => Err(Error::Control(ControlError::Failed)),
+            index => Ok(index as usize),
+        }
+    }
+
+    /// Deletes the item at the given index, dropping its value.
+    pub fn delete(&self, index: usize) -> Result<()> {
+        let key = self.item_key(index)?;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-deleteitem
+        match self.send(LVM_DELETEITEM, index, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => {
+                free_item_data(self.window.hwnd_ptr(), key);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes all items from the list view, dropping their values.
+    pub fn clear(&self) {
+        for index in 0..self.count() {
+            if let Ok(key) = self.item_key(index) {
+                free_item_data(self.window.hwnd_ptr(), key);
+            }
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-deleteallitems
+        self.send(LVM_DELETEALLITEMS, 0, 0);
+    }
+
+    /// Gets the text of the item at the given index (if `column` is 0), or that of one of its
+    /// sub-items.
+    pub fn item_text(&self, index: usize, column: usize) -> String {
+        let text = read_text(|buffer| {
+            let mut item: LVITEMW = unsafe { mem::zeroed() };
+            item.iSubItem = column as c_int;
+            item.pszText = buffer.as_mut_ptr();
+            item.cchTextMax = buffer.len() as c_int;
+            // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getitemtext
+            self.send_item(LVM_GETITEMTEXTW, index, &mut item);
+            Ok(())
+        });
+        text.unwrap_or_default()
+    }
+
+    /// Sets the text of the item at the given index (if `column` is 0), or that of one of its
+    /// sub-items.
+    pub fn set_item_text(&self, index: usize, column: usize, text: &str) -> Result<()> {
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.iSubItem = column as c_int;
+        item.pszText = text.as_mut_ptr();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setitemtext
+        match self.send_item(LVM_SETITEMTEXTW, index, &mut item) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the index of the image, in the image lists of the list view, shown next to the item
+    /// at the given index.
+    pub fn set_item_image(&self, index: usize, image: usize) -> Result<()> {
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.mask = LVIF_IMAGE;
+        item.iItem = index as c_int;
+        item.iImage = image as c_int;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setitem
+        match self.send_item(LVM_SETITEMW, 0, &mut item) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    fn item_key(&self, index: usize) -> Result<isize> {
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.mask = LVIF_PARAM;
+        item.iItem = index as c_int;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getitem
+        match self.send_item(LVM_GETITEMW, 0, &mut item) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(item.lParam),
+        }
+    }
+
+    /// Calls `f` with the value attached to the item at the given index, if any, and returns its result.
+    pub fn with_item_data<R, F>(&self, index: usize, f: F) -> Result<Option<R>>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.item_key(index)
+            .map(|key| item_value(self.window.hwnd_ptr(), key).map(|value| f(&value)))
+    }
+
+    /// Attaches the value to the item at the given index, dropping the previous one.
+    pub fn set_item_data(&self, index: usize, data: T) -> Result<()> {
+        let old_key = self.item_key(index)?;
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.mask = LVIF_PARAM;
+        item.iItem = index as c_int;
+        item.lParam = store_item_data(self.window.hwnd_ptr(), data);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setitem
+        match self.send_item(LVM_SETITEMW, 0, &mut item) {
+            0 => {
+                free_item_data(self.window.hwnd_ptr(), item.lParam);
+                Err(Error::Control(ControlError::Failed))
+            }
+            _ => {
+                free_item_data(self.window.hwnd_ptr(), old_key);
+                Ok(())
+            }
+        }
+    }
+
+    /// Sorts the items by comparing their values. Items without a value are sorted last.
+    pub fn sort_by<F>(&self, compare: F) -> Result<()>
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        // The values are gathered beforehand, so that the comparison can freely use the list
+        // view.
+        let mut context = SortContext {
+            values: (0..self.count())
+                .filter_map(|index| {
+                    let key = self.item_key(index).ok()?;
+                    item_value(self.window.hwnd_ptr(), key).map(|value| (key, value))
+                })
+                .collect(),
+            compare,
+        };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-sortitems
+        let result = self.send(
+            LVM_SORTITEMS,
+            &mut context as *mut SortContext<T, F> as usize,
+            compare_items::<T, F> as *const () as isize,
+        );
+        match result {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Scrolls the list view so that the item at the given index is entirely visible.
+    pub fn ensure_visible(&self, index: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-ensurevisible
+        match self.send(LVM_ENSUREVISIBLE, index, FALSE as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Begins in-place editing of the text of the item at the given index. The list view must
+    /// have the focus.
+    pub fn edit_label(&self, index: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-editlabel
+        match self.send(LVM_EDITLABELW, index, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    // Selection and focus.
+
+    /// Gets the number of selected items.
+    pub fn selected_count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getselectedcount
+        self.send(LVM_GETSELECTEDCOUNT, 0, 0) as usize
+    }
+
+    /// Gets the indices of the selected items, in ascending order.
+    pub fn selected_items(&self) -> Vec<usize> {
+        let mut items = Vec::with_capacity(self.selected_count());
+        let mut index = -1;
+        loop {
+            // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getnextitem
+            index = self.send(LVM_GETNEXTITEM, index as usize, LVNI_SELECTED);
+            if index == -1 {
+                break items;
+            }
+            items.push(index as usize);
+        }
+    }
+
+    /// Whether the item at the given index is selected.
+    pub fn is_selected(&self, index: usize) -> bool {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getitemstate
+        let state = self.send(LVM_GETITEMSTATE, index, LVIS_SELECTED as isize) as UINT;
+        (state & LVIS_SELECTED) != 0
+    }
+
+    /// Selects or deselects the item at the given index, or all items if it's `None`.
+    pub fn set_item_selected(&self, index: Option<usize>, selected: bool) -> Result<()> {
+        self.set_item_state(index, LVIS_SELECTED, selected)
+    }
+
+    /// Gets the index of the item with the focus, if any.
+    pub fn focused(&self) -> Option<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-getnextitem
+        match self.send(LVM_GETNEXTITEM, -1isize as usize, LVNI_FOCUSED) {
+            -1 => None,
+            index => Some(index as usize),
+        }
+    }
+
+    /// Gives the focus to the item at the given index. Only one item can have the focus.
+    pub fn set_focused(&self, index: usize) -> Result<()> {
+        self.set_item_state(Some(index), LVIS_FOCUSED, true)
+    }
+
+    fn set_item_state(&self, index: Option<usize>, state: UINT, enabled: bool) -> Result<()> {
+        let index = index.map_or(-1, |index| index as isize);
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.stateMask = state;
+        item.state = if enabled { state } else { 0 };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvm-setitemstate
+        match self.send_item(LVM_SETITEMSTATE, index as usize, &mut item) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T: Clone + 'static> ListView<'_, T> {
+    /// Gets a clone of the value attached to the item at the given index, if any.
+    pub fn item_data(&self, index: usize) -> Result<Option<T>> {
+        self.with_item_data(index, T::clone)
+    }
+}
+
+/// Answers the requests that virtual list views send to their parent. Returns the result of the
+/// message if it was answered.
+pub(crate) fn intercept_parent_message(msg: UINT, _: WPARAM, lparam: LPARAM) -> Option<isize> {
+    match msg {
+        WM_NOTIFY => {
+            let window = unsafe { (*(lparam as *const NMHDR)).hwndFrom } as usize;
+            // Cloned out, so that the source can set the data of other list views.
+            let source = VIRTUAL_DATA.with(|sources| sources.borrow().get(&window).cloned())?;
+            unsafe { answer_virtual_notify(&*source, lparam) }
+        }
+        _ => None,
+    }
+}
+
+/// Forgets the source of the list view, which is being destroyed.
+pub(super) fn release(hwnd: HWND) {
+    let source = VIRTUAL_DATA.with(|sources| sources.borrow_mut().remove(&(hwnd as usize)));
+    drop(source);
+}
+
+/// Answers a request of a virtual list view with its source.
+///
+/// # Safety
+///
+/// `lparam` must point to a notification sent by a list view.
+unsafe fn answer_virtual_notify(source: &dyn VirtualData, lparam: LPARAM) -> Option<isize> {
+    // Only the Unicode variants are sent, because the control was created with `CreateWindowExW`.
+    match (*(lparam as *const NMHDR)).code {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvn-getdispinfo
+        LVN_GETDISPINFOW => {
+            let info = &mut *(lparam as *mut NMLVDISPINFOW);
+            fill_display_info(source, &mut info.item);
+            Some(0)
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvn-odcachehint
+        LVN_ODCACHEHINT => {
+            let hint = &*(lparam as *const NMLVCACHEHINT);
+            source.cache_hint(hint.iFrom as usize, hint.iTo as usize);
+            Some(0)
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/lvn-odfinditem
+        LVN_ODFINDITEMW => {
+            let find = &*(lparam as *const NMLVFINDITEMW);
+            let info = &find.lvfi;
+            if info.flags & LVFI_STRING == 0 || info.psz.is_null() {
+                return Some(-1);
+            }
+            let text = U16CStr::from_ptr_str(info.psz).to_string_lossy();
+            let prefix = info.flags & (LVFI_PARTIAL | LVFI_SUBSTRING) != 0;
+            let row = source.find(&text, find.iStart as usize, prefix);
+            Some(row.map_or(-1, |row| row as isize))
+        }
+        _ => None,
+    }
+}
+
+/// Copies the text the list view asked for into its buffer, cutting it short (without splitting
+/// any character) if it doesn't fit.
+///
+/// # Safety
+///
+/// The text of the item must point to a buffer of its text length, as is the case for the item
+/// of an `LVN_GETDISPINFO` notification.
+unsafe fn fill_display_info(source: &dyn VirtualData, item: &mut LVITEMW) {
+    if item.mask & LVIF_TEXT == 0 || item.pszText.is_null() || item.cchTextMax <= 0 {
+        return;
+    }
+    let text = source.text(item.iItem as usize, item.iSubItem as usize);
+    let buffer = slice::from_raw_parts_mut(item.pszText, item.cchTextMax as usize);
+    let len = encode_wide_prefix(&text, buffer);
+    buffer[len] = 0;
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nc-commctrl-pfnlvcompare
+unsafe extern "system" fn compare_items<T, F>(a: LPARAM, b: LPARAM, context: LPARAM) -> c_int
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    let context = &mut *(context as *mut SortContext<T, F>);
+    let ordering = match (context.values.get(&a), context.values.get(&b)) {
+        (Some(a), Some(b)) => (context.compare)(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+    ordering as c_int
+}
+
+impl<'a, T> From<Window<'a>> for ListView<'a, T> {
+    fn from(window: Window<'a>) -> Self {
+        ListView {
+            window,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Deref for ListView<'a, T> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use winapi::um::commctrl::LVFI_PARAM;
+
+    struct Rows {
+        rows: &'static [&'static str],
+        hint: Cell<Option<(usize, usize)>>,
+        searches: RefCell<Vec<(String, usize, bool)>>,
+    }
+
+    impl Rows {
+        fn new() -> Self {
+            Self {
+                rows: &["alpha", "beta", "gamma", "Gamma ray", "ab😀"],
+                hint: Cell::new(None),
+                searches: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl VirtualData for Rows {
+        fn text(&self, row: usize, column: usize) -> Cow<'_, str> {
+            match column {
+                0 => Cow::Borrowed(self.rows[row]),
+                _ => Cow::Owned(format!("{}:{}", row, column)),
+            }
+        }
+
+        fn cache_hint(&self, from: usize, to: usize) {
+            self.hint.set(Some((from, to)));
+        }
+
+        fn find(&self, text: &str, start: usize, prefix: bool) -> Option<usize> {
+            self.searches
+                .borrow_mut()
+                .push((text.to_owned(), start, prefix));
+            let text = text.to_lowercase();
+            (start..self.rows.len()).chain(0..start).find(|&row| {
+                let row = self.rows[row].to_lowercase();
+                if prefix {
+                    row.starts_with(&text)
+                } else {
+                    row == text
+                }
+            })
+        }
+    }
+
+    // Asks for the text of the item as the list view would, telling it that the buffer holds
+    // `size` units. The actual buffer is larger, so that writes past the end can be seen.
+    fn display_info(source: &Rows, row: i32, column: i32, size: i32) -> Vec<u16> {
+        let mut buffer = vec![0xFFFF; 16];
+        let mut info: NMLVDISPINFOW = unsafe { mem::zeroed() };
+        info.hdr.code = LVN_GETDISPINFOW;
+        info.item.mask = LVIF_TEXT;
+        info.item.iItem = row;
+        info.item.iSubItem = column;
+        info.item.pszText = buffer.as_mut_ptr();
+        info.item.cchTextMax = size;
+        let result = unsafe { answer_virtual_notify(source, &mut info as *mut _ as LPARAM) };
+        assert_eq!(result, Some(0));
+        buffer
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().collect()
+    }
+
+    #[test]
+    fn display_info_text() {
+        let source = Rows::new();
+        let buffer = display_info(&source, 1, 0, 16);
+        assert_eq!(buffer[..5], wide("beta\0")[..]);
+        assert!(buffer[5..].iter().all(|&c| c == 0xFFFF));
+
+        let buffer = display_info(&source, 2, 3, 16);
+        assert_eq!(buffer[..4], wide("2:3\0")[..]);
+    }
+
+    #[test]
+    fn display_info_truncates() {
+        let source = Rows::new();
+        let buffer = display_info(&source, 0, 0, 3);
+        assert_eq!(buffer[..3], wide("al\0")[..]);
+        assert!(buffer[3..].iter().all(|&c| c == 0xFFFF));
+
+        // Only the terminator fits.
+        let buffer = display_info(&source, 0, 0, 1);
+        assert_eq!(buffer[0], 0);
+        assert!(buffer[1..].iter().all(|&c| c == 0xFFFF));
+
+        // The surrogate pair is never split, even if its first half would fit.
+        let buffer = display_info(&source, 4, 0, 4);
+        assert_eq!(buffer[..3], wide("ab\0")[..]);
+        assert!(buffer[3..].iter().all(|&c| c == 0xFFFF));
+
+        let buffer = display_info(&source, 4, 0, 5);
+        assert_eq!(buffer[..5], wide("ab😀\0")[..]);
+    }
+
+    #[test]
+    fn display_info_without_buffer() {
+        let source = Rows::new();
+        for size in &[0, -1, i32::MIN] {
+            let buffer = display_info(&source, 0, 0, *size);
+            assert!(buffer.iter().all(|&c| c == 0xFFFF));
+        }
+
+        // Nothing is written if the text was not asked for, or there's nowhere to write it.
+        let mut buffer = vec![0xFFFF; 16];
+        let mut item: LVITEMW = unsafe { mem::zeroed() };
+        item.mask = LVIF_IMAGE;
+        item.pszText = buffer.as_mut_ptr();
+        item.cchTextMax = buffer.len() as c_int;
+        unsafe { fill_display_info(&source, &mut item) };
+        assert!(buffer.iter().all(|&c| c == 0xFFFF));
+
+        item.mask = LVIF_TEXT;
+        item.pszText = std::ptr::null_mut();
+        unsafe { fill_display_info(&source, &mut item) };
+    }
+
+    #[test]
+    fn cache_hint() {
+        let source = Rows::new();
+        let mut hint: NMLVCACHEHINT = unsafe { mem::zeroed() };
+        hint.hdr.code = LVN_ODCACHEHINT;
+        hint.iFrom = 1;
+        hint.iTo = 3;
+        let result = unsafe { answer_virtual_notify(&source, &mut hint as *mut _ as LPARAM) };
+        assert_eq!(result, Some(0));
+        assert_eq!(source.hint.get(), Some((1, 3)));
+    }
+
+    fn find(source: &Rows, text: &str, flags: UINT, start: i32) -> Option<isize> {
+        let text = to_wide(text).unwrap();
+        let mut find: NMLVFINDITEMW = unsafe { mem::zeroed() };
+        find.hdr.code = LVN_ODFINDITEMW;
+        find.iStart = start;
+        find.lvfi.flags = flags;
+        find.lvfi.psz = text.as_ptr();
+        unsafe { answer_virtual_notify(source, &mut find as *mut _ as LPARAM) }
+    }
+
+    #[test]
+    fn find_item() {
+        let source = Rows::new();
+        assert_eq!(find(&source, "gamma", LVFI_STRING, 0), Some(2));
+        assert_eq!(find(&source, "GAM", LVFI_STRING | LVFI_PARTIAL, 3), Some(3));
+        assert_eq!(
+            find(&source, "al", LVFI_STRING | LVFI_SUBSTRING, 1),
+            Some(0)
+        );
+        assert_eq!(find(&source, "delta", LVFI_STRING, 0), Some(-1));
+        assert_eq!(
+            *source.searches.borrow(),
+            vec![
+                (String::from("gamma"), 0, false),
+                (String::from("GAM"), 3, true),
+                (String::from("al"), 1, true),
+                (String::from("delta"), 0, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_item_without_string() {
+        let source = Rows::new();
+        assert_eq!(find(&source, "beta", LVFI_PARAM, 0), Some(-1));
+        assert_eq!(find(&source, "beta", 0, 0), Some(-1));
+
+        let mut find: NMLVFINDITEMW = unsafe { mem::zeroed() };
+        find.hdr.code = LVN_ODFINDITEMW;
+        find.lvfi.flags = LVFI_STRING;
+        let result = unsafe { answer_virtual_notify(&source, &mut find as *mut _ as LPARAM) };
+        assert_eq!(result, Some(-1));
+        assert!(source.searches.borrow().is_empty());
+    }
+
+    #[test]
+    fn other_notifications() {
+        let source = Rows::new();
+        let mut header: NMHDR = unsafe { mem::zeroed() };
+        header.code = LVN_GETDISPINFOW.wrapping_add(1000);
+        let result = unsafe { answer_virtual_notify(&source, &mut header as *mut _ as LPARAM) };
+        assert_eq!(result, None);
+    }
+}
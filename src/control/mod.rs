@@ -0,0 +1,167 @@
+//! Typed wrappers around the standard and common controls.
+//!
+//! Each control wraps a `Window`, which it dereferences to, so the generic window methods (such
+//! as `get_text` or `set_font`) are still available. Controls can either be created through a
+//! `window::Builder`, or obtained from an existing window (such as one returned by
+//! `Window::get_dialog_item`) with `From`.
+//!
+//! See also https://docs.microsoft.com/en-us/windows/win32/controls/individual-control-info.
+pub mod button;
+pub mod combo_box;
+pub mod edit;
+pub mod image_list;
+pub mod list_box;
+pub mod list_view;
+pub mod progress_bar;
+pub mod tab_control;
+pub mod trackbar;
+pub mod tree_view;
+pub mod up_down;
+
+use crate::{from_wide, Result};
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use winapi::shared::basetsd::{DWORD_PTR, UINT_PTR};
+use winapi::shared::minwindef::{DWORD, LPARAM, LRESULT, UINT, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::commctrl::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass};
+use winapi::um::winuser::WM_NCDESTROY;
+
+// The values attached to the items of one control, by the key set as the data of their item.
+// They are shared, so that they can be lent out without keeping the map borrowed.
+type ItemValues = HashMap<isize, Rc<dyn Any>>;
+
+thread_local! {
+    // Values attached to the items of controls, by the address of their window. The items only
+    // hold the key, so that a bogus value set by other means can never be mistaken for a
+    // pointer, nor refer to the values of another control.
+    static ITEM_DATA: RefCell<HashMap<usize, ItemValues>> = RefCell::new(HashMap::new());
+    static NEXT_ITEM_KEY: Cell<isize> = const { Cell::new(1) };
+}
+
+// Identifies the subclass that releases the state of the control once it's destroyed.
+const RELEASE_SUBCLASS_ID: UINT_PTR = 1;
+
+/// Styles that only apply to one class of control. Their bits overlap with the styles of other
+/// classes, so they are added with `window::Builder::add_control_style` instead.
+pub trait ControlStyle {
+    /// The bits of the style in the window style.
+    fn bits(self) -> DWORD;
+}
+
+/// Lets the controls that need it answer the messages they send to their parent. Returns the
+/// result of the message if it was answered.
+pub(crate) fn intercept_parent_message(msg: UINT, wparam: WPARAM, lparam: LPARAM) -> Option<isize> {
+    // Each control only answers the notifications of the windows it keeps a source for.
+    list_view::intercept_parent_message(msg, wparam, lparam)
+        .or_else(|| tree_view::intercept_parent_message(msg, wparam, lparam))
+}
+
+/// Makes sure that the state kept for the control (such as the values attached to its items) is
+/// released once it's destroyed. Doing it more than once has no effect.
+pub(crate) fn release_on_destroy(hwnd: HWND) {
+    // https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nf-commctrl-setwindowsubclass
+    let result = unsafe { SetWindowSubclass(hwnd, Some(release_proc), RELEASE_SUBCLASS_ID, 0) };
+    debug_assert!(result != 0, "failed to subclass control");
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/api/commctrl/nc-commctrl-subclassproc
+unsafe extern "system" fn release_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    id: UINT_PTR,
+    _: DWORD_PTR,
+) -> LRESULT {
+    let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+    // This is the last message the control receives.
+    if msg == WM_NCDESTROY {
+        RemoveWindowSubclass(hwnd, Some(release_proc), id);
+        release(hwnd);
+    }
+    result
+}
+
+/// Drops the state kept for the control.
+fn release(hwnd: HWND) {
+    // Taken out first, so that the values are dropped once the map is no longer borrowed.
+    let values = ITEM_DATA.with(|data| data.borrow_mut().remove(&(hwnd as usize)));
+    drop(values);
+    list_view::release(hwnd);
+    tree_view::release(hwnd);
+}
+
+/// Stores the value for an item of the control, returning the key to set as its data.
+pub(crate) fn store_item_data<T: 'static>(hwnd: HWND, value: T) -> isize {
+    let key = NEXT_ITEM_KEY.with(|next| {
+        let key = next.get();
+        // Zero is the default data of new items, and negative values are error codes.
+        next.set(if key == isize::MAX { 1 } else { key + 1 });
+        key
+    });
+    release_on_destroy(hwnd);
+    ITEM_DATA.with(|data| {
+        data.borrow_mut()
+            .entry(hwnd as usize)
+            .or_default()
+            .insert(key, Rc::new(value))
+    });
+    key
+}
+
+/// Gets the value stored for the control under the key, if there is one and it's of the right
+/// type. The map is not borrowed while the value is in use, so the control can be freely used.
+pub(crate) fn item_value<T: 'static>(hwnd: HWND, key: isize) -> Option<Rc<T>> {
+    let value = ITEM_DATA.with(|data| {
+        data.borrow()
+            .get(&(hwnd as usize))
+            .and_then(|values| values.get(&key))
+            .cloned()
+    })?;
+    value.downcast().ok()
+}
+
+/// Drops the value stored for the control under the key, if any.
+pub(crate) fn free_item_data(hwnd: HWND, key: isize) {
+    // Taken out first, so that the value is dropped once the map is no longer borrowed.
+    let value = ITEM_DATA.with(|data| {
+        data.borrow_mut()
+            .get_mut(&(hwnd as usize))
+            .and_then(|values| values.remove(&key))
+    });
+    drop(value);
+}
+
+/// Reads text from the control, whose length can't be queried beforehand. `fill` asks the
+/// control to copy the text into the buffer, which is retried with a larger buffer until the
+/// text fits (with room to spare for the NUL character, so that it's known not to be cut).
+pub(crate) fn read_text<F>(mut fill: F) -> Result<String>
+where
+    F: FnMut(&mut [u16]) -> Result<()>,
+{
+    let mut buffer = vec![0u16; 64];
+    loop {
+        fill(&mut buffer)?;
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        if len + 1 < buffer.len() {
+            buffer.truncate(len);
+            break Ok(from_wide(&buffer));
+        }
+        buffer.resize(buffer.len() * 2, 0);
+    }
+}
+
+pub use button::Button;
+pub use combo_box::ComboBox;
+pub use edit::Edit;
+pub use image_list::ImageList;
+pub use list_box::ListBox;
+pub use list_view::ListView;
+pub use progress_bar::ProgressBar;
+pub use tab_control::{TabControl, TabHost};
+pub use trackbar::Trackbar;
+pub use tree_view::TreeView;
+pub use up_down::UpDown;
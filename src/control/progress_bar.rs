@@ -0,0 +1,167 @@
+//! Progress bars https://docs.microsoft.com/en-us/windows/win32/controls/progress-bar-control.
+use super::ControlStyle;
+use crate::window::{self, Window};
+use crate::{class, Result};
+use std::ops::Deref;
+use std::time::Duration;
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{DWORD, FALSE, TRUE, UINT};
+use winapi::um::commctrl::{
+    PBM_DELTAPOS, PBM_GETPOS, PBM_GETRANGE, PBM_GETSTATE, PBM_GETSTEP, PBM_SETMARQUEE, PBM_SETPOS,
+    PBM_SETRANGE32, PBM_SETSTATE, PBM_SETSTEP, PBM_STEPIT, PBRANGE, PBST_ERROR, PBST_NORMAL,
+    PBST_PAUSED, PBS_MARQUEE, PBS_SMOOTH, PBS_SMOOTHREVERSE, PBS_VERTICAL,
+};
+use winapi::um::winuser::SendMessageW;
+
+/// Progress bar styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/progress-bar-control-styles.
+#[repr(u32)]
+pub enum Style {
+    /// The progress bar moves constantly without showing any specific progress, to indicate
+    /// that work is being done. The animation is controlled with `start_marquee`.
+    Marquee = PBS_MARQUEE,
+
+    /// The progress is displayed as a smooth bar, instead of segments. Ignored by visual styles.
+    Smooth = PBS_SMOOTH,
+
+    /// Animates the bar when the position decreases, as it does when it increases.
+    SmoothReverse = PBS_SMOOTHREVERSE,
+
+    /// The progress is displayed vertically, from bottom to top.
+    Vertical = PBS_VERTICAL,
+}
+
+/// The state of a progress bar, which determines its color.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    /// In progress, usually green.
+    Normal = PBST_NORMAL,
+    /// An error occurred, usually red.
+    Error = PBST_ERROR,
+    /// Progress is paused, usually yellow.
+    Paused = PBST_PAUSED,
+}
+
+#[derive(Debug)]
+pub struct ProgressBar<'a> {
+    window: Window<'a>,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl ProgressBar<'_> {
+    /// Creates a new progress bar, with a range from 0 to 100. The builder should at least set
+    /// the parent window and the child identifier of the control.
+    pub fn create(builder: window::Builder) -> Result<ProgressBar<'static>> {
+        builder
+            .create(class::progress_bar(), "")
+            .map(|window| ProgressBar { window })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the minimum and maximum positions of the progress bar.
+    pub fn range(&self) -> (i32, i32) {
+        let mut range = PBRANGE { iLow: 0, iHigh: 0 };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-getrange
+        self.send(PBM_GETRANGE, 0, &mut range as *mut PBRANGE as isize);
+        (range.iLow, range.iHigh)
+    }
+
+    /// Sets the minimum and maximum positions of the progress bar.
+    pub fn set_range(&self, min: i32, max: i32) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-setrange32
+        self.send(PBM_SETRANGE32, min as usize, max as isize);
+    }
+
+    /// Gets the current position of the progress bar.
+    pub fn position(&self) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-getpos
+        self.send(PBM_GETPOS, 0, 0) as i32
+    }
+
+    /// Sets the current position of the progress bar, returning the previous one.
+    pub fn set_position(&self, position: i32) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-setpos
+        self.send(PBM_SETPOS, position as usize, 0) as i32
+    }
+
+    /// Advances the current position by the given amount, returning the previous position.
+    pub fn advance(&self, delta: i32) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-deltapos
+        self.send(PBM_DELTAPOS, delta as usize, 0) as i32
+    }
+
+    /// Gets the amount by which `step` advances the position.
+    pub fn step_size(&self) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-getstep
+        self.send(PBM_GETSTEP, 0, 0) as i32
+    }
+
+    /// Sets the amount by which `step` advances the position, 10 by default. Returns the
+    /// previous amount.
+    pub fn set_step_size(&self, step: i32) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-setstep
+        self.send(PBM_SETSTEP, step as usize, 0) as i32
+    }
+
+    /// Advances the current position by the step size, returning the previous position. When
+    /// the position goes past the maximum, it starts over from the minimum.
+    pub fn step(&self) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-stepit
+        self.send(PBM_STEPIT, 0, 0) as i32
+    }
+
+    /// Starts the animation of a progress bar with the `Marquee` style, updating it after each
+    /// interval (30 milliseconds if `None`).
+    pub fn start_marquee(&self, interval: Option<Duration>) {
+        let interval = interval.map_or(0, |interval| interval.as_millis() as isize);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-setmarquee
+        self.send(PBM_SETMARQUEE, TRUE as usize, interval);
+    }
+
+    /// Stops the animation of a progress bar with the `Marquee` style.
+    pub fn stop_marquee(&self) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-setmarquee
+        self.send(PBM_SETMARQUEE, FALSE as usize, 0);
+    }
+
+    /// Gets the state of the progress bar.
+    pub fn state(&self) -> State {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-getstate
+        match self.send(PBM_GETSTATE, 0, 0) as c_int {
+            PBST_ERROR => State::Error,
+            PBST_PAUSED => State::Paused,
+            _ => State::Normal,
+        }
+    }
+
+    /// Sets the state of the progress bar, returning the previous one. This requires version 6
+    /// of the common controls.
+    pub fn set_state(&self, state: State) -> State {
+        let previous = self.state();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/pbm-setstate
+        self.send(PBM_SETSTATE, state as usize, 0);
+        previous
+    }
+}
+
+impl<'a> From<Window<'a>> for ProgressBar<'a> {
+    fn from(window: Window<'a>) -> Self {
+        ProgressBar { window }
+    }
+}
+
+impl<'a> Deref for ProgressBar<'a> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
@@ -0,0 +1,376 @@
+//! Tab controls https://docs.microsoft.com/en-us/windows/win32/controls/tab-controls.
+//!
+//! A tab control only draws the tabs. The `TabHost` takes care of showing the page of the
+//! selected tab, and of keeping the pages in the display area of the control.
+use super::{read_text, ControlStyle};
+use crate::error::ControlError;
+use crate::message::Message;
+use crate::window::{self, Show, Window};
+use crate::{class, rect, to_wide, Error, Result};
+use std::cell::RefCell;
+use std::mem;
+use std::ops::Deref;
+use std::ptr::{self, NonNull};
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{DWORD, FALSE, UINT};
+use winapi::shared::windef::{LPPOINT, RECT};
+use winapi::um::commctrl::{
+    TCIF_TEXT, TCITEMW, TCM_ADJUSTRECT, TCM_DELETEALLITEMS, TCM_DELETEITEM, TCM_GETCURSEL,
+    TCM_GETITEMCOUNT, TCM_GETITEMW, TCM_INSERTITEMW, TCM_SETCURSEL, TCM_SETITEMW, TCN_SELCHANGE,
+    TCS_BOTTOM, TCS_BUTTONS, TCS_FIXEDWIDTH, TCS_FLATBUTTONS, TCS_FOCUSNEVER,
+    TCS_FOCUSONBUTTONDOWN, TCS_FORCEICONLEFT, TCS_FORCELABELLEFT, TCS_HOTTRACK, TCS_MULTILINE,
+    TCS_RAGGEDRIGHT, TCS_SCROLLOPPOSITE, TCS_TOOLTIPS, TCS_VERTICAL,
+};
+use winapi::um::winuser::{
+    GetParent, GetWindowRect, MapWindowPoints, SendMessageW, SetWindowPos, HWND_TOP, SWP_NOACTIVATE,
+};
+
+/// Tab control styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/tab-control-styles.
+#[repr(u32)]
+pub enum Style {
+    /// Tabs appear at the bottom of the control, or at the right with `Vertical`.
+    Bottom = TCS_BOTTOM,
+
+    /// Tabs appear as buttons, and no border is drawn around the display area.
+    Buttons = TCS_BUTTONS,
+
+    /// All tabs are the same width.
+    FixedWidth = TCS_FIXEDWIDTH,
+
+    /// Selected tabs appear as being indented into the background. Only with `Buttons`.
+    FlatButtons = TCS_FLATBUTTONS,
+
+    /// The tab control never receives the input focus.
+    FocusNever = TCS_FOCUSNEVER,
+
+    /// The tab control receives the input focus when clicked.
+    FocusOnButtonDown = TCS_FOCUSONBUTTONDOWN,
+
+    /// Icons are aligned with the left edge of each fixed-width tab. Only with `FixedWidth`.
+    ForceIconLeft = TCS_FORCEICONLEFT,
+
+    /// Labels are aligned with the left edge of each fixed-width tab. Only with `FixedWidth`.
+    ForceLabelLeft = TCS_FORCELABELLEFT,
+
+    /// Tabs are highlighted when the mouse is over them.
+    HotTrack = TCS_HOTTRACK,
+
+    /// Tabs are displayed in multiple rows if needed, instead of a single row with arrows.
+    Multiline = TCS_MULTILINE,
+
+    /// Rows of tabs are not stretched to fill the width of the control.
+    RaggedRight = TCS_RAGGEDRIGHT,
+
+    /// Unneeded tabs scroll to the opposite side of the control when a tab is selected.
+    ScrollOpposite = TCS_SCROLLOPPOSITE,
+
+    /// The tab control has a tooltip control associated with it.
+    ToolTips = TCS_TOOLTIPS,
+
+    /// Tabs appear at the left side of the control, with their text displayed vertically.
+    /// Only with `Multiline`.
+    Vertical = TCS_VERTICAL,
+}
+
+#[derive(Debug)]
+pub struct TabControl<'a> {
+    window: Window<'a>,
+}
+
+/// Owns one page (a child window or modeless dialog) per tab of a tab control, and shows the
+/// page of the selected tab in the display area of the control.
+///
+/// The pages must be children of the same parent as the tab control, which should have the
+/// `ClipSiblings` style so that it doesn't draw over them. The parent has to pass its messages
+/// to `handle_message`, so that the pages follow the selection and the size of the control.
+/// The pages are destroyed when the host is dropped.
+#[derive(Debug)]
+pub struct TabHost<'a> {
+    tabs: TabControl<'a>,
+    pages: RefCell<Vec<Window<'static>>>,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl TabControl<'_> {
+    /// Creates a new tab control. The builder should at least set the parent window and the
+    /// child identifier of the control.
+    pub fn create(builder: window::Builder) -> Result<TabControl<'static>> {
+        builder
+            .create(class::tab_control(), "")
+            .map(|window| TabControl { window })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the number of tabs.
+    pub fn count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-getitemcount
+        self.send(TCM_GETITEMCOUNT, 0, 0) as usize
+    }
+
+    /// Inserts a new tab with the given text at the index, returning the index where it was
+    /// actually inserted.
+    pub fn insert(&self, index: usize, text: &str) -> Result<usize> {
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut item: TCITEMW = unsafe { mem::zeroed() };
+        item.mask = TCIF_TEXT;
+        item.pszText = text.as_mut_ptr();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-insertitem
+        match self.send(TCM_INSERTITEMW, index, &mut item as *mut TCITEMW as isize) {
+            -1 => Err(Error::Control(ControlError::Failed)),
+            index => Ok(index as usize),
+        }
+    }
+
+    /// Adds a new tab with the given text after the last one, returning its index.
+    pub fn add(&self, text: &str) -> Result<usize> {
+        self.insert(self.count(), text)
+    }
+
+    /// Removes the tab at the index.
+    pub fn remove(&self, index: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-deleteitem
+        match self.send(TCM_DELETEITEM, index, 0) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes all tabs.
+    pub fn clear(&self) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-deleteallitems
+        self.send(TCM_DELETEALLITEMS, 0, 0);
+    }
+
+    /// Gets the text of the tab at the index.
+    pub fn text(&self, index: usize) -> Result<String> {
+        read_text(|buffer| {
+            let mut item: TCITEMW = unsafe { mem::zeroed() };
+            item.mask = TCIF_TEXT;
+            item.pszText = buffer.as_mut_ptr();
+            item.cchTextMax = buffer.len() as c_int;
+            // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-getitem
+            match self.send(TCM_GETITEMW, index, &mut item as *mut TCITEMW as isize) {
+                0 => Err(Error::Control(ControlError::Failed)),
+                _ => Ok(()),
+            }
+        })
+    }
+
+    /// Renames the tab at the index.
+    pub fn set_text(&self, index: usize, text: &str) -> Result<()> {
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut item: TCITEMW = unsafe { mem::zeroed() };
+        item.mask = TCIF_TEXT;
+        item.pszText = text.as_mut_ptr();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-setitem
+        match self.send(TCM_SETITEMW, index, &mut item as *mut TCITEMW as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Gets the index of the selected tab, if any.
+    pub fn selected(&self) -> Option<usize> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-getcursel
+        match self.send(TCM_GETCURSEL, 0, 0) {
+            -1 => None,
+            index => Some(index as usize),
+        }
+    }
+
+    /// Selects the tab at the index. Unlike when the user selects a tab, no `SelectionChange`
+    /// notification is sent.
+    pub fn set_selected(&self, index: usize) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-setcursel
+        let previous = self.send(TCM_SETCURSEL, index, 0);
+        // The previous selection is returned, which is also -1 if there was none.
+        if previous == -1 && self.selected() != Some(index) {
+            Err(Error::Control(ControlError::Failed))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Gets the display area of the tab control (the area below the tabs, where the page of
+    /// the selected tab goes) in the client coordinates of its parent.
+    pub fn display_rect(&self) -> Result<rect::Rect> {
+        let mut rect = RECT {
+            left: 0,
+            top: 0,
+            right: 0,
+            bottom: 0,
+        };
+        unsafe {
+            let hwnd = self.window.hwnd_ptr();
+            if GetWindowRect(hwnd, &mut rect) == 0 {
+                return Err(Error::last_os_error());
+            }
+            // The window rectangle is in screen coordinates.
+            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-mapwindowpoints
+            MapWindowPoints(
+                ptr::null_mut(),
+                GetParent(hwnd),
+                &mut rect as *mut RECT as LPPOINT,
+                2,
+            );
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tcm-adjustrect
+        self.send(
+            TCM_ADJUSTRECT,
+            FALSE as usize,
+            &mut rect as *mut RECT as isize,
+        );
+        Ok(rect::Rect(rect))
+    }
+}
+
+impl<'a> TabHost<'a> {
+    /// Creates a host for the pages of the tab control, which should have no tabs yet.
+    pub fn new(tabs: TabControl<'a>) -> Self {
+        TabHost {
+            tabs,
+            pages: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The tab control of the host. Tabs should only be added or removed through the host, or
+    /// the pages won't match their tabs, and adding or removing pages will fail.
+    pub fn tab_control(&self) -> &TabControl<'a> {
+        &self.tabs
+    }
+
+    /// Inserts a tab with the given text at the index, along with its page, returning the index
+    /// where it was actually inserted. The page is only shown if its tab is selected, which is
+    /// the case for the first.
+    pub fn insert_page(&self, index: usize, text: &str, page: Window<'static>) -> Result<usize> {
+        // Indices past the last tab insert after it.
+        if index.min(self.tabs.count()) > self.pages.borrow().len() {
+            return Err(Error::Control(ControlError::Failed));
+        }
+        let index = self.tabs.insert(index, text)?;
+        self.pages.borrow_mut().insert(index, page);
+        self.layout()?;
+        self.show_selected();
+        Ok(index)
+    }
+
+    /// Adds a tab with the given text after the last one, along with its page, returning its
+    /// index.
+    pub fn add_page(&self, text: &str, page: Window<'static>) -> Result<usize> {
+        self.insert_page(self.tabs.count(), text, page)
+    }
+
+    /// Removes the tab at the index, destroying its page. If it was selected, the first tab is
+    /// selected instead.
+    pub fn remove_page(&self, index: usize) -> Result<()> {
+        if index >= self.pages.borrow().len() {
+            return Err(Error::Control(ControlError::Failed));
+        }
+        self.tabs.remove(index)?;
+        let page = self.pages.borrow_mut().remove(index);
+        drop(page.destroy());
+        if self.tabs.selected().is_none() && self.tabs.count() != 0 {
+            self.tabs.set_selected(0)?;
+        }
+        self.show_selected();
+        Ok(())
+    }
+
+    /// Gets the page of the tab at the index.
+    pub fn page(&self, index: usize) -> Option<Window<'_>> {
+        let pages = self.pages.borrow();
+        let hwnd = NonNull::new(pages.get(index)?.hwnd_ptr())?;
+        Some(Window::Borrowed { hwnd })
+    }
+
+    /// Selects the tab at the index and shows its page.
+    pub fn select(&self, index: usize) -> Result<()> {
+        self.tabs.set_selected(index)?;
+        self.show_selected();
+        Ok(())
+    }
+
+    /// Shows the page of the selected tab, and hides all others.
+    pub fn show_selected(&self) {
+        let selected = self.tabs.selected();
+        for (index, page) in self.pages.borrow().iter().enumerate() {
+            let show = if Some(index) == selected {
+                Show::ShowNa
+            } else {
+                Show::Hide
+            };
+            page.set_show_state(show);
+        }
+    }
+
+    /// Moves and resizes all pages to fill the display area of the tab control, on top of it.
+    pub fn layout(&self) -> Result<()> {
+        let rect = self.tabs.display_rect()?;
+        for page in self.pages.borrow().iter() {
+            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowpos
+            let result = unsafe {
+                SetWindowPos(
+                    page.hwnd_ptr(),
+                    HWND_TOP,
+                    rect.x(),
+                    rect.y(),
+                    rect.width(),
+                    rect.height(),
+                    SWP_NOACTIVATE,
+                )
+            };
+            if result == 0 {
+                return Err(Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles the messages of the parent window that concern the pages: the `SelectionChange`
+    /// notifications of the tab control, and `Size`. The tab control must already have been
+    /// resized, so this should be called after the parent handles `Size` itself.
+    pub fn handle_message(&self, message: &Message) {
+        match message {
+            Message::Size(_) => drop(self.layout()),
+            // https://docs.microsoft.com/en-us/windows/win32/controls/tcn-selchange
+            Message::Notify(info)
+                if info.code() == TCN_SELCHANGE
+                    && info.window().map(|w| w.hwnd_ptr()) == Some(self.tabs.hwnd_ptr()) =>
+            {
+                self.show_selected()
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Drop for TabHost<'_> {
+    fn drop(&mut self) {
+        // The pages may have been destroyed along with their parent already.
+        for page in self.pages.get_mut().drain(..) {
+            drop(page.destroy());
+        }
+    }
+}
+
+impl<'a> From<Window<'a>> for TabControl<'a> {
+    fn from(window: Window<'a>) -> Self {
+        TabControl { window }
+    }
+}
+
+impl<'a> Deref for TabControl<'a> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
@@ -0,0 +1,222 @@
+//! Trackbars https://docs.microsoft.com/en-us/windows/win32/controls/trackbar-controls.
+//!
+//! Trackbars notify their parent of changes with `HorizontalScroll` or `VerticalScroll`
+//! messages (depending on their orientation), which `ScrollData::trackbar_change` decodes.
+use super::ControlStyle;
+use crate::error::ControlError;
+use crate::message::TrackbarMessage;
+use crate::window::{self, Window};
+use crate::{class, from_wide, Error, Result};
+use std::ops::Deref;
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{DWORD, TRUE, UINT};
+use winapi::um::commctrl::{
+    TBM_CLEARTICS, TBM_GETLINESIZE, TBM_GETNUMTICS, TBM_GETPAGESIZE, TBM_GETPOS, TBM_GETRANGEMAX,
+    TBM_GETRANGEMIN, TBM_SETLINESIZE, TBM_SETPAGESIZE, TBM_SETPOS, TBM_SETRANGEMAX,
+    TBM_SETRANGEMIN, TBM_SETTIC, TBM_SETTICFREQ, TBS_AUTOTICKS, TBS_BOTH, TBS_DOWNISLEFT,
+    TBS_ENABLESELRANGE, TBS_FIXEDLENGTH, TBS_NOTHUMB, TBS_NOTICKS, TBS_NOTIFYBEFOREMOVE,
+    TBS_REVERSED, TBS_TOOLTIPS, TBS_TOP, TBS_TRANSPARENTBKGND, TBS_VERT, TRACKBAR_CLASS,
+};
+use winapi::um::winuser::{GetClassNameW, SendMessageW};
+
+/// Trackbar styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/trackbar-control-styles.
+///
+/// Trackbars are horizontal, with ticks below the slider, unless stated otherwise.
+#[repr(u32)]
+pub enum Style {
+    /// Adds a tick for every increment in the range of the trackbar.
+    AutoTicks = TBS_AUTOTICKS,
+
+    /// Displays ticks on both sides of the trackbar.
+    Both = TBS_BOTH,
+
+    /// Pressing down moves the slider to the left, and pressing up to the right.
+    DownIsLeft = TBS_DOWNISLEFT,
+
+    /// Displays a selection range in the trackbar.
+    EnableSelectionRange = TBS_ENABLESELRANGE,
+
+    /// The length of the slider can be changed.
+    FixedLength = TBS_FIXEDLENGTH,
+
+    /// The trackbar has no slider.
+    NoThumb = TBS_NOTHUMB,
+
+    /// The trackbar has no ticks.
+    NoTicks = TBS_NOTICKS,
+
+    /// Notifies the parent before the slider moves, instead of after.
+    NotifyBeforeMove = TBS_NOTIFYBEFOREMOVE,
+
+    /// Smaller values are down and larger values are up, for vertical trackbars.
+    Reversed = TBS_REVERSED,
+
+    /// The trackbar shows a tooltip with the current position while the slider is dragged.
+    ToolTips = TBS_TOOLTIPS,
+
+    /// Displays ticks above the trackbar, or to the left for vertical trackbars.
+    Top = TBS_TOP,
+
+    /// The background is painted by the parent.
+    TransparentBackground = TBS_TRANSPARENTBKGND,
+
+    /// The trackbar is vertical, with ticks to the right of the slider.
+    Vertical = TBS_VERT,
+}
+
+#[derive(Debug)]
+pub struct Trackbar<'a> {
+    window: Window<'a>,
+}
+
+/// The user moved the slider of a trackbar.
+#[derive(Debug)]
+pub struct ValueChanged<'a> {
+    /// The trackbar whose slider was moved.
+    pub trackbar: Trackbar<'a>,
+    /// The new position of the slider.
+    pub position: i32,
+    /// How the slider was moved.
+    pub reason: TrackbarMessage,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl Trackbar<'_> {
+    /// Creates a new trackbar, with a range from 0 to 100. The builder should at least set the
+    /// parent window and the child identifier of the control.
+    pub fn create(builder: window::Builder) -> Result<Trackbar<'static>> {
+        builder
+            .create(class::trackbar(), "")
+            .map(|window| Trackbar { window })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Gets the current position of the slider.
+    pub fn position(&self) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-getpos
+        self.send(TBM_GETPOS, 0, 0) as i32
+    }
+
+    /// Moves the slider to the position. Positions outside the range are clamped.
+    pub fn set_position(&self, position: i32) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-setpos
+        self.send(TBM_SETPOS, TRUE as usize, position as isize);
+    }
+
+    /// Gets the minimum and maximum positions of the slider.
+    pub fn range(&self) -> (i32, i32) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-getrangemin
+        let min = self.send(TBM_GETRANGEMIN, 0, 0) as i32;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-getrangemax
+        let max = self.send(TBM_GETRANGEMAX, 0, 0) as i32;
+        (min, max)
+    }
+
+    /// Sets the minimum and maximum positions of the slider.
+    pub fn set_range(&self, min: i32, max: i32) {
+        // Unlike `TBM_SETRANGE`, these aren't limited to 16 bits.
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-setrangemin
+        self.send(TBM_SETRANGEMIN, 0, min as isize);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-setrangemax
+        self.send(TBM_SETRANGEMAX, TRUE as usize, max as isize);
+    }
+
+    /// Sets how often ticks are displayed, for trackbars with the `AutoTicks` style. For
+    /// example, a frequency of 2 displays a tick every other increment.
+    pub fn set_tick_frequency(&self, frequency: u32) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-setticfreq
+        self.send(TBM_SETTICFREQ, frequency as usize, 0);
+    }
+
+    /// Adds a tick at the position. The first and last ticks are always present.
+    pub fn add_tick(&self, position: i32) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-settic
+        match self.send(TBM_SETTIC, 0, position as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Removes all ticks but the first and last.
+    pub fn clear_ticks(&self) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-cleartics
+        self.send(TBM_CLEARTICS, TRUE as usize, 0);
+    }
+
+    /// Gets the number of ticks, including the first and last.
+    pub fn tick_count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-getnumtics
+        self.send(TBM_GETNUMTICS, 0, 0) as usize
+    }
+
+    /// Gets how much the slider moves with the page up and page down keys, or when clicking on
+    /// the channel.
+    pub fn page_size(&self) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-getpagesize
+        self.send(TBM_GETPAGESIZE, 0, 0) as i32
+    }
+
+    /// Sets how much the slider moves with the page up and page down keys, or when clicking on
+    /// the channel. Returns the previous page size.
+    pub fn set_page_size(&self, size: i32) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-setpagesize
+        self.send(TBM_SETPAGESIZE, 0, size as isize) as i32
+    }
+
+    /// Gets how much the slider moves with the arrow keys.
+    pub fn line_size(&self) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-getlinesize
+        self.send(TBM_GETLINESIZE, 0, 0) as i32
+    }
+
+    /// Sets how much the slider moves with the arrow keys, returning the previous line size.
+    pub fn set_line_size(&self, size: i32) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tbm-setlinesize
+        self.send(TBM_SETLINESIZE, 0, size as isize) as i32
+    }
+}
+
+impl<'a> ValueChanged<'a> {
+    pub(crate) fn from_scroll(window: Window<'a>, reason: TrackbarMessage) -> Option<Self> {
+        if reason == TrackbarMessage::EndTrack || !is_trackbar(&window) {
+            return None;
+        }
+        let trackbar = Trackbar { window };
+        Some(ValueChanged {
+            position: trackbar.position(),
+            trackbar,
+            reason,
+        })
+    }
+}
+
+// Scroll bars send the same messages, so the class has to be checked.
+fn is_trackbar(window: &Window) -> bool {
+    let mut name = [0u16; 32];
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getclassnamew
+    let len = unsafe { GetClassNameW(window.hwnd_ptr(), name.as_mut_ptr(), name.len() as c_int) };
+    // Class names are case-insensitive.
+    from_wide(&name[..len as usize]).eq_ignore_ascii_case(TRACKBAR_CLASS)
+}
+
+impl<'a> From<Window<'a>> for Trackbar<'a> {
+    fn from(window: Window<'a>) -> Self {
+        Trackbar { window }
+    }
+}
+
+impl<'a> Deref for Trackbar<'a> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
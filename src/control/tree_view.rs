@@ -0,0 +1,585 @@
+//! Tree views https://docs.microsoft.com/en-us/windows/win32/controls/tree-view-controls.
+use super::image_list::ImageList;
+use super::{
+    free_item_data, item_value, read_text, release_on_destroy, store_item_data, ControlStyle,
+};
+use crate::error::ControlError;
+use crate::window::{self, Window};
+use crate::{class, to_wide, Error, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop};
+use std::ops::Deref;
+use std::ptr::{self, NonNull};
+use std::rc::Rc;
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{DWORD, LPARAM, UINT, WPARAM};
+use winapi::shared::windef::{HWND, HWND__};
+use winapi::um::commctrl::{
+    HTREEITEM, INDEXTOSTATEIMAGEMASK, NMTREEVIEWW, TREEITEM, TVE_COLLAPSE, TVE_EXPAND, TVGN_CARET,
+    TVGN_CHILD, TVGN_NEXT, TVGN_PARENT, TVGN_ROOT, TVIF_CHILDREN, TVIF_IMAGE, TVIF_PARAM,
+    TVIF_SELECTEDIMAGE, TVIF_STATE, TVIF_TEXT, TVINSERTSTRUCTW, TVIS_EXPANDED, TVIS_STATEIMAGEMASK,
+    TVITEMW, TVI_FIRST, TVI_LAST, TVI_ROOT, TVI_SORT, TVM_DELETEITEM, TVM_EDITLABELW,
+    TVM_ENSUREVISIBLE, TVM_EXPAND, TVM_GETCOUNT, TVM_GETITEMSTATE, TVM_GETITEMW, TVM_GETNEXTITEM,
+    TVM_INSERTITEMW, TVM_SELECTITEM, TVM_SETIMAGELIST, TVM_SETITEMW, TVN_ITEMEXPANDINGW,
+    TVSIL_NORMAL, TVSIL_STATE, TVS_CHECKBOXES, TVS_DISABLEDRAGDROP, TVS_EDITLABELS,
+    TVS_FULLROWSELECT, TVS_HASBUTTONS, TVS_HASLINES, TVS_INFOTIP, TVS_LINESATROOT, TVS_NOHSCROLL,
+    TVS_NOSCROLL, TVS_NOTOOLTIPS, TVS_SHOWSELALWAYS, TVS_SINGLEEXPAND, TVS_TRACKSELECT,
+};
+use winapi::um::winuser::{SendMessageW, NMHDR, WM_NOTIFY};
+
+/// Tree view styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/tree-view-control-window-styles.
+#[repr(u32)]
+pub enum Style {
+    /// Enables check boxes for items.
+    CheckBoxes = TVS_CHECKBOXES,
+
+    /// Prevents the tree view from sending `BeginDrag` notifications.
+    DisableDragDrop = TVS_DISABLEDRAGDROP,
+
+    /// Item text can be edited in place.
+    EditLabels = TVS_EDITLABELS,
+
+    /// The whole row of the selected item is highlighted. Can't be used with `HasLines`.
+    FullRowSelect = TVS_FULLROWSELECT,
+
+    /// Displays plus and minus buttons next to parent items, to expand or collapse them.
+    HasButtons = TVS_HASBUTTONS,
+
+    /// Uses lines to show the hierarchy of items.
+    HasLines = TVS_HASLINES,
+
+    /// Asks the parent for the text of the tooltips of items.
+    InfoTip = TVS_INFOTIP,
+
+    /// Uses lines to link items at the root of the tree view. Ignored without `HasLines`.
+    LinesAtRoot = TVS_LINESATROOT,
+
+    /// Disables horizontal scrolling.
+    NoHorizontalScroll = TVS_NOHSCROLL,
+
+    /// Disables both horizontal and vertical scrolling.
+    NoScroll = TVS_NOSCROLL,
+
+    /// Disables tooltips.
+    NoToolTips = TVS_NOTOOLTIPS,
+
+    /// The selected item remains selected when the tree view loses focus.
+    ShowSelectionAlways = TVS_SHOWSELALWAYS,
+
+    /// Selecting an item expands it, and collapses the item that was previously selected.
+    SingleExpand = TVS_SINGLEEXPAND,
+
+    /// Enables hot tracking of the items under the mouse.
+    TrackSelect = TVS_TRACKSELECT,
+}
+
+/// Where to insert a new node among its siblings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Position {
+    First,
+    Last,
+    /// In alphabetical order.
+    Sorted,
+    /// Right after the given sibling.
+    After(Node),
+}
+
+/// Which of the image lists of a tree view to use.
+#[repr(usize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageListKind {
+    /// The images shown next to each item, both when selected and not.
+    Normal = TVSIL_NORMAL,
+    /// State images, shown to the left of the normal image.
+    State = TVSIL_STATE,
+}
+
+/// A handle to a node (or item) of a tree view. It's only valid until the node is deleted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Node(NonNull<TREEITEM>);
+
+/// A tree view whose nodes can hold a value of type `T`.
+///
+/// As with `ListBox`, the values are owned by the tree view, and dropped when their node is
+/// deleted, the tree view is cleared or the control is destroyed.
+#[derive(Debug)]
+pub struct TreeView<'a, T = ()> {
+    window: Window<'a>,
+    _data: PhantomData<T>,
+}
+
+// Called when a node of the tree view (with the given window) is about to expand.
+type ChildrenLoader = Rc<dyn Fn(NonNull<HWND__>, Node)>;
+
+thread_local! {
+    // Loaders of the tree views with lazy children, by the address of their window. They are
+    // dropped once their tree view is destroyed.
+    static CHILDREN_LOADERS: RefCell<HashMap<usize, ChildrenLoader>> =
+        RefCell::new(HashMap::new());
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl Node {
+    pub(crate) fn from_raw(item: HTREEITEM) -> Option<Self> {
+        NonNull::new(item).map(Node)
+    }
+
+    fn as_raw(self) -> HTREEITEM {
+        self.0.as_ptr()
+    }
+}
+
+impl<T: 'static> TreeView<'_, T> {
+    /// Creates a new tree view. The builder should at least set the parent window and the child
+    /// identifier of the control.
+    pub fn create(builder: window::Builder) -> Result<TreeView<'static, T>> {
+        builder
+            .create(class::tree_view(), "")
+            .map(|window| TreeView {
+                window,
+                _data: PhantomData,
+            })
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    fn send_item(&self, msg: UINT, item: &mut TVITEMW) -> Result<()> {
+        match self.send(msg, 0, item as *mut TVITEMW as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    fn item(&self, node: Node, mask: UINT) -> TVITEMW {
+        let mut item: TVITEMW = unsafe { mem::zeroed() };
+        item.mask = mask;
+        item.hItem = node.as_raw();
+        item
+    }
+
+    /// Sets the image list used for the given kind of images, returning the previous one.
+    ///
+    /// The tree view takes ownership of the image list, but unlike list views, it never
+    /// destroys it, so it's leaked unless it's replaced (and then dropped) before the tree view
+    /// is destroyed.
+    pub fn set_image_list(&self, list: ImageList, kind: ImageListKind) -> Option<ImageList> {
+        let list = ManuallyDrop::new(list);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-setimagelist
+        let previous = self.send(TVM_SETIMAGELIST, kind as usize, list.list.as_ptr() as isize);
+        NonNull::new(previous as *mut _).map(|list| ImageList { list })
+    }
+
+    /// Gets the number of nodes in the tree view.
+    pub fn count(&self) -> usize {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-getcount
+        self.send(TVM_GETCOUNT, 0, 0) as usize
+    }
+
+    /// Inserts a node with the given text as a child of `parent` (or at the root, if it's
+    /// `None`), at the given position among its siblings.
+    pub fn insert(&self, parent: Option<Node>, position: Position, text: &str) -> Result<Node> {
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut insert: TVINSERTSTRUCTW = unsafe { mem::zeroed() };
+        insert.hParent = parent.map_or(TVI_ROOT, Node::as_raw);
+        insert.hInsertAfter = match position {
+            Position::First => TVI_FIRST,
+            Position::Last => TVI_LAST,
+            Position::Sorted => TVI_SORT,
+            Position::After(sibling) => sibling.as_raw(),
+        };
+        unsafe {
+            let item = insert.u.item_mut();
+            item.mask = TVIF_TEXT;
+            item.pszText = text.as_mut_ptr();
+        }
+        self.insert_raw(&mut insert)
+    }
+
+    fn insert_raw(&self, insert: &mut TVINSERTSTRUCTW) -> Result<Node> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-insertitem
+        let result = self.send(TVM_INSERTITEMW, 0, insert as *mut _ as isize);
+        Node::from_raw(result as HTREEITEM).ok_or(Error::Control(ControlError::Failed))
+    }
+
+    /// Adds a node with the given text as the last child of `parent` (or at the root, if it's
+    /// `None`).
+    pub fn add(&self, parent: Option<Node>, text: &str) -> Result<Node> {
+        self.insert(parent, Position::Last, text)
+    }
+
+    /// Adds a node like `add`, and attaches the value to it.
+    pub fn add_with_data(&self, parent: Option<Node>, text: &str, data: T) -> Result<Node> {
+        let node = self.add(parent, text)?;
+        self.set_item_data(node, data)?;
+        Ok(node)
+    }
+
+    /// Deletes the node and all of its descendants, dropping their values.
+    pub fn delete(&self, node: Node) -> Result<()> {
+        let mut keys = Vec::new();
+        self.collect_keys(node, &mut keys);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-deleteitem
+        match self.send(TVM_DELETEITEM, 0, node.as_raw() as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => {
+                keys.into_iter()
+                    .for_each(|key| free_item_data(self.window.hwnd_ptr(), key));
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes all nodes from the tree view, dropping their values.
+    pub fn clear(&self) {
+        for node in self.children(None) {
+            let mut keys = Vec::new();
+            self.collect_keys(node, &mut keys);
+            keys.into_iter()
+                .for_each(|key| free_item_data(self.window.hwnd_ptr(), key));
+        }
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-deleteitem
+        self.send(TVM_DELETEITEM, 0, TVI_ROOT as isize);
+    }
+
+    fn collect_keys(&self, node: Node, keys: &mut Vec<isize>) {
+        if let Ok(key) = self.item_key(node) {
+            keys.push(key);
+        }
+        for child in self.children(Some(node)) {
+            self.collect_keys(child, keys);
+        }
+    }
+
+    /// Moves the node, along with its descendants and their values, to be a child of `parent`
+    /// (or at the root, if it's `None`) at the given position. Tree views can't move nodes, so
+    /// the node is copied and the original deleted, which means its handle changes. The new
+    /// handle is returned. If the copy fails, whatever was copied is deleted and the original
+    /// node is kept.
+    pub fn move_node(&self, node: Node, parent: Option<Node>, position: Position) -> Result<Node> {
+        // Moving a node inside itself would copy it forever.
+        let mut ancestor = parent;
+        while let Some(current) = ancestor {
+            if current == node {
+                return Err(Error::Control(ControlError::Failed));
+            }
+            ancestor = self.parent(current);
+        }
+
+        let new_node = self.copy_node(node, parent, position)?;
+        // The values now belong to the copy, so they must not be dropped.
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-deleteitem
+        self.send(TVM_DELETEITEM, 0, node.as_raw() as isize);
+        Ok(new_node)
+    }
+
+    fn copy_node(&self, node: Node, parent: Option<Node>, position: Position) -> Result<Node> {
+        let mut text = to_wide(&self.text(node))?.into_vec_with_nul();
+
+        let mut item = self.item(
+            node,
+            TVIF_IMAGE | TVIF_SELECTEDIMAGE | TVIF_PARAM | TVIF_STATE | TVIF_CHILDREN,
+        );
+        item.stateMask = !0;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-getitem
+        self.send_item(TVM_GETITEMW, &mut item)?;
+        item.mask |= TVIF_TEXT;
+        item.hItem = ptr::null_mut();
+        item.pszText = text.as_mut_ptr();
+
+        let mut insert: TVINSERTSTRUCTW = unsafe { mem::zeroed() };
+        insert.hParent = parent.map_or(TVI_ROOT, Node::as_raw);
+        insert.hInsertAfter = match position {
+            Position::First => TVI_FIRST,
+            Position::Last => TVI_LAST,
+            Position::Sorted => TVI_SORT,
+            Position::After(sibling) => sibling.as_raw(),
+        };
+        unsafe {
+            *insert.u.item_mut() = item;
+        }
+        let new_node = self.insert_raw(&mut insert)?;
+
+        for child in self.children(Some(node)) {
+            if let Err(e) = self.copy_node(child, Some(new_node), Position::Last) {
+                // The values still belong to the original, so only the partial copy is deleted.
+                // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-deleteitem
+                self.send(TVM_DELETEITEM, 0, new_node.as_raw() as isize);
+                return Err(e);
+            }
+        }
+        Ok(new_node)
+    }
+
+    /// Gets the text of the node.
+    pub fn text(&self, node: Node) -> String {
+        let text = read_text(|buffer| {
+            let mut item = self.item(node, TVIF_TEXT);
+            item.pszText = buffer.as_mut_ptr();
+            item.cchTextMax = buffer.len() as c_int;
+            // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-getitem
+            self.send_item(TVM_GETITEMW, &mut item)
+        });
+        text.unwrap_or_default()
+    }
+
+    /// Sets the text of the node.
+    pub fn set_text(&self, node: Node, text: &str) -> Result<()> {
+        let mut text = to_wide(text)?.into_vec_with_nul();
+        let mut item = self.item(node, TVIF_TEXT);
+        item.pszText = text.as_mut_ptr();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-setitem
+        self.send_item(TVM_SETITEMW, &mut item)
+    }
+
+    /// Sets the index of the images, in the image list of the tree view, shown next to the node
+    /// when it's not selected and when it is.
+    pub fn set_image(&self, node: Node, image: usize, selected_image: usize) -> Result<()> {
+        let mut item = self.item(node, TVIF_IMAGE | TVIF_SELECTEDIMAGE);
+        item.iImage = image as c_int;
+        item.iSelectedImage = selected_image as c_int;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-setitem
+        self.send_item(TVM_SETITEMW, &mut item)
+    }
+
+    fn item_key(&self, node: Node) -> Result<isize> {
+        let mut item = self.item(node, TVIF_PARAM);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-getitem
+        self.send_item(TVM_GETITEMW, &mut item)?;
+        Ok(item.lParam)
+    }
+
+    /// Calls `f` with the value attached to the node, if any, and returns its result.
+    pub fn with_item_data<R, F>(&self, node: Node, f: F) -> Result<Option<R>>
+    where
+        F: FnOnce(&T) -> R,
+    {
+        self.item_key(node)
+            .map(|key| item_value(self.window.hwnd_ptr(), key).map(|value| f(&value)))
+    }
+
+    /// Attaches the value to the node, dropping the previous one.
+    pub fn set_item_data(&self, node: Node, data: T) -> Result<()> {
+        let old_key = self.item_key(node)?;
+        let mut item = self.item(node, TVIF_PARAM);
+        item.lParam = store_item_data(self.window.hwnd_ptr(), data);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-setitem
+        match self.send_item(TVM_SETITEMW, &mut item) {
+            Ok(()) => {
+                free_item_data(self.window.hwnd_ptr(), old_key);
+                Ok(())
+            }
+            Err(e) => {
+                free_item_data(self.window.hwnd_ptr(), item.lParam);
+                Err(e)
+            }
+        }
+    }
+
+    // Navigation.
+
+    fn next_node(&self, node: Option<Node>, relation: WPARAM) -> Option<Node> {
+        let node = node.map_or(ptr::null_mut(), Node::as_raw);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-getnextitem
+        Node::from_raw(self.send(TVM_GETNEXTITEM, relation, node as isize) as HTREEITEM)
+    }
+
+    /// Gets the parent of the node, or `None` if it's at the root.
+    pub fn parent(&self, node: Node) -> Option<Node> {
+        self.next_node(Some(node), TVGN_PARENT)
+    }
+
+    /// Gets the first child of `parent` (or the first node at the root, if it's `None`).
+    pub fn first_child(&self, parent: Option<Node>) -> Option<Node> {
+        match parent {
+            Some(parent) => self.next_node(Some(parent), TVGN_CHILD),
+            None => self.next_node(None, TVGN_ROOT),
+        }
+    }
+
+    /// Gets the sibling that follows the node.
+    pub fn next_sibling(&self, node: Node) -> Option<Node> {
+        self.next_node(Some(node), TVGN_NEXT)
+    }
+
+    /// Gets the children of `parent` (or the nodes at the root, if it's `None`), in order.
+    pub fn children(&self, parent: Option<Node>) -> Vec<Node> {
+        let mut children = Vec::new();
+        let mut child = self.first_child(parent);
+        while let Some(node) = child {
+            children.push(node);
+            child = self.next_sibling(node);
+        }
+        children
+    }
+
+    // Expansion.
+
+    /// Whether the children of the node are shown.
+    pub fn is_expanded(&self, node: Node) -> bool {
+        self.state(node, TVIS_EXPANDED) != 0
+    }
+
+    /// Shows or hides the children of the node. Nothing happens if the node has no children.
+    pub fn set_expanded(&self, node: Node, expanded: bool) {
+        let action = if expanded { TVE_EXPAND } else { TVE_COLLAPSE };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-expand
+        self.send(TVM_EXPAND, action, node.as_raw() as isize);
+    }
+
+    /// Sets whether the node shows a button to expand it, even if it has no children yet.
+    /// Used along with `set_children_loader`.
+    pub fn set_has_children(&self, node: Node, has_children: bool) -> Result<()> {
+        let mut item = self.item(node, TVIF_CHILDREN);
+        item.cChildren = has_children as c_int;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-setitem
+        self.send_item(TVM_SETITEMW, &mut item)
+    }
+
+    /// Loads the children of nodes on demand. The first time a node without children is about
+    /// to expand, the loader is called to add them. Nodes start without a button to expand
+    /// them, so `set_has_children` should be used on those that may have children.
+    ///
+    /// If the loader adds no children, the button is removed. As with `ListView::set_virtual_data`,
+    /// the requests are answered in the parent before reaching the message callback (which
+    /// won't see the `ItemExpanding` notifications), and the loader is dropped when the tree
+    /// view is destroyed.
+    pub fn set_children_loader<F>(&self, loader: F)
+    where
+        F: Fn(&TreeView<T>, Node) + 'static,
+    {
+        let loader: ChildrenLoader = Rc::new(move |hwnd, node| {
+            let tree: TreeView<T> = Window::Borrowed { hwnd }.into();
+            if tree.first_child(Some(node)).is_none() {
+                loader(&tree, node);
+                if tree.first_child(Some(node)).is_none() {
+                    let _ = tree.set_has_children(node, false);
+                }
+            }
+        });
+        release_on_destroy(self.window.hwnd_ptr());
+        let key = self.window.hwnd_ptr() as usize;
+        let previous = CHILDREN_LOADERS.with(|loaders| loaders.borrow_mut().insert(key, loader));
+        drop(previous);
+    }
+
+    // Selection and state.
+
+    /// Gets the selected node, if any.
+    pub fn selected(&self) -> Option<Node> {
+        self.next_node(None, TVGN_CARET)
+    }
+
+    /// Selects the node and makes it visible, or removes the selection if it's `None`.
+    pub fn set_selected(&self, node: Option<Node>) -> Result<()> {
+        let node = node.map_or(ptr::null_mut(), Node::as_raw);
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-selectitem
+        match self.send(TVM_SELECTITEM, TVGN_CARET, node as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Expands the ancestors of the node and scrolls the tree view so that it's visible.
+    pub fn ensure_visible(&self, node: Node) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-ensurevisible
+        self.send(TVM_ENSUREVISIBLE, 0, node.as_raw() as isize);
+    }
+
+    /// Begins in-place editing of the text of the node. The tree view must have the focus.
+    pub fn edit_label(&self, node: Node) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-editlabel
+        match self.send(TVM_EDITLABELW, 0, node.as_raw() as isize) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+
+    fn state(&self, node: Node, mask: UINT) -> UINT {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-getitemstate
+        self.send(TVM_GETITEMSTATE, node.as_raw() as usize, mask as isize) as UINT & mask
+    }
+
+    /// Whether the check box of the node is checked. Only for tree views with the
+    /// `CheckBoxes` style.
+    pub fn is_checked(&self, node: Node) -> bool {
+        // The state image 1 is the unchecked box, and 2 the checked one.
+        self.state(node, TVIS_STATEIMAGEMASK) == INDEXTOSTATEIMAGEMASK(2)
+    }
+
+    /// Checks or clears the check box of the node. Only for tree views with the `CheckBoxes`
+    /// style.
+    pub fn set_checked(&self, node: Node, checked: bool) -> Result<()> {
+        let mut item = self.item(node, TVIF_STATE);
+        item.stateMask = TVIS_STATEIMAGEMASK;
+        item.state = INDEXTOSTATEIMAGEMASK(if checked { 2 } else { 1 });
+        // https://docs.microsoft.com/en-us/windows/win32/controls/tvm-setitem
+        self.send_item(TVM_SETITEMW, &mut item)
+    }
+}
+
+impl<T: Clone + 'static> TreeView<'_, T> {
+    /// Gets a clone of the value attached to the node, if any.
+    pub fn item_data(&self, node: Node) -> Result<Option<T>> {
+        self.with_item_data(node, T::clone)
+    }
+}
+
+/// Loads the children of nodes that are about to expand in tree views with a loader. Returns the
+/// result of the message if it was answered.
+pub(crate) fn intercept_parent_message(msg: UINT, _: WPARAM, lparam: LPARAM) -> Option<isize> {
+    match msg {
+        WM_NOTIFY => {
+            let header = unsafe { &*(lparam as *const NMHDR) };
+            // Only the Unicode variant is sent, because the control was created with
+            // `CreateWindowExW`.
+            if header.code != TVN_ITEMEXPANDINGW {
+                return None;
+            }
+            let window = NonNull::new(header.hwndFrom)?;
+            // Cloned out, so that the loader can set the loader of other tree views.
+            let loader = CHILDREN_LOADERS
+                .with(|loaders| loaders.borrow().get(&(window.as_ptr() as usize)).cloned())?;
+            // https://docs.microsoft.com/en-us/windows/win32/controls/tvn-itemexpanding
+            let info = unsafe { &*(lparam as *const NMTREEVIEWW) };
+            if (info.action as usize & TVE_EXPAND) != 0 {
+                if let Some(node) = Node::from_raw(info.itemNew.hItem) {
+                    loader(window, node);
+                }
+            }
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+/// Forgets the loader of the tree view, which is being destroyed.
+pub(super) fn release(hwnd: HWND) {
+    let loader = CHILDREN_LOADERS.with(|loaders| loaders.borrow_mut().remove(&(hwnd as usize)));
+    drop(loader);
+}
+
+impl<'a, T> From<Window<'a>> for TreeView<'a, T> {
+    fn from(window: Window<'a>) -> Self {
+        TreeView {
+            window,
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> Deref for TreeView<'a, T> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
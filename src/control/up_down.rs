@@ -0,0 +1,192 @@
+//! Up-down controls https://docs.microsoft.com/en-us/windows/win32/controls/up-down-controls.
+//!
+//! An up-down control is a pair of arrow buttons, usually attached to an edit control (its
+//! "buddy") to increment or decrement the number it displays.
+use super::{ControlStyle, Edit};
+use crate::error::ControlError;
+use crate::window::{self, Window};
+use crate::{class, Error, Result};
+use std::ops::Deref;
+use winapi::ctypes::c_int;
+use winapi::shared::minwindef::{BOOL, DWORD, UINT};
+use winapi::um::commctrl::{
+    UDACCEL, UDM_GETBASE, UDM_GETPOS32, UDM_GETRANGE32, UDM_SETACCEL, UDM_SETBASE, UDM_SETBUDDY,
+    UDM_SETPOS32, UDM_SETRANGE32, UDS_ALIGNLEFT, UDS_ALIGNRIGHT, UDS_ARROWKEYS, UDS_AUTOBUDDY,
+    UDS_HORZ, UDS_HOTTRACK, UDS_NOTHOUSANDS, UDS_SETBUDDYINT, UDS_WRAP,
+};
+use winapi::um::winuser::SendMessageW;
+
+/// Up-down control styles as defined in https://docs.microsoft.com/en-us/windows/win32/controls/up-down-control-styles.
+#[repr(u32)]
+pub enum Style {
+    /// Places the control next to the left edge of its buddy, which is moved to the right.
+    AlignLeft = UDS_ALIGNLEFT,
+
+    /// Places the control next to the right edge of its buddy, which is made narrower.
+    AlignRight = UDS_ALIGNRIGHT,
+
+    /// The up and down arrow keys change the position while the buddy has the focus.
+    ArrowKeys = UDS_ARROWKEYS,
+
+    /// Uses the previous window in the Z order as the buddy, instead of setting one.
+    AutoBuddy = UDS_AUTOBUDDY,
+
+    /// The arrows point left and right instead of up and down.
+    Horizontal = UDS_HORZ,
+
+    /// The arrows are highlighted when the mouse is over them.
+    HotTrack = UDS_HOTTRACK,
+
+    /// No thousands separator is inserted in the text of the buddy.
+    NoThousands = UDS_NOTHOUSANDS,
+
+    /// The control sets the text of its buddy to the position when it changes.
+    SetBuddyInt = UDS_SETBUDDYINT,
+
+    /// The position wraps around when it goes past either end of the range.
+    Wrap = UDS_WRAP,
+}
+
+/// The base used to display the position in the buddy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base {
+    Decimal,
+    Hexadecimal,
+}
+
+/// How much the position changes while an arrow is held down, once it's been held for the
+/// given number of seconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Acceleration {
+    pub seconds: u32,
+    pub increment: u32,
+}
+
+#[derive(Debug)]
+pub struct UpDown<'a> {
+    window: Window<'a>,
+}
+
+impl ControlStyle for Style {
+    fn bits(self) -> DWORD {
+        self as DWORD
+    }
+}
+
+impl UpDown<'_> {
+    /// Creates a new up-down control buddied to the edit control, with a range from 0 to 100.
+    /// The builder should at least set the parent window and the child identifier of the
+    /// control. Usually, it also sets the `SetBuddyInt`, `AlignRight` and `ArrowKeys` styles.
+    pub fn create(builder: window::Builder, buddy: &Edit) -> Result<UpDown<'static>> {
+        let up_down = builder
+            .create(class::up_down(), "")
+            .map(|window| UpDown { window })?;
+        up_down.set_buddy(buddy);
+        // The default range goes from 100 to 0, which is rarely what's wanted.
+        up_down.set_range(0, 100);
+        Ok(up_down)
+    }
+
+    fn send(&self, msg: UINT, wparam: usize, lparam: isize) -> isize {
+        unsafe { SendMessageW(self.window.hwnd_ptr(), msg, wparam, lparam) }
+    }
+
+    /// Sets the edit control attached to the up-down control.
+    pub fn set_buddy(&self, buddy: &Edit) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-setbuddy
+        self.send(UDM_SETBUDDY, buddy.hwnd_ptr() as usize, 0);
+    }
+
+    /// Gets the minimum and maximum positions.
+    pub fn range(&self) -> (i32, i32) {
+        let mut min: c_int = 0;
+        let mut max: c_int = 0;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-getrange32
+        self.send(
+            UDM_GETRANGE32,
+            &mut min as *mut c_int as usize,
+            &mut max as *mut c_int as isize,
+        );
+        (min, max)
+    }
+
+    /// Sets the minimum and maximum positions. The minimum may be larger than the maximum, in
+    /// which case the up arrow decrements the position.
+    pub fn set_range(&self, min: i32, max: i32) {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-setrange32
+        self.send(UDM_SETRANGE32, min as usize, max as isize);
+    }
+
+    /// Gets the current position. Fails if the text of the buddy is not a valid position.
+    pub fn position(&self) -> Result<i32> {
+        let mut failed: BOOL = 0;
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-getpos32
+        let position = self.send(UDM_GETPOS32, 0, &mut failed as *mut BOOL as isize) as i32;
+        if failed == 0 {
+            Ok(position)
+        } else {
+            Err(Error::Control(ControlError::Failed))
+        }
+    }
+
+    /// Sets the current position, returning the previous one. Positions outside the range are
+    /// clamped.
+    pub fn set_position(&self, position: i32) -> i32 {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-setpos32
+        self.send(UDM_SETPOS32, 0, position as isize) as i32
+    }
+
+    /// Gets the base used to display the position in the buddy.
+    pub fn base(&self) -> Base {
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-getbase
+        match self.send(UDM_GETBASE, 0, 0) {
+            16 => Base::Hexadecimal,
+            _ => Base::Decimal,
+        }
+    }
+
+    /// Sets the base used to display the position in the buddy.
+    pub fn set_base(&self, base: Base) {
+        let base = match base {
+            Base::Decimal => 10,
+            Base::Hexadecimal => 16,
+        };
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-setbase
+        self.send(UDM_SETBASE, base, 0);
+    }
+
+    /// Sets how the position changes while an arrow is held down. The accelerations should be
+    /// sorted by their seconds.
+    pub fn set_acceleration(&self, acceleration: &[Acceleration]) -> Result<()> {
+        let acceleration = acceleration
+            .iter()
+            .map(|accel| UDACCEL {
+                nSec: accel.seconds,
+                nInc: accel.increment,
+            })
+            .collect::<Vec<_>>();
+        // https://docs.microsoft.com/en-us/windows/win32/controls/udm-setaccel
+        match self.send(
+            UDM_SETACCEL,
+            acceleration.len(),
+            acceleration.as_ptr() as isize,
+        ) {
+            0 => Err(Error::Control(ControlError::Failed)),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<'a> From<Window<'a>> for UpDown<'a> {
+    fn from(window: Window<'a>) -> Self {
+        UpDown { window }
+    }
+}
+
+impl<'a> Deref for UpDown<'a> {
+    type Target = Window<'a>;
+
+    fn deref(&self) -> &Window<'a> {
+        &self.window
+    }
+}
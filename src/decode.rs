@@ -0,0 +1,237 @@
+//! Decoding of the parameters packed into the `wParam` and `lParam` of window messages.
+//!
+//! This is plain bit manipulation, so unlike the messages themselves, it does not depend on the
+//! Windows API and is tested on any host. The parameters are taken as the integer types that
+//! `WPARAM` (`usize`) and `LPARAM` (`isize`) are defined as.
+
+// https://docs.microsoft.com/en-us/windows/win32/inputdev/about-keyboard-input#keystroke-message-flags
+pub(crate) fn repeat_count(lparam: isize) -> u16 {
+    (lparam & 0xFFFF) as u16
+}
+
+pub(crate) fn scan_code(lparam: isize) -> u8 {
+    ((lparam >> 16) & 0xFF) as u8
+}
+
+pub(crate) fn extended(lparam: isize) -> bool {
+    (lparam >> 24) & 1 != 0
+}
+
+pub(crate) fn context_code(lparam: isize) -> bool {
+    (lparam >> 29) & 1 != 0
+}
+
+pub(crate) fn previous_state(lparam: isize) -> bool {
+    (lparam >> 30) & 1 != 0
+}
+
+pub(crate) fn transition_state(lparam: isize) -> bool {
+    (lparam >> 31) & 1 != 0
+}
+
+// Coordinates are packed as signed words, because they can be negative in multi-monitor setups,
+// so they must be sign-extended (like `GET_X_LPARAM` and `GET_Y_LPARAM` do).
+pub(crate) fn x_coordinate(lparam: isize) -> i32 {
+    lparam as u16 as i16 as i32
+}
+
+pub(crate) fn y_coordinate(lparam: isize) -> i32 {
+    (lparam >> 16) as u16 as i16 as i32
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/inputdev/wm-mousewheel
+pub(crate) fn wheel_delta(wparam: usize) -> i16 {
+    (wparam >> 16) as u16 as i16
+}
+
+/// Assembles the characters sent by character messages.
+///
+/// Characters outside the Basic Multilingual Plane arrive in two separate messages, one for each
+/// half of the UTF-16 surrogate pair, so the first half has to be kept until the second one
+/// arrives. Only the code units of `Char` (or `SystemChar`) messages for the same window should
+/// be fed to the same decoder, in the order they arrive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CharDecoder {
+    high_surrogate: Option<u16>,
+}
+
+impl CharDecoder {
+    /// Creates a decoder with no pending surrogate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes the next UTF-16 code unit. Returns `None` for the first half of a surrogate pair,
+    /// which is kept until the second half is decoded, and for unpaired second halves. A pending
+    /// first half that is not followed by the second one is discarded.
+    pub fn decode(&mut self, unit: u16) -> Option<char> {
+        match unit {
+            0xD800..=0xDBFF => {
+                self.high_surrogate = Some(unit);
+                None
+            }
+            0xDC00..=0xDFFF => self.high_surrogate.take().and_then(|high| {
+                let high = (high as u32 - 0xD800) << 10;
+                let low = unit as u32 - 0xDC00;
+                std::char::from_u32(0x10000 + high + low)
+            }),
+            _ => {
+                self.high_surrogate = None;
+                std::char::from_u32(unit as u32)
+            }
+        }
+    }
+
+    /// Whether the first half of a surrogate pair is waiting for the second one.
+    pub fn is_pending(&self) -> bool {
+        self.high_surrogate.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A repeated keystroke for the right CTRL key with ALT held down, after it was already down.
+    const KEY_REPEAT: isize = 0x0000_0003 | 0x1D << 16 | 1 << 24 | 1 << 29 | 1 << 30;
+    // The release of the A key, with every other flag clear.
+    const KEY_RELEASE: isize = 0x0000_0001 | 0x1E << 16 | 1 << 30 | 1 << 31;
+
+    #[test]
+    fn keystroke_flags() {
+        assert_eq!(repeat_count(KEY_REPEAT), 3);
+        assert_eq!(scan_code(KEY_REPEAT), 0x1D);
+        assert!(extended(KEY_REPEAT));
+        assert!(context_code(KEY_REPEAT));
+        assert!(previous_state(KEY_REPEAT));
+        assert!(!transition_state(KEY_REPEAT));
+
+        assert_eq!(repeat_count(KEY_RELEASE), 1);
+        assert_eq!(scan_code(KEY_RELEASE), 0x1E);
+        assert!(!extended(KEY_RELEASE));
+        assert!(!context_code(KEY_RELEASE));
+        assert!(previous_state(KEY_RELEASE));
+        assert!(transition_state(KEY_RELEASE));
+    }
+
+    #[test]
+    fn keystroke_flags_ignore_upper_bits() {
+        // On 64-bit targets, the parameter is sign-extended from the 32 bits Windows sends.
+        let lparam = KEY_RELEASE as u32 as i32 as isize;
+        assert_eq!(repeat_count(lparam), 1);
+        assert_eq!(scan_code(lparam), 0x1E);
+        assert!(transition_state(lparam));
+    }
+
+    #[test]
+    fn repeat_count_uses_the_whole_word() {
+        assert_eq!(repeat_count(0xFFFF), 0xFFFF);
+        assert_eq!(scan_code(0xFFFF), 0);
+    }
+
+    // Packs the coordinates like `MAKELPARAM` does, and sign-extends the result as Windows does
+    // when it sends the message to a 64-bit process.
+    fn point(x: i16, y: i16) -> isize {
+        (x as u16 as u32 | (y as u16 as u32) << 16) as i32 as isize
+    }
+
+    #[test]
+    fn positive_coordinates() {
+        assert_eq!(x_coordinate(point(0, 0)), 0);
+        assert_eq!(x_coordinate(point(1920, 1080)), 1920);
+        assert_eq!(y_coordinate(point(1920, 1080)), 1080);
+        assert_eq!(x_coordinate(point(i16::MAX, i16::MAX)), 32767);
+        assert_eq!(y_coordinate(point(i16::MAX, i16::MAX)), 32767);
+    }
+
+    #[test]
+    fn negative_coordinates() {
+        // A monitor to the left of and above the primary one.
+        let lparam = point(-1280, -1);
+        assert_eq!(x_coordinate(lparam), -1280);
+        assert_eq!(y_coordinate(lparam), -1);
+
+        assert_eq!(x_coordinate(point(-1, 300)), -1);
+        assert_eq!(y_coordinate(point(-1, 300)), 300);
+        assert_eq!(x_coordinate(point(200, -1024)), 200);
+        assert_eq!(y_coordinate(point(200, -1024)), -1024);
+        assert_eq!(x_coordinate(point(i16::MIN, i16::MIN)), -32768);
+        assert_eq!(y_coordinate(point(i16::MIN, i16::MIN)), -32768);
+    }
+
+    #[test]
+    fn coordinates_without_sign_extension() {
+        // The same value, if the upper bits were left clear instead.
+        let lparam = point(-1280, -1) as u32 as isize;
+        assert_eq!(x_coordinate(lparam), -1280);
+        assert_eq!(y_coordinate(lparam), -1);
+    }
+
+    #[test]
+    fn wheel_delta_sign() {
+        let mk_control = 0x0008;
+        assert_eq!(wheel_delta(120 << 16 | mk_control), 120);
+        assert_eq!(
+            wheel_delta((-120i16 as u16 as usize) << 16 | mk_control),
+            -120
+        );
+        // Upper bits, if any, are ignored.
+        let wparam = ((-240i16 as u16 as u32) << 16) as i32 as isize as usize;
+        assert_eq!(wheel_delta(wparam), -240);
+    }
+
+    #[test]
+    fn basic_multilingual_plane() {
+        let mut decoder = CharDecoder::new();
+        assert_eq!(decoder.decode('a' as u16), Some('a'));
+        assert_eq!(decoder.decode('ñ' as u16), Some('ñ'));
+        assert_eq!(decoder.decode('€' as u16), Some('€'));
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn surrogate_pair() {
+        let mut units = [0; 2];
+        '😀'.encode_utf16(&mut units);
+
+        let mut decoder = CharDecoder::new();
+        assert_eq!(decoder.decode(units[0]), None);
+        assert!(decoder.is_pending());
+        assert_eq!(decoder.decode(units[1]), Some('😀'));
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn decoding_is_pure() {
+        let mut units = [0; 2];
+        '😀'.encode_utf16(&mut units);
+
+        // Decoding the same unit with separate decoders does not affect either of them.
+        let pending = {
+            let mut decoder = CharDecoder::new();
+            decoder.decode(units[0]);
+            decoder
+        };
+        let (mut first, mut second) = (pending, pending);
+        assert_eq!(first.decode(units[1]), Some('😀'));
+        assert_eq!(second.decode(units[1]), Some('😀'));
+        assert_eq!(CharDecoder::new().decode(units[1]), None);
+    }
+
+    #[test]
+    fn unpaired_surrogates() {
+        let mut decoder = CharDecoder::new();
+        assert_eq!(decoder.decode(0xDC00), None);
+        assert!(!decoder.is_pending());
+
+        // A high surrogate followed by anything but a low one is discarded.
+        assert_eq!(decoder.decode(0xD83D), None);
+        assert_eq!(decoder.decode('x' as u16), Some('x'));
+        assert_eq!(decoder.decode(0xDE00), None);
+
+        // The latest high surrogate replaces a pending one.
+        assert_eq!(decoder.decode(0xD800), None);
+        assert_eq!(decoder.decode(0xD83D), None);
+        assert_eq!(decoder.decode(0xDE00), Some('😀'));
+    }
+}
@@ -1,52 +1,176 @@
-use crate::{base_instance, window, DialogCallback, Error, Result};
-use std::ffi::CString;
+use crate::resource::{self, res, Id};
+use crate::{base_instance, class, from_wide, message, window, DialogCallback, Error, Result};
 use std::ptr;
-use winapi::shared::minwindef::MAX_PATH;
+use std::rc::Rc;
+use winapi::shared::minwindef::{LPARAM, MAX_PATH};
+use winapi::shared::winerror::ERROR_INVALID_WINDOW_HANDLE;
 use winapi::um::commdlg::{
-    GetOpenFileNameA, GetSaveFileNameA, LPOPENFILENAMEA, OFN_ALLOWMULTISELECT, OFN_CREATEPROMPT,
+    GetOpenFileNameW, GetSaveFileNameW, LPOPENFILENAMEW, OFN_ALLOWMULTISELECT, OFN_CREATEPROMPT,
     OFN_DONTADDTORECENT, OFN_ENABLEHOOK, OFN_ENABLEINCLUDENOTIFY, OFN_ENABLESIZING,
     OFN_ENABLETEMPLATE, OFN_ENABLETEMPLATEHANDLE, OFN_EXPLORER, OFN_EXTENSIONDIFFERENT,
     OFN_FILEMUSTEXIST, OFN_FORCESHOWHIDDEN, OFN_HIDEREADONLY, OFN_LONGNAMES, OFN_NOCHANGEDIR,
     OFN_NODEREFERENCELINKS, OFN_NOLONGNAMES, OFN_NONETWORKBUTTON, OFN_NOREADONLYRETURN,
     OFN_NOTESTFILECREATE, OFN_NOVALIDATE, OFN_OVERWRITEPROMPT, OFN_PATHMUSTEXIST, OFN_READONLY,
-    OFN_SHAREAWARE, OFN_SHOWHELP, OPENFILENAMEA,
+    OFN_SHAREAWARE, OFN_SHOWHELP, OPENFILENAMEW,
 };
-use winapi::um::winnt::{LPCSTR, LPSTR};
-use winapi::um::winuser::{DialogBoxParamA, MAKEINTRESOURCEA};
+use winapi::um::errhandlingapi::{GetLastError, SetLastError};
+use winapi::um::winuser::{
+    DialogBoxIndirectParamW, DialogBoxParamW, DS_3DLOOK, DS_ABSALIGN, DS_CENTER, DS_CENTERMOUSE,
+    DS_CONTEXTHELP, DS_CONTROL, DS_FIXEDSYS, DS_LOCALEDIT, DS_MODALFRAME, DS_NOFAILCREATE,
+    DS_NOIDLEMSG, DS_SETFOREGROUND, DS_SHELLFONT, DS_SYSMODAL, LPCDLGTEMPLATEW, MAKEINTRESOURCEW,
+    WS_BORDER, WS_CAPTION, WS_CHILD, WS_POPUP, WS_SYSMENU, WS_VISIBLE,
+};
+
+/// Dialog box styles as defined in https://docs.microsoft.com/en-us/windows/win32/dlgbox/dialog-box-styles.
+#[repr(u32)]
+pub enum Style {
+    /// Indicates that the coordinates of the dialog box are screen coordinates. If this style is
+    /// not specified, the coordinates are client coordinates.
+    AbsAlign = DS_ABSALIGN,
+
+    /// Centers the dialog box in the working area of the monitor that contains the owner window.
+    /// If no owner window is specified, the dialog box is centered in the working area of a
+    /// monitor determined by the system.
+    Center = DS_CENTER,
+
+    /// Centers the dialog box on the mouse cursor.
+    CenterMouse = DS_CENTERMOUSE,
+
+    /// Includes a question mark in the title bar of the dialog box. When the user clicks the
+    /// question mark, the cursor changes to a question mark with a pointer. If the user then
+    /// clicks a control in the dialog box, the control receives a `Help` message.
+    ContextHelp = DS_CONTEXTHELP,
+
+    /// Creates a dialog box that works well as a child window of another dialog box, much like a
+    /// page in a property sheet. This style allows the user to tab among the control windows of
+    /// a child dialog box, use its accelerator keys, and so on, as if it were part of the parent
+    /// dialog box.
+    Control = DS_CONTROL,
+
+    /// Causes the dialog box to use the `SYSTEM_FIXED_FONT` instead of the default `SYSTEM_FONT`.
+    /// This is a monospace font compatible with the System font in 16-bit versions of Windows
+    /// earlier than 3.0.
+    FixedSys = DS_FIXEDSYS,
+
+    /// Applies to 16-bit applications only. This style directs edit controls in the dialog box
+    /// to allocate memory from the application's data segment. Otherwise, edit controls allocate
+    /// storage from a global memory object.
+    LocalEdit = DS_LOCALEDIT,
+
+    /// Creates a dialog box with a modal dialog-box frame that can be combined with a title bar
+    /// and window menu by specifying the `Caption` and `SysMenu` window styles.
+    ModalFrame = DS_MODALFRAME,
+
+    /// Creates the dialog box even if errors occur. For example, if a child window cannot be
+    /// created or if the system cannot create a special data segment for an edit control, the
+    /// dialog box is created anyway.
+    NoFailCreate = DS_NOFAILCREATE,
+
+    /// Suppresses `EnterIdle` messages that the system would otherwise send to the owner of the
+    /// dialog box while the dialog box is displayed.
+    NoIdleMsg = DS_NOIDLEMSG,
+
+    /// Causes the system to use the `SetForegroundWindow` function to bring the dialog box to the
+    /// foreground. This style is useful for modal dialog boxes that require immediate attention
+    /// from the user regardless of whether the owner window is the foreground window.
+    SetForeground = DS_SETFOREGROUND,
+
+    /// Indicates that the dialog box should use the system font. The face of the template's font
+    /// must be set to "MS Shell Dlg".
+    ShellFont = DS_SHELLFONT,
+
+    /// This style is obsolete and is included for compatibility with 16-bit versions of Windows.
+    /// If you specify this style, the system creates the dialog box with the `TopMost` style.
+    SysModal = DS_SYSMODAL,
+
+    /// Obsolete. The system automatically applies the three-dimensional look to dialog boxes
+    /// created by applications.
+    ThreeDLook = DS_3DLOOK,
+}
+
+/// Builder to describe a dialog box in code, rather than in a resource script. Positions and
+/// sizes are in dialog units.
+pub struct Template {
+    dialog: resource::Dialog,
+}
+
+/// Builder to describe a control inside of a dialog box `Template`.
+pub struct Item {
+    control: resource::Control,
+}
 
 /// Creates a modal dialog box from a dialog box template resource. The function does not
 /// return control until the specified callback function terminates the modal dialog box
 /// by calling the `Window::end_dialog` function.
-pub fn show(resource: u16, callback: DialogCallback) -> Result<isize> {
+pub fn show<F>(resource: u16, callback: F) -> Result<isize>
+where
+    F: Fn(&window::Window, message::Message) -> isize + 'static,
+{
     let hinstance = base_instance();
-    let resource = MAKEINTRESOURCEA(resource);
+    let resource = MAKEINTRESOURCEW(resource);
 
-    // Can't know what the dialog's handle is beforehand. The special value 0 will be
-    // replaced with the right value as soon as the init dialog message arrives.
-    crate::HWND_TO_DLG_CALLBACK
-        .lock()
-        .unwrap()
-        .insert(0, callback);
+    // The dialog procedure takes its own reference to the callback when the init dialog
+    // message arrives, which is kept until the dialog is destroyed.
+    let callback: Rc<DialogCallback> = Rc::new(Box::new(callback));
 
     let result = unsafe {
-        DialogBoxParamA(
+        SetLastError(0);
+        DialogBoxParamW(
             hinstance,
             resource,
             ptr::null_mut(),
             Some(window::dlg_proc_wrapper),
-            0,
+            Rc::as_ptr(&callback) as LPARAM,
+        )
+    };
+
+    modal_result(result)
+}
+
+/// Creates a modal dialog box from a dialog box template built in memory. The function does
+/// not return control until the specified callback function terminates the modal dialog box
+/// by calling the `Window::end_dialog` function.
+pub fn show_template<F>(template: &Template, callback: F) -> Result<isize>
+where
+    F: Fn(&window::Window, message::Message) -> isize + 'static,
+{
+    let hinstance = base_instance();
+    let buffer = template.to_aligned();
+
+    let callback: Rc<DialogCallback> = Rc::new(Box::new(callback));
+
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-dialogboxindirectparamw
+    let result = unsafe {
+        SetLastError(0);
+        DialogBoxIndirectParamW(
+            hinstance,
+            buffer.as_ptr() as LPCDLGTEMPLATEW,
+            ptr::null_mut(),
+            Some(window::dlg_proc_wrapper),
+            Rc::as_ptr(&callback) as LPARAM,
         )
     };
 
-    // In the code at http://winprog.org/tutorial/dlgfaq.html, DialogBox returns 0 as well,
-    // which according to the official documentation "If the function fails because the
-    // hWndParent parameter is invalid, the return value is zero. The function returns zero
-    // in this case for compatibility with previous versions of Windows.".
-    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-dialogboxparama
-    //
-    // It seems safe to ignore 0 as there being an error.
+    modal_result(result)
+}
+
+/// Maps the value returned by `DialogBoxParam` and `DialogBoxIndirectParam`, which is the one
+/// given to `EndDialog`, or -1 if they fail. The last error must be cleared before calling them.
+///
+/// In the code at http://winprog.org/tutorial/dlgfaq.html, DialogBox returns 0 as well,
+/// which according to the official documentation "If the function fails because the
+/// hWndParent parameter is invalid, the return value is zero. The function returns zero
+/// in this case for compatibility with previous versions of Windows.".
+/// https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-dialogboxparamw
+///
+/// The dialog box may also end with 0, so that's only an error if the last error says so.
+pub(crate) fn modal_result(result: isize) -> Result<isize> {
     match result {
         -1 => Err(Error::last_os_error()),
+        0 => match unsafe { GetLastError() } {
+            ERROR_INVALID_WINDOW_HANDLE => Err(Error::from_code(ERROR_INVALID_WINDOW_HANDLE)),
+            _ => Ok(0),
+        },
         n => Ok(n),
     }
 }
@@ -204,10 +328,10 @@ pub enum OpenFileConfig {
 
 pub struct OpenFileBuilder<'a> {
     owner: &'a window::Window<'a>,
-    filter: Vec<u8>,
-    file: Vec<u8>,
+    filter: Vec<u16>,
+    file: Vec<u16>,
     flags: u32,
-    default_ext: Option<CString>,
+    default_ext: Option<Vec<u16>>,
 }
 
 impl<'a> OpenFileBuilder<'a> {
@@ -232,12 +356,12 @@ impl<'a> OpenFileBuilder<'a> {
     pub fn set_filters(mut self, filters: &[(&str, &str)]) -> Self {
         self.filter.clear();
         for &(display, pattern) in filters.into_iter() {
-            self.filter.extend(display.as_bytes().iter().copied());
-            self.filter.push(0u8);
-            self.filter.extend(pattern.as_bytes().iter().copied());
-            self.filter.push(0u8);
+            self.filter.extend(display.encode_utf16());
+            self.filter.push(0);
+            self.filter.extend(pattern.encode_utf16());
+            self.filter.push(0);
         }
-        self.filter.push(0u8);
+        self.filter.push(0);
         self
     }
 
@@ -246,7 +370,13 @@ impl<'a> OpenFileBuilder<'a> {
     /// the first three characters are appended. The string should not contain a period (.). If
     /// this member is NULL and the user fails to type an extension, no extension is appended.
     pub fn set_default_ext(mut self, extension: &str) -> Self {
-        self.default_ext = Some(CString::new(extension).unwrap());
+        // A null character would end the string anyway, so it's fine to stop there.
+        let mut extension = extension
+            .encode_utf16()
+            .take_while(|&c| c != 0)
+            .collect::<Vec<_>>();
+        extension.push(0);
+        self.default_ext = Some(extension);
         self
     }
 
@@ -256,16 +386,16 @@ impl<'a> OpenFileBuilder<'a> {
         self
     }
 
-    fn structure(&mut self) -> OPENFILENAMEA {
-        OPENFILENAMEA {
-            lStructSize: std::mem::size_of::<OPENFILENAMEA>() as u32,
+    fn structure(&mut self) -> OPENFILENAMEW {
+        OPENFILENAMEW {
+            lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
             hwndOwner: self.owner.hwnd_ptr(),
             hInstance: ptr::null_mut(),
-            lpstrFilter: self.filter.as_ptr() as LPCSTR,
+            lpstrFilter: self.filter.as_ptr(),
             lpstrCustomFilter: ptr::null_mut(),
             nMaxCustFilter: 0,
             nFilterIndex: 0,
-            lpstrFile: self.file.as_mut_ptr() as LPSTR,
+            lpstrFile: self.file.as_mut_ptr(),
             nMaxFile: MAX_PATH as u32,
             lpstrFileTitle: ptr::null_mut(),
             nMaxFileTitle: 0,
@@ -291,13 +421,11 @@ impl<'a> OpenFileBuilder<'a> {
     /// Creates an Open dialog box that lets the user specify the drive, directory, and the name
     /// of a file or set of files to be opened.
     pub fn ask_open_path(mut self) -> Option<String> {
-        // https://docs.microsoft.com/en-us/windows/win32/api/commdlg/nf-commdlg-getopenfilenamea
+        // https://docs.microsoft.com/en-us/windows/win32/api/commdlg/nf-commdlg-getopenfilenamew
         let mut buffer = self.structure();
-        let result = unsafe { GetOpenFileNameA(&mut buffer as LPOPENFILENAMEA) };
+        let result = unsafe { GetOpenFileNameW(&mut buffer as LPOPENFILENAMEW) };
         if result != 0 {
-            self.file
-                .truncate(self.file.iter().position(|&c| c == 0).unwrap_or(0));
-            Some(CString::new(self.file).unwrap().into_string().unwrap())
+            Some(from_wide(&self.file))
         } else {
             None
         }
@@ -306,15 +434,194 @@ impl<'a> OpenFileBuilder<'a> {
     /// Creates a Save dialog box that lets the user specify the drive, directory, and name of a
     /// file to save.
     pub fn ask_save_path(mut self) -> Option<String> {
-        // https://docs.microsoft.com/en-us/windows/win32/api/commdlg/nf-commdlg-getsavefilenamea
+        // https://docs.microsoft.com/en-us/windows/win32/api/commdlg/nf-commdlg-getsavefilenamew
         let mut buffer = self.structure();
-        let result = unsafe { GetSaveFileNameA(&mut buffer as LPOPENFILENAMEA) };
+        let result = unsafe { GetSaveFileNameW(&mut buffer as LPOPENFILENAMEW) };
         if result != 0 {
-            self.file
-                .truncate(self.file.iter().position(|&c| c == 0).unwrap_or(0));
-            Some(CString::new(self.file).unwrap().into_string().unwrap())
+            Some(from_wide(&self.file))
         } else {
             None
         }
     }
 }
+
+impl Template {
+    /// Adds a new window style.
+    pub fn add_style(mut self, style: window::Style) -> Self {
+        self.dialog.style |= style as u32;
+        self
+    }
+
+    /// Adds a new dialog box style.
+    pub fn add_dialog_style(mut self, style: Style) -> Self {
+        self.dialog.style |= style as u32;
+        self
+    }
+
+    /// Adds a new extended window style.
+    pub fn add_extended_style(mut self, style: window::ExtendedStyle) -> Self {
+        self.dialog.extended_style |= style as u32;
+        self
+    }
+
+    /// Position of the upper-left corner of the dialog box.
+    pub fn pos(mut self, x: i16, y: i16) -> Self {
+        self.dialog.x = x;
+        self.dialog.y = y;
+        self
+    }
+
+    /// Size of the dialog box.
+    pub fn size(mut self, width: i16, height: i16) -> Self {
+        self.dialog.width = width;
+        self.dialog.height = height;
+        self
+    }
+
+    /// Sets the text in the title bar of the dialog box, and gives it a title bar.
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.dialog.caption = caption.to_owned();
+        self.dialog.style |= WS_CAPTION;
+        self
+    }
+
+    /// Sets the font used by the text in the dialog box and its controls.
+    pub fn font(mut self, point_size: u16, face: &str) -> Self {
+        self.dialog.font = Some(resource::Font {
+            point_size,
+            weight: 0,
+            italic: false,
+            charset: 1, // DEFAULT_CHARSET
+            face: face.to_owned(),
+        });
+        self
+    }
+
+    /// Adds a new control to the dialog box. Controls are created in the order they are added,
+    /// which is also the order the TAB key moves between them.
+    pub fn add_item(mut self, item: Item) -> Self {
+        self.dialog.controls.push(item.control);
+        self
+    }
+
+    /// Serializes the template as a `DLGTEMPLATEEX` structure followed by its `DLGITEMTEMPLATEEX`
+    /// items https://docs.microsoft.com/en-us/windows/win32/dlgbox/dlgtemplateex.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        res::dialog_template(&self.dialog)
+    }
+
+    /// The template must be aligned on a `DWORD` boundary, which a buffer of bytes is not
+    /// guaranteed to be.
+    pub(crate) fn to_aligned(&self) -> Vec<u32> {
+        let bytes = self.to_bytes();
+        let mut buffer = vec![0u32; bytes.len().div_ceil(4)];
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len());
+        }
+        buffer
+    }
+}
+
+impl Item {
+    /// Adds a new window style.
+    pub fn add_style(mut self, style: window::Style) -> Self {
+        self.control.style |= style as u32;
+        self
+    }
+
+    /// Adds a style specific to the control's class, such as `BS_DEFPUSHBUTTON` for buttons.
+    pub fn add_control_style(mut self, style: u32) -> Self {
+        self.control.style |= style;
+        self
+    }
+
+    /// Adds a new extended window style.
+    pub fn add_extended_style(mut self, style: window::ExtendedStyle) -> Self {
+        self.control.extended_style |= style as u32;
+        self
+    }
+
+    /// Position of the upper-left corner of the control, relative to the dialog box.
+    pub fn pos(mut self, x: i16, y: i16) -> Self {
+        self.control.x = x;
+        self.control.y = y;
+        self
+    }
+
+    /// Size of the control.
+    pub fn size(mut self, width: i16, height: i16) -> Self {
+        self.control.width = width;
+        self.control.height = height;
+        self
+    }
+
+    /// Sets the initial text of the control.
+    pub fn text(mut self, text: &str) -> Self {
+        self.control.text = Id::from(text);
+        self
+    }
+}
+
+/// Creates a builder to describe a dialog box in code.
+///
+/// The dialog box is a pop-up window with a border and a window menu by default, like those
+/// defined in resource scripts.
+pub fn template() -> Template {
+    Template {
+        dialog: resource::Dialog {
+            extended: true,
+            style: WS_POPUP | WS_BORDER | WS_SYSMENU,
+            extended_style: 0,
+            help_id: 0,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            caption: String::new(),
+            class: None,
+            menu: None,
+            font: None,
+            controls: Vec::new(),
+        },
+    }
+}
+
+/// Creates a builder to describe a control of the given class and identifier, to be added to
+/// a dialog box `Template`.
+///
+/// The control is a visible child window by default.
+pub fn item(class: &class::Class, id: u16) -> Item {
+    Item {
+        control: resource::Control {
+            class: class_id(class),
+            text: Id::from(""),
+            id: id as u32,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            style: WS_CHILD | WS_VISIBLE,
+            extended_style: 0,
+            help_id: 0,
+        },
+    }
+}
+
+/// Templates refer to the predefined system classes by ordinal, and to any other by name.
+fn class_id(class: &class::Class) -> Id {
+    let name = match class {
+        class::Class::Owned { class_name, .. } => class_name.as_slice(),
+        class::Class::Static { class_name } => &class_name[..],
+    };
+    let name = from_wide(name);
+
+    match name.as_str() {
+        "Button" => Id::Ordinal(resource::Control::BUTTON),
+        "Edit" => Id::Ordinal(resource::Control::EDIT),
+        "Static" => Id::Ordinal(resource::Control::STATIC),
+        "ListBox" => Id::Ordinal(resource::Control::LIST_BOX),
+        "ScrollBar" => Id::Ordinal(resource::Control::SCROLL_BAR),
+        "ComboBox" => Id::Ordinal(resource::Control::COMBO_BOX),
+        _ => Id::Name(name),
+    }
+}
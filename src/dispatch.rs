@@ -0,0 +1,168 @@
+//! Running code on the thread that owns the windows from any other thread.
+//!
+//! Windows can only be used from the thread that created them, which is also the thread running
+//! their message loop. Worker threads can instead send closures to that thread through a
+//! `Dispatcher`, which posts them as messages to a hidden window, so that they run inside the
+//! message loop (including the loops of modal dialogs and message boxes).
+use crate::{ascii_to_wide, base_instance, non_null_or_err, Error, Result};
+use std::cell::Cell;
+use std::ptr;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{mpsc, Once};
+use winapi::shared::minwindef::{LPARAM, LRESULT, UINT, WPARAM};
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{
+    CreateWindowExW, DefWindowProcW, PostMessageW, RegisterClassExW, RegisterWindowMessageW,
+    HWND_MESSAGE, WNDCLASSEXW,
+};
+
+const CLASS_NAME: &[u16] = &ascii_to_wide(b"minimal-windows-gui.Dispatcher\0");
+const MESSAGE_NAME: &[u16] = &ascii_to_wide(b"minimal-windows-gui.Dispatch\0");
+
+type Task = Box<dyn FnOnce() + Send>;
+
+static REGISTER_CLASS: Once = Once::new();
+static DISPATCH_MESSAGE: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    // The hidden window is created on first use and lives for as long as the thread does.
+    static DISPATCH_WINDOW: Cell<HWND> = const { Cell::new(ptr::null_mut()) };
+}
+
+/// Handle to post closures to the thread it was obtained from. It can be cloned and sent to
+/// other threads.
+#[derive(Clone, Debug)]
+pub struct Dispatcher {
+    // `HWND` is not `Send`, but posting messages to a window is allowed from any thread.
+    hwnd: usize,
+    message: UINT,
+}
+
+/// Obtains the private message used to run the tasks, registering it if needed.
+fn dispatch_message() -> Result<UINT> {
+    match DISPATCH_MESSAGE.load(Ordering::Relaxed) {
+        0 => {
+            // The system returns the same value every time for the same name, so there is no
+            // harm if several threads register it at once.
+            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerwindowmessagew
+            let message = unsafe { RegisterWindowMessageW(MESSAGE_NAME.as_ptr()) };
+            if message == 0 {
+                return Err(Error::last_os_error());
+            }
+            DISPATCH_MESSAGE.store(message, Ordering::Relaxed);
+            Ok(message)
+        }
+        message => Ok(message),
+    }
+}
+
+/// Creates the message-only window that receives the tasks for the current thread.
+fn create_window() -> Result<HWND> {
+    REGISTER_CLASS.call_once(|| unsafe {
+        // If this fails, so will creating the window, which reports the error.
+        RegisterClassExW(&WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: 0,
+            lpfnWndProc: Some(dispatch_proc),
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: base_instance(),
+            hIcon: ptr::null_mut(),
+            hCursor: ptr::null_mut(),
+            hbrBackground: ptr::null_mut(),
+            lpszMenuName: ptr::null(),
+            lpszClassName: CLASS_NAME.as_ptr(),
+            hIconSm: ptr::null_mut(),
+        });
+    });
+
+    // https://docs.microsoft.com/en-us/windows/win32/winmsg/window-features#message-only-windows
+    let result = unsafe {
+        CreateWindowExW(
+            0,
+            CLASS_NAME.as_ptr(),
+            ptr::null(),
+            0,
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            ptr::null_mut(),
+            base_instance(),
+            ptr::null_mut(),
+        )
+    };
+    non_null_or_err(result).map(|hwnd| hwnd.as_ptr())
+}
+
+unsafe extern "system" fn dispatch_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    if msg != 0 && msg == DISPATCH_MESSAGE.load(Ordering::Relaxed) {
+        // The task was leaked when posted, so this is the only owner.
+        let task = Box::from_raw(lparam as *mut Task);
+        task();
+        0
+    } else {
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+}
+
+impl Dispatcher {
+    /// Obtains the dispatcher for the current thread. The closures posted to it will run when
+    /// this thread processes its messages, so it should be the thread running the message loop.
+    pub fn current() -> Result<Self> {
+        let message = dispatch_message()?;
+        let hwnd = DISPATCH_WINDOW.with(|window| {
+            if window.get().is_null() {
+                window.set(create_window()?);
+            }
+            Ok::<_, Error>(window.get())
+        })?;
+
+        Ok(Self {
+            hwnd: hwnd as usize,
+            message,
+        })
+    }
+
+    /// Posts the closure to run on the dispatcher's thread and returns immediately.
+    ///
+    /// Fails if the message could not be posted, such as when the dispatcher's thread has
+    /// exited. In this case, the closure is dropped without running.
+    pub fn invoke<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let task: *mut Task = Box::into_raw(Box::new(Box::new(f)));
+        let result = unsafe { PostMessageW(self.hwnd as HWND, self.message, 0, task as LPARAM) };
+
+        if result != 0 {
+            Ok(())
+        } else {
+            let error = Error::last_os_error();
+            drop(unsafe { Box::from_raw(task) });
+            Err(error)
+        }
+    }
+
+    /// Like `invoke`, but the value returned by the closure is sent back through the returned
+    /// channel once it runs. Receiving from the channel blocks until then, so this should not
+    /// be used from the dispatcher's thread.
+    pub fn call<T, F>(&self, f: F) -> Result<mpsc::Receiver<T>>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.invoke(move || {
+            // The caller may no longer be interested in the result.
+            let _ = sender.send(f());
+        })?;
+        Ok(receiver)
+    }
+}
@@ -0,0 +1,198 @@
+//! The error type used throughout the crate.
+use std::fmt;
+use std::io;
+use std::ptr;
+use widestring::NulError;
+use winapi::shared::minwindef::DWORD;
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::winbase::{
+    FormatMessageW, FORMAT_MESSAGE_FROM_SYSTEM, FORMAT_MESSAGE_IGNORE_INSERTS,
+};
+use winapi::um::winuser::{LB_ERR, LB_ERRSPACE};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A function of the Windows API failed. Contains the system error code (as returned by
+    /// `GetLastError`) and the system's description of it.
+    Os { code: u32, message: String },
+    /// A control could not perform the operation.
+    Control(ControlError),
+    /// The string can't be passed to the Windows API, because it contains a null character at
+    /// the given position (in UTF-16 code units), which would cut it short.
+    InvalidString { position: usize },
+    /// A GDI function failed. These don't report the reason, so only the function is known.
+    Gdi(&'static str),
+    /// The key combination of an accelerator could not be parsed.
+    InvalidChord(String),
+}
+
+/// Errors reported by the controls themselves, rather than the system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlError {
+    /// The operation failed, usually because an index was out of range (`LB_ERR` or `CB_ERR`).
+    Failed,
+    /// There was not enough space to store the item (`LB_ERRSPACE` or `CB_ERRSPACE`).
+    OutOfSpace,
+}
+
+impl Error {
+    /// Creates an error from the last error that occurred in the calling thread.
+    pub fn last_os_error() -> Self {
+        Self::from_code(unsafe { GetLastError() })
+    }
+
+    /// Creates an error from a system error code, retrieving its description.
+    pub fn from_code(code: u32) -> Self {
+        Error::Os {
+            code,
+            message: format_message(code),
+        }
+    }
+
+    /// The system error code, if this is an OS error.
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            Error::Os { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Obtains the system's description of an error code, without the trailing new line.
+fn format_message(code: DWORD) -> String {
+    if code == 0 {
+        // Some functions fail without setting an error code.
+        return String::from("unknown error");
+    }
+
+    let mut buffer = [0u16; 512];
+    // https://docs.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-formatmessagew
+    let len = unsafe {
+        FormatMessageW(
+            FORMAT_MESSAGE_FROM_SYSTEM | FORMAT_MESSAGE_IGNORE_INSERTS,
+            ptr::null(),
+            code,
+            0,
+            buffer.as_mut_ptr(),
+            buffer.len() as DWORD,
+            ptr::null_mut(),
+        )
+    };
+
+    if len == 0 {
+        format!("unknown error {:#x}", code)
+    } else {
+        String::from_utf16_lossy(&buffer[..len as usize])
+            .trim_end()
+            .to_owned()
+    }
+}
+
+/// Maps the result of a list box or combo box message that returns an index or count.
+pub(crate) fn control_result(result: isize) -> Result<usize> {
+    // The combo box codes (`CB_ERR` and `CB_ERRSPACE`) have the same values.
+    match result {
+        LB_ERR => Err(Error::Control(ControlError::Failed)),
+        LB_ERRSPACE => Err(Error::Control(ControlError::OutOfSpace)),
+        n => Ok(n as usize),
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Os { code, message } => write!(f, "{} (os error {})", message, code),
+            Error::Control(ControlError::Failed) => f.write_str("the control operation failed"),
+            Error::Control(ControlError::OutOfSpace) => {
+                f.write_str("the control does not have enough space")
+            }
+            Error::InvalidString { position } => {
+                write!(f, "string contains a null character at {}", position)
+            }
+            Error::Gdi(function) => write!(f, "{} failed", function),
+            Error::InvalidChord(chord) => write!(f, "invalid accelerator chord: {:?}", chord),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<NulError<u16>> for Error {
+    fn from(error: NulError<u16>) -> Self {
+        Error::InvalidString {
+            position: error.nul_position(),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Os { code, .. } => io::Error::from_raw_os_error(code as i32),
+            Error::InvalidString { .. } | Error::InvalidChord(_) => {
+                io::Error::new(io::ErrorKind::InvalidInput, error)
+            }
+            _ => io::Error::other(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use widestring::U16CString;
+    use winapi::shared::winerror::ERROR_ACCESS_DENIED;
+    use winapi::um::winuser::LB_OKAY;
+
+    #[test]
+    fn control_results() {
+        assert_eq!(control_result(LB_OKAY), Ok(0));
+        assert_eq!(control_result(3), Ok(3));
+        assert_eq!(
+            control_result(LB_ERR),
+            Err(Error::Control(ControlError::Failed))
+        );
+        assert_eq!(
+            control_result(LB_ERRSPACE),
+            Err(Error::Control(ControlError::OutOfSpace))
+        );
+    }
+
+    #[test]
+    fn nul_position() {
+        let error = U16CString::from_str("ab\0c").unwrap_err();
+        assert_eq!(Error::from(error), Error::InvalidString { position: 2 });
+
+        let error = U16CString::from_str("\0").unwrap_err();
+        assert_eq!(Error::from(error), Error::InvalidString { position: 0 });
+    }
+
+    #[test]
+    fn io_error_kinds() {
+        let os = Error::Os {
+            code: ERROR_ACCESS_DENIED,
+            message: String::from("Access is denied."),
+        };
+        let error = io::Error::from(os);
+        assert_eq!(error.raw_os_error(), Some(ERROR_ACCESS_DENIED as i32));
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+
+        let error = io::Error::from(Error::InvalidString { position: 1 });
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(error.raw_os_error(), None);
+
+        let error = io::Error::from(Error::InvalidChord(String::from("Ctrl+")));
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "invalid accelerator chord: \"Ctrl+\"");
+
+        let error = io::Error::from(Error::Control(ControlError::OutOfSpace));
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(error.to_string(), "the control does not have enough space");
+
+        let error = io::Error::from(Error::Gdi("BitBlt"));
+        assert_eq!(error.kind(), io::ErrorKind::Other);
+        assert_eq!(error.to_string(), "BitBlt failed");
+    }
+}
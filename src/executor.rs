@@ -0,0 +1,401 @@
+//! The single-threaded executor behind the `task` module.
+//!
+//! Tasks live in the thread that spawned them, and are polled by functions scheduled through a
+//! `WakeSource`, which on Windows posts them as messages to the thread's message loop. The
+//! executor itself does not depend on the Windows API, so it is tested on any host.
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread;
+
+/// Runs functions on the thread the tasks were spawned from, once it gets to process them.
+///
+/// Functions can be scheduled from any thread, but must run on the executor's thread, in the
+/// same order they were scheduled.
+pub(crate) trait WakeSource: Send + Sync + 'static {
+    type Error;
+
+    fn schedule(&self, f: Box<dyn FnOnce() + Send>) -> Result<(), Self::Error>;
+}
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    waker: Waker,
+    scheduled: Arc<AtomicBool>,
+}
+
+struct TaskWaker<S> {
+    id: usize,
+    // Set while a function to poll the task is pending, so that waking it several times before
+    // it runs only polls it once.
+    scheduled: Arc<AtomicBool>,
+    source: S,
+}
+
+thread_local! {
+    static TASKS: RefCell<HashMap<usize, Task>> = RefCell::new(HashMap::new());
+    static NEXT_TASK_ID: Cell<usize> = const { Cell::new(0) };
+    static DEFERRED: RefCell<VecDeque<Box<dyn FnOnce()>>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Future that completes with the value computed by `spawn_blocking`.
+pub struct Background<T> {
+    shared: Arc<Mutex<Slot<T>>>,
+}
+
+// The result of the background thread once it's done, and the waker of the task awaiting it.
+type Slot<T> = (Option<thread::Result<T>>, Option<Waker>);
+
+impl<S: WakeSource> Wake for TaskWaker<S> {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !self.scheduled.swap(true, Ordering::AcqRel) {
+            let id = self.id;
+            // If the function can't be scheduled the thread is gone, and the task along with it.
+            let _ = self.source.schedule(Box::new(move || poll_task(id)));
+        }
+    }
+}
+
+/// Spawns a future on the current thread, which is polled from the functions scheduled through
+/// the source: first right away, and then every time it's woken, until it completes.
+pub(crate) fn spawn<S, F>(source: S, future: F) -> Result<(), S::Error>
+where
+    S: WakeSource,
+    F: Future<Output = ()> + 'static,
+{
+    let id = NEXT_TASK_ID.with(|next| {
+        let id = next.get();
+        next.set(id.wrapping_add(1));
+        id
+    });
+
+    let scheduled = Arc::new(AtomicBool::new(true));
+    let waker = Arc::new(TaskWaker {
+        id,
+        scheduled: Arc::clone(&scheduled),
+        source,
+    });
+
+    TASKS.with(|tasks| {
+        tasks.borrow_mut().insert(
+            id,
+            Task {
+                future: Box::pin(future),
+                waker: Waker::from(Arc::clone(&waker)),
+                scheduled,
+            },
+        )
+    });
+
+    let result = waker.source.schedule(Box::new(move || poll_task(id)));
+    if result.is_err() {
+        TASKS.with(|tasks| tasks.borrow_mut().remove(&id));
+    }
+    result
+}
+
+fn poll_task(id: usize) {
+    // The task is taken out while it's polled, because polling it can spawn new tasks, or
+    // run a nested message loop (such as a modal dialog) that polls other tasks.
+    let task = TASKS.with(|tasks| tasks.borrow_mut().remove(&id));
+    if let Some(mut task) = task {
+        // Clear the flag first, so that waking the task while it's polled schedules it again.
+        task.scheduled.store(false, Ordering::Release);
+        let mut cx = Context::from_waker(&task.waker);
+        if task.future.as_mut().poll(&mut cx).is_pending() {
+            TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+        }
+    }
+}
+
+/// Runs the function from the source, rather than immediately.
+pub(crate) fn defer<S: WakeSource>(source: &S, f: Box<dyn FnOnce()>) -> Result<(), S::Error> {
+    DEFERRED.with(|deferred| deferred.borrow_mut().push_back(f));
+    let result = source.schedule(Box::new(|| {
+        // The functions run in the same order they were scheduled.
+        if let Some(f) = DEFERRED.with(|deferred| deferred.borrow_mut().pop_front()) {
+            f();
+        }
+    }));
+    if result.is_err() {
+        DEFERRED.with(|deferred| deferred.borrow_mut().pop_back());
+    }
+    result
+}
+
+/// Runs the function in a new thread, returning a future that completes with its result.
+///
+/// If the function panics, the future completes with the panic's payload instead, the same way
+/// `JoinHandle::join` does.
+pub fn spawn_blocking<T, F>(f: F) -> Background<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new((None, None::<Waker>)));
+    let result = Arc::clone(&shared);
+    thread::spawn(move || {
+        let value = panic::catch_unwind(AssertUnwindSafe(f));
+        let waker = {
+            let mut result = result.lock().unwrap();
+            result.0 = Some(value);
+            result.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    });
+
+    Background { shared }
+}
+
+impl<T> Future for Background<T> {
+    type Output = thread::Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<thread::Result<T>> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.0.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                shared.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// Stands in for the message loop: scheduled functions wait in a channel until `run`.
+    struct Queue {
+        sender: Mutex<mpsc::Sender<Box<dyn FnOnce() + Send>>>,
+    }
+
+    struct Loop {
+        receiver: mpsc::Receiver<Box<dyn FnOnce() + Send>>,
+    }
+
+    impl WakeSource for Arc<Queue> {
+        type Error = ();
+
+        fn schedule(&self, f: Box<dyn FnOnce() + Send>) -> Result<(), ()> {
+            self.sender.lock().unwrap().send(f).map_err(drop)
+        }
+    }
+
+    fn queue() -> (Arc<Queue>, Loop) {
+        let (sender, receiver) = mpsc::channel();
+        let queue = Queue {
+            sender: Mutex::new(sender),
+        };
+        (Arc::new(queue), Loop { receiver })
+    }
+
+    impl Loop {
+        /// Runs the scheduled functions until there are none left, returning how many ran.
+        fn run(&self) -> usize {
+            let mut count = 0;
+            while let Ok(f) = self.receiver.try_recv() {
+                f();
+                count += 1;
+            }
+            count
+        }
+    }
+
+    /// Future that is pending until `ready` is set, counting how many times it was polled.
+    struct Probe {
+        state: Rc<ProbeState>,
+        wake_while_polled: bool,
+    }
+
+    #[derive(Default)]
+    struct ProbeState {
+        polls: Cell<usize>,
+        ready: Cell<bool>,
+        waker: RefCell<Option<Waker>>,
+    }
+
+    fn probe(wake_while_polled: bool) -> (Probe, Rc<ProbeState>) {
+        let state = Rc::new(ProbeState::default());
+        let probe = Probe {
+            state: Rc::clone(&state),
+            wake_while_polled,
+        };
+        (probe, state)
+    }
+
+    impl Future for Probe {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            let state = &self.state;
+            state.polls.set(state.polls.get() + 1);
+            if state.ready.get() {
+                return Poll::Ready(());
+            }
+            *state.waker.borrow_mut() = Some(cx.waker().clone());
+            if self.wake_while_polled {
+                cx.waker().wake_by_ref();
+            }
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn spawned_tasks_are_polled_by_the_source() {
+        let (queue, event_loop) = queue();
+        let (probe, state) = probe(false);
+
+        spawn(queue, probe).unwrap();
+        assert_eq!(state.polls.get(), 0);
+        assert_eq!(event_loop.run(), 1);
+        assert_eq!(state.polls.get(), 1);
+
+        state.ready.set(true);
+        state.waker.take().unwrap().wake();
+        assert_eq!(event_loop.run(), 1);
+        assert_eq!(state.polls.get(), 2);
+        assert!(TASKS.with(|tasks| tasks.borrow().is_empty()));
+    }
+
+    #[test]
+    fn waking_several_times_polls_once() {
+        let (queue, event_loop) = queue();
+        let (probe, state) = probe(false);
+
+        spawn(queue, probe).unwrap();
+        event_loop.run();
+        let waker = state.waker.take().unwrap();
+        waker.wake_by_ref();
+        waker.wake_by_ref();
+        let clone = waker.clone();
+        clone.wake();
+
+        assert_eq!(event_loop.run(), 1);
+        assert_eq!(state.polls.get(), 2);
+
+        // Once polled, waking it schedules it again.
+        waker.wake_by_ref();
+        assert_eq!(event_loop.run(), 1);
+        assert_eq!(state.polls.get(), 3);
+    }
+
+    #[test]
+    fn waking_from_another_thread() {
+        let (queue, event_loop) = queue();
+        let (probe, state) = probe(false);
+
+        spawn(queue, probe).unwrap();
+        event_loop.run();
+        let waker = state.waker.take().unwrap();
+        thread::spawn(move || waker.wake()).join().unwrap();
+
+        assert_eq!(event_loop.run(), 1);
+        assert_eq!(state.polls.get(), 2);
+    }
+
+    #[test]
+    fn waking_while_polled_polls_again() {
+        let (queue, event_loop) = queue();
+        let (probe, state) = probe(true);
+
+        spawn(queue, probe).unwrap();
+        // Each poll schedules the next one, so run them one at a time.
+        for expected in 1..=3 {
+            let f = event_loop.receiver.try_recv().unwrap();
+            f();
+            assert_eq!(state.polls.get(), expected);
+        }
+
+        state.ready.set(true);
+        assert_eq!(event_loop.run(), 1);
+        assert_eq!(state.polls.get(), 4);
+        assert_eq!(event_loop.run(), 0);
+    }
+
+    #[test]
+    fn tasks_and_deferred_functions_run_in_order() {
+        let (queue, event_loop) = queue();
+        let order = Rc::new(RefCell::new(Vec::new()));
+
+        for i in 0..3 {
+            let order = Rc::clone(&order);
+            spawn(
+                Arc::clone(&queue),
+                async move { order.borrow_mut().push(i) },
+            )
+            .unwrap();
+        }
+        for i in 3..6 {
+            let order = Rc::clone(&order);
+            defer(&queue, Box::new(move || order.borrow_mut().push(i))).unwrap();
+        }
+
+        assert!(order.borrow().is_empty());
+        assert_eq!(event_loop.run(), 6);
+        assert_eq!(*order.borrow(), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn failing_to_schedule_drops_the_work() {
+        let (queue, event_loop) = queue();
+        drop(event_loop);
+
+        let (probe, state) = probe(false);
+        assert!(spawn(Arc::clone(&queue), probe).is_err());
+        assert!(defer(&queue, Box::new(|| unreachable!())).is_err());
+
+        assert_eq!(state.polls.get(), 0);
+        assert_eq!(Rc::strong_count(&state), 1);
+        assert!(TASKS.with(|tasks| tasks.borrow().is_empty()));
+        assert!(DEFERRED.with(|deferred| deferred.borrow().is_empty()));
+    }
+
+    fn block_on_background<T: 'static>(background: Background<T>) -> thread::Result<T> {
+        let (queue, event_loop) = queue();
+        let result = Rc::new(RefCell::new(None));
+        let output = Rc::clone(&result);
+        spawn(queue, async move {
+            *output.borrow_mut() = Some(background.await);
+        })
+        .unwrap();
+
+        while result.borrow().is_none() {
+            let f = event_loop
+                .receiver
+                .recv_timeout(Duration::from_secs(10))
+                .expect("background task never completed");
+            f();
+        }
+        let value = result.borrow_mut().take();
+        value.unwrap()
+    }
+
+    #[test]
+    fn background_value() {
+        let value = block_on_background(spawn_blocking(|| 6 * 7));
+        assert_eq!(value.unwrap(), 42);
+    }
+
+    #[test]
+    fn background_panic() {
+        let value = block_on_background(spawn_blocking(|| -> i32 { panic!("boom") }));
+        let payload = value.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+    }
+}
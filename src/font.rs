@@ -1,3 +1,4 @@
+use crate::{Error, Result};
 use winapi::shared::windef::HFONT;
 use winapi::um::wingdi::{GetStockObject, DEFAULT_GUI_FONT};
 
@@ -5,10 +6,10 @@ pub struct Font {
     font: HFONT,
 }
 
-pub fn get_default() -> Result<Font, ()> {
+pub fn get_default() -> Result<Font> {
     let result = unsafe { GetStockObject(DEFAULT_GUI_FONT as i32) };
     if result.is_null() {
-        Err(())
+        Err(Error::Gdi("GetStockObject"))
     } else {
         Ok(Font {
             font: result as HFONT,
@@ -1,15 +1,14 @@
 use super::{Canvas, Paint};
-use crate::{base_instance, non_null_or_err};
+use crate::{base_instance, non_null_or_err, to_wide, Error, Result};
 
-use std::ffi::CString;
 use std::mem;
 use std::ptr::{self, NonNull};
 use winapi::shared::minwindef::LPVOID;
 use winapi::shared::windef::{HBITMAP, HBITMAP__, HGDIOBJ};
-use winapi::um::wingdi::{CreateBitmap, DeleteObject, GetObjectA, BITMAP};
-use winapi::um::winnt::{HANDLE, LPCSTR};
+use winapi::um::wingdi::{CreateBitmap, DeleteObject, GetObjectW, BITMAP};
+use winapi::um::winnt::HANDLE;
 use winapi::um::winuser::{
-    LoadBitmapA, LoadImageA, IMAGE_BITMAP, LR_LOADFROMFILE, MAKEINTRESOURCEA,
+    LoadBitmapW, LoadImageW, IMAGE_BITMAP, LR_LOADFROMFILE, MAKEINTRESOURCEW,
 };
 
 #[derive(Debug)]
@@ -22,7 +21,7 @@ pub struct Info {
     _size: usize,
 }
 
-pub fn new(width: i32, height: i32, planes: u32, bit_count: u32) -> Result<Bitmap, ()> {
+pub fn new(width: i32, height: i32, planes: u32, bit_count: u32) -> Result<Bitmap> {
     // Documentation claims:
     // > This function can return the following value:
     // > ERROR_INVALID_BITMAP | The calculated size of the bitmap is less than zero.
@@ -33,22 +32,20 @@ pub fn new(width: i32, height: i32, planes: u32, bit_count: u32) -> Result<Bitma
     let result = unsafe { CreateBitmap(width, height, planes, bit_count, ptr::null()) };
     NonNull::new(result)
         .map(|bitmap| Bitmap { bitmap })
-        .ok_or(())
+        .ok_or(Error::Gdi("CreateBitmap"))
 }
 
-pub fn load(resource: u16) -> Result<Bitmap, ()> {
-    let result = unsafe { LoadBitmapA(base_instance(), MAKEINTRESOURCEA(resource)) };
-    NonNull::new(result)
-        .map(|bitmap| Bitmap { bitmap })
-        .ok_or(())
+pub fn load(resource: u16) -> Result<Bitmap> {
+    let result = unsafe { LoadBitmapW(base_instance(), MAKEINTRESOURCEW(resource)) };
+    non_null_or_err(result).map(|bitmap| Bitmap { bitmap })
 }
 
-pub fn from_file(path: &str) -> crate::Result<Bitmap> {
-    let path = CString::new(path)?;
+pub fn from_file(path: &str) -> Result<Bitmap> {
+    let path = to_wide(path)?;
     let result = unsafe {
-        LoadImageA(
+        LoadImageW(
             ptr::null_mut(),
-            path.as_ptr() as LPCSTR,
+            path.as_ptr(),
             IMAGE_BITMAP,
             0,
             0,
@@ -60,10 +57,10 @@ pub fn from_file(path: &str) -> crate::Result<Bitmap> {
 
 impl Bitmap {
     /// Retrieves information for the specified graphics object.
-    pub fn info(&self) -> Result<Info, ()> {
+    pub fn info(&self) -> Result<Info> {
         let mut info: BITMAP = unsafe { mem::zeroed() };
         let result = unsafe {
-            GetObjectA(
+            GetObjectW(
                 self.bitmap.as_ptr() as HANDLE,
                 mem::size_of::<BITMAP>() as i32,
                 &mut info as *mut BITMAP as LPVOID,
@@ -75,7 +72,7 @@ impl Bitmap {
                 _size: result as usize,
             })
         } else {
-            Err(())
+            Err(Error::Gdi("GetObject"))
         }
     }
 
@@ -84,30 +81,26 @@ impl Bitmap {
     /// This essentially tells the bitmap which color to treat as transparent.
     ///
     /// The mask used to update self is returned.
-    pub fn set_color_transparent(&self, (r, g, b): (u8, u8, u8)) -> Result<Bitmap, ()> {
-        let info = self.info().unwrap();
+    pub fn set_color_transparent(&self, (r, g, b): (u8, u8, u8)) -> Result<Bitmap> {
+        let info = self.info()?;
 
         // Create the bitmap that will hold the object mask (single plane, single bit depth).
-        let mask_bmp = new(info.width(), info.height(), 1, 1).unwrap();
+        let mask_bmp = new(info.width(), info.height(), 1, 1)?;
 
         // Create the canvas we can operate on (and drop it or the bmp will remain held).
         {
-            let masked = Canvas::from_current_screen()
-                .unwrap()
+            let masked = Canvas::from_current_screen()?
                 .bind(self)
-                .map_err(drop) // TODO remove this once it impls debug
-                .unwrap();
+                .map_err(|_| Error::Gdi("SelectObject"))?;
 
-            let mask = Canvas::from_current_screen()
-                .unwrap()
+            let mask = Canvas::from_current_screen()?
                 .bind(&mask_bmp)
-                .map_err(drop) // TODO remove this once it impls debug
-                .unwrap();
+                .map_err(|_| Error::Gdi("SelectObject"))?;
 
             // Here's where the magic happens.
-            masked.set_background((r, g, b)).unwrap();
-            mask.bitwise().set(&masked).unwrap();
-            masked.bitwise().xor(&mask).unwrap();
+            masked.set_background((r, g, b))?;
+            mask.bitwise().set(&masked)?;
+            masked.bitwise().xor(&mask)?;
         }
 
         Ok(mask_bmp)
@@ -151,8 +144,9 @@ impl Info {
 impl Drop for Bitmap {
     fn drop(&mut self) {
         let result = unsafe { DeleteObject(self.bitmap.as_ptr() as HGDIOBJ) };
-        if result == 0 {
-            panic!("failed to delete bitmap, it might still be in use");
-        }
+        debug_assert!(
+            result != 0,
+            "failed to delete bitmap, it might still be in use"
+        );
     }
 }
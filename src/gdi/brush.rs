@@ -1,3 +1,4 @@
+use crate::{Error, Result};
 use std::ptr::NonNull;
 use winapi::shared::windef::{HBRUSH, HBRUSH__, HGDIOBJ};
 use winapi::um::wingdi::{
@@ -29,17 +30,17 @@ impl Brush {
     }
 }
 
-pub fn white() -> Result<Brush, ()> {
+pub fn white() -> Result<Brush> {
     let result = unsafe { GetStockObject(WHITE_BRUSH as i32) };
     NonNull::new(result as HBRUSH)
-        .ok_or(())
+        .ok_or(Error::Gdi("GetStockObject"))
         .map(|brush| Brush { brush, stock: true })
 }
 
-pub fn light_gray() -> Result<Brush, ()> {
+pub fn light_gray() -> Result<Brush> {
     let result = unsafe { GetStockObject(LTGRAY_BRUSH as i32) };
     NonNull::new(result as HBRUSH)
-        .ok_or(())
+        .ok_or(Error::Gdi("GetStockObject"))
         .map(|brush| Brush { brush, stock: true })
 }
 
@@ -50,8 +51,6 @@ impl Drop for Brush {
         }
 
         let result = unsafe { DeleteObject(self.brush.as_ptr() as HGDIOBJ) };
-        if result == 0 {
-            panic!("invalid handle or still selected into a DC");
-        }
+        debug_assert!(result != 0, "invalid handle or still selected into a DC");
     }
 }
@@ -8,7 +8,7 @@
 //!
 //! The objects that can be used to paint on a canvas all implement the [`Canvas`] trait.
 use super::{brush, Bitmap, Paint};
-use crate::{rect, window};
+use crate::{rect, window, Error, Result};
 
 use std::fmt;
 use std::marker::PhantomData;
@@ -71,52 +71,60 @@ where
     // Canvas creation (both borrowed and owned).
 
     /// Attempt to create a new, owned version of a canvas compatible with the current screen.
-    pub fn from_current_screen() -> Result<Self, ()> {
+    pub fn from_current_screen() -> Result<Self> {
         let result = unsafe { CreateCompatibleDC(ptr::null_mut()) };
-        NonNull::new(result).ok_or(()).map(|hdc| Self {
-            hdc,
-            mode: Mode::OwnedDc,
-            selection: Selection::Default,
-        })
+        NonNull::new(result)
+            .ok_or(Error::Gdi("CreateCompatibleDC"))
+            .map(|hdc| Self {
+                hdc,
+                mode: Mode::OwnedDc,
+                selection: Selection::Default,
+            })
     }
 
     /// Attempt to create a new canvas in order to directly paint on the specified window.
-    pub fn from_window(window: &'w window::Window) -> Result<Self, ()> {
+    pub fn from_window(window: &'w window::Window) -> Result<Self> {
         let mut info = unsafe { mem::zeroed() };
         let result = unsafe { BeginPaint(window.hwnd_ptr(), &mut info) };
-        NonNull::new(result).ok_or(()).map(|hdc| Canvas {
-            hdc,
-            mode: Mode::Paint { window, info },
-            selection: Selection::Default,
-        })
+        NonNull::new(result)
+            .ok_or(Error::Gdi("BeginPaint"))
+            .map(|hdc| Canvas {
+                hdc,
+                mode: Mode::Paint { window, info },
+                selection: Selection::Default,
+            })
     }
 
     /// Attempt to create a new canvas to act as a buffer with the same settings as the window.
-    pub fn from_window_settings(window: &'w window::Window) -> Result<Self, ()> {
+    pub fn from_window_settings(window: &'w window::Window) -> Result<Self> {
         let result = unsafe { GetDC(window.hwnd_ptr()) };
-        NonNull::new(result).ok_or(()).map(|hdc| Canvas {
-            hdc,
-            mode: Mode::BorrowedDc { window },
-            selection: Selection::Default,
-        })
+        NonNull::new(result)
+            .ok_or(Error::Gdi("GetDC"))
+            .map(|hdc| Canvas {
+                hdc,
+                mode: Mode::BorrowedDc { window },
+                selection: Selection::Default,
+            })
     }
 
     /// Attempt to create a new, owned version of this canvas.
-    pub fn try_clone(&self) -> Result<Self, ()> {
+    pub fn try_clone(&self) -> Result<Self> {
         let result = unsafe { CreateCompatibleDC(self.hdc.as_ptr()) };
-        NonNull::new(result).ok_or(()).map(|hdc| Self {
-            hdc,
-            mode: Mode::OwnedDc,
-            selection: Selection::Default,
-        })
+        NonNull::new(result)
+            .ok_or(Error::Gdi("CreateCompatibleDC"))
+            .map(|hdc| Self {
+                hdc,
+                mode: Mode::OwnedDc,
+                selection: Selection::Default,
+            })
     }
 
     /// Create a new bitmap compatible with this device-context.
-    pub fn create_bitmap(&self, width: i32, height: i32) -> Result<Bitmap, ()> {
+    pub fn create_bitmap(&self, width: i32, height: i32) -> Result<Bitmap> {
         let result = unsafe { CreateCompatibleBitmap(self.hdc.as_ptr(), width, height) };
         NonNull::new(result)
             .map(|bitmap| Bitmap { bitmap })
-            .ok_or(())
+            .ok_or(Error::Gdi("CreateCompatibleBitmap"))
     }
 
     // Device capabilities.
@@ -135,21 +143,21 @@ where
 
     /// Miscellaneous painting operations.
 
-    pub fn fill_rect(&self, rect: rect::Rect, brush: brush::Brush) -> Result<(), ()> {
+    pub fn fill_rect(&self, rect: rect::Rect, brush: brush::Brush) -> Result<()> {
         let mut rect = rect.0;
         let result = unsafe { FillRect(self.hdc.as_ptr(), &mut rect as LPRECT, brush.as_ptr()) };
         if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(Error::Gdi("FillRect"))
         }
     }
 
     /// Set the background color of the device-context.
-    pub fn set_background(&self, (r, g, b): (u8, u8, u8)) -> Result<(), ()> {
+    pub fn set_background(&self, (r, g, b): (u8, u8, u8)) -> Result<()> {
         let result = unsafe { SetBkColor(self.hdc.as_ptr(), RGB(r, g, b)) };
         if result == CLR_INVALID {
-            Err(())
+            Err(Error::Gdi("SetBkColor"))
         } else {
             Ok(())
         }
@@ -164,7 +172,10 @@ where
     /// There can only be one object bound at a time, hence why this method moves the canvas.
     ///
     /// The default object may be "special", for example, painting to it may render on screen.
-    pub fn bind<'q, P>(mut self, object: &'q P) -> Result<Canvas<'w, 'q>, Canvas<'w, 'p>>
+    pub fn bind<'q, P>(
+        mut self,
+        object: &'q P,
+    ) -> std::result::Result<Canvas<'w, 'q>, Canvas<'w, 'p>>
     where
         P: Paint,
         'w: 'q,
@@ -199,7 +210,7 @@ where
     /// Bind back the default object.
     ///
     /// Does nothing if the default object was already bound.
-    pub fn bind_default(mut self) -> Result<Canvas<'w, 'static>, Canvas<'w, 'p>> {
+    pub fn bind_default(mut self) -> std::result::Result<Canvas<'w, 'static>, Canvas<'w, 'p>> {
         match self.selection {
             Selection::Default => {}
             Selection::Custom { dc_object, .. } => {
@@ -250,26 +261,26 @@ impl<'c, 'w, 'p> Bitwise<'c, 'w, 'p> {
     }
 
     /// Apply the bitwise AND operation of the given source canvas into self (the destination).
-    pub fn and(self, source: &Canvas) -> Result<(), ()> {
+    pub fn and(self, source: &Canvas) -> Result<()> {
         self.transfer(source, SRCAND)
     }
 
     /// Apply the bitwise OR operation of the given source canvas into self (the destination).
-    pub fn or(self, source: &Canvas) -> Result<(), ()> {
+    pub fn or(self, source: &Canvas) -> Result<()> {
         self.transfer(source, SRCPAINT)
     }
 
     /// Apply the bitwise XOR operation of the given source canvas into self (the destination).
-    pub fn xor(self, source: &Canvas) -> Result<(), ()> {
+    pub fn xor(self, source: &Canvas) -> Result<()> {
         self.transfer(source, SRCINVERT)
     }
 
     /// Apply the bitwise SET operation of the given source canvas into self (the destination).
-    pub fn set(self, source: &Canvas) -> Result<(), ()> {
+    pub fn set(self, source: &Canvas) -> Result<()> {
         self.transfer(source, SRCCOPY)
     }
 
-    fn transfer(self, source: &Canvas, raster_op: u32) -> Result<(), ()> {
+    fn transfer(self, source: &Canvas, raster_op: u32) -> Result<()> {
         let result = unsafe {
             BitBlt(
                 self.canvas.hdc.as_ptr(),
@@ -287,20 +298,24 @@ impl<'c, 'w, 'p> Bitwise<'c, 'w, 'p> {
         if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(Error::Gdi("BitBlt"))
         }
     }
 }
 
+// Failing to clean up is not worth a panic, so it's only reported on debug builds, and not if the
+// canvas is dropped while unwinding (when a second panic would abort).
 impl Drop for Canvas<'_, '_> {
     fn drop(&mut self) {
+        let unwinding = std::thread::panicking();
         match self.selection {
             Selection::Default => {}
             Selection::Custom { dc_object, .. } => {
                 let result = unsafe { SelectObject(self.hdc.as_ptr(), dc_object) };
-                if result.is_null() || result == HGDI_ERROR {
-                    panic!("failed to return selected object");
-                }
+                debug_assert!(
+                    unwinding || (!result.is_null() && result != HGDI_ERROR),
+                    "failed to return selected object"
+                );
             }
         }
 
@@ -313,17 +328,16 @@ impl Drop for Canvas<'_, '_> {
             Mode::Moved => 1,
         };
 
-        if result == 0 {
-            panic!(
-                "failed to drop {} canvas",
-                match self.mode {
-                    Mode::Paint { .. } => "painting",
-                    Mode::BorrowedDc { .. } => "borrowed",
-                    Mode::OwnedDc => "owned",
-                    Mode::Moved => "moved",
-                }
-            )
-        }
+        debug_assert!(
+            unwinding || result != 0,
+            "failed to drop {} canvas",
+            match self.mode {
+                Mode::Paint { .. } => "painting",
+                Mode::BorrowedDc { .. } => "borrowed",
+                Mode::OwnedDc => "owned",
+                Mode::Moved => "moved",
+            }
+        );
     }
 }
 
@@ -70,18 +70,21 @@ impl Icon {
     }
 
     pub(crate) fn load_small(&self) -> Result<NonNull<HICON__>> {
-        self.load_size(16)
+        self.load_size(16, 16)
     }
 
     pub(crate) fn load_large(&self) -> Result<NonNull<HICON__>> {
-        self.load_size(32)
+        self.load_size(32, 32)
     }
 
-    fn load_size(&self, size: i32) -> Result<NonNull<HICON__>> {
+    /// Loads the icon with the given size. Unlike the built-in icons, custom icons are loaded
+    /// anew every time, and the caller owns them (see `is_owned`).
+    pub(crate) fn load_size(&self, width: i32, height: i32) -> Result<NonNull<HICON__>> {
         match self {
             Icon::FromResource(_) => {
-                let result =
-                    unsafe { LoadImageW(base_instance(), self.value(), IMAGE_ICON, size, size, 0) };
+                let result = unsafe {
+                    LoadImageW(base_instance(), self.value(), IMAGE_ICON, width, height, 0)
+                };
 
                 let result = result as HICON;
                 non_null_or_err(result)
@@ -92,8 +95,8 @@ impl Icon {
                         ptr::null_mut(),
                         self.value(),
                         IMAGE_ICON,
-                        size,
-                        size,
+                        width,
+                        height,
                         LR_LOADFROMFILE,
                     )
                 };
@@ -104,4 +107,10 @@ impl Icon {
             _ => self.load(),
         }
     }
+
+    /// Whether the icon returned by `load_size` belongs to the caller, who should destroy it
+    /// once it's no longer needed. Built-in icons are shared, and must not be destroyed.
+    pub(crate) fn is_owned(&self) -> bool {
+        matches!(self, Icon::FromResource(_) | Icon::FromFile(_))
+    }
 }
@@ -0,0 +1,726 @@
+//! Virtual-key codes and key combinations.
+//!
+//! The codes are fixed numbers, so unlike the messages and accelerator tables that use them, this
+//! does not depend on the Windows API and is tested on any host.
+
+// https://docs.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes
+const VK_CANCEL: i32 = 0x03;
+const VK_BACK: i32 = 0x08;
+const VK_TAB: i32 = 0x09;
+const VK_CLEAR: i32 = 0x0C;
+const VK_RETURN: i32 = 0x0D;
+const VK_SHIFT: i32 = 0x10;
+const VK_CONTROL: i32 = 0x11;
+const VK_MENU: i32 = 0x12;
+const VK_PAUSE: i32 = 0x13;
+const VK_CAPITAL: i32 = 0x14;
+const VK_ESCAPE: i32 = 0x1B;
+const VK_SPACE: i32 = 0x20;
+const VK_PRIOR: i32 = 0x21;
+const VK_NEXT: i32 = 0x22;
+const VK_END: i32 = 0x23;
+const VK_HOME: i32 = 0x24;
+const VK_LEFT: i32 = 0x25;
+const VK_UP: i32 = 0x26;
+const VK_RIGHT: i32 = 0x27;
+const VK_DOWN: i32 = 0x28;
+const VK_SELECT: i32 = 0x29;
+const VK_PRINT: i32 = 0x2A;
+const VK_EXECUTE: i32 = 0x2B;
+const VK_SNAPSHOT: i32 = 0x2C;
+const VK_INSERT: i32 = 0x2D;
+const VK_DELETE: i32 = 0x2E;
+const VK_HELP: i32 = 0x2F;
+const VK_LWIN: i32 = 0x5B;
+const VK_RWIN: i32 = 0x5C;
+const VK_APPS: i32 = 0x5D;
+const VK_SLEEP: i32 = 0x5F;
+const VK_NUMPAD0: i32 = 0x60;
+const VK_NUMPAD9: i32 = 0x69;
+const VK_MULTIPLY: i32 = 0x6A;
+const VK_ADD: i32 = 0x6B;
+const VK_SEPARATOR: i32 = 0x6C;
+const VK_SUBTRACT: i32 = 0x6D;
+const VK_DECIMAL: i32 = 0x6E;
+const VK_DIVIDE: i32 = 0x6F;
+const VK_F1: i32 = 0x70;
+const VK_F24: i32 = 0x87;
+const VK_NUMLOCK: i32 = 0x90;
+const VK_SCROLL: i32 = 0x91;
+const VK_LSHIFT: i32 = 0xA0;
+const VK_RSHIFT: i32 = 0xA1;
+const VK_LCONTROL: i32 = 0xA2;
+const VK_RCONTROL: i32 = 0xA3;
+const VK_LMENU: i32 = 0xA4;
+const VK_RMENU: i32 = 0xA5;
+const VK_BROWSER_BACK: i32 = 0xA6;
+const VK_BROWSER_FORWARD: i32 = 0xA7;
+const VK_BROWSER_REFRESH: i32 = 0xA8;
+const VK_BROWSER_STOP: i32 = 0xA9;
+const VK_BROWSER_SEARCH: i32 = 0xAA;
+const VK_BROWSER_FAVORITES: i32 = 0xAB;
+const VK_BROWSER_HOME: i32 = 0xAC;
+const VK_VOLUME_MUTE: i32 = 0xAD;
+const VK_VOLUME_DOWN: i32 = 0xAE;
+const VK_VOLUME_UP: i32 = 0xAF;
+const VK_MEDIA_NEXT_TRACK: i32 = 0xB0;
+const VK_MEDIA_PREV_TRACK: i32 = 0xB1;
+const VK_MEDIA_STOP: i32 = 0xB2;
+const VK_MEDIA_PLAY_PAUSE: i32 = 0xB3;
+const VK_LAUNCH_MAIL: i32 = 0xB4;
+const VK_LAUNCH_MEDIA_SELECT: i32 = 0xB5;
+const VK_LAUNCH_APP1: i32 = 0xB6;
+const VK_LAUNCH_APP2: i32 = 0xB7;
+const VK_OEM_1: i32 = 0xBA;
+const VK_OEM_PLUS: i32 = 0xBB;
+const VK_OEM_COMMA: i32 = 0xBC;
+const VK_OEM_MINUS: i32 = 0xBD;
+const VK_OEM_PERIOD: i32 = 0xBE;
+const VK_OEM_2: i32 = 0xBF;
+const VK_OEM_3: i32 = 0xC0;
+const VK_OEM_4: i32 = 0xDB;
+const VK_OEM_5: i32 = 0xDC;
+const VK_OEM_6: i32 = 0xDD;
+const VK_OEM_7: i32 = 0xDE;
+const VK_OEM_8: i32 = 0xDF;
+const VK_OEM_102: i32 = 0xE2;
+const VK_PROCESSKEY: i32 = 0xE5;
+const VK_PACKET: i32 = 0xE7;
+
+// https://docs.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-accel
+const FVIRTKEY: u8 = 0x01;
+const FSHIFT: u8 = 0x04;
+const FCONTROL: u8 = 0x08;
+const FALT: u8 = 0x10;
+
+/// Virtual-key codes as defined in https://docs.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes.
+///
+/// Keys that produce the same code in every keyboard layout have their own variant. Keys whose
+/// code depends on the layout (such as `Oem1`) are named after the key that produces the code
+/// in the US standard keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VirtualKey {
+    /// Control-break processing.
+    Cancel,
+    Backspace,
+    Tab,
+    Clear,
+    Enter,
+    /// Either SHIFT key.
+    Shift,
+    /// Either CTRL key.
+    Control,
+    /// Either ALT key.
+    Alt,
+    Pause,
+    CapsLock,
+    Escape,
+    Space,
+    PageUp,
+    PageDown,
+    End,
+    Home,
+    Left,
+    Up,
+    Right,
+    Down,
+    Select,
+    Print,
+    Execute,
+    PrintScreen,
+    Insert,
+    Delete,
+    Help,
+    /// The '0' through '9' keys above the letters, with their digit.
+    Digit(u8),
+    /// The 'A' through 'Z' keys, with their uppercase letter.
+    Letter(char),
+    LeftWindows,
+    RightWindows,
+    /// The context menu key.
+    Applications,
+    Sleep,
+    /// The '0' through '9' keys in the numeric keypad, with their digit.
+    Numpad(u8),
+    Multiply,
+    Add,
+    Separator,
+    Subtract,
+    Decimal,
+    Divide,
+    /// The F1 through F24 keys, with their number.
+    Function(u8),
+    NumLock,
+    ScrollLock,
+    LeftShift,
+    RightShift,
+    LeftControl,
+    RightControl,
+    LeftAlt,
+    RightAlt,
+    BrowserBack,
+    BrowserForward,
+    BrowserRefresh,
+    BrowserStop,
+    BrowserSearch,
+    BrowserFavorites,
+    BrowserHome,
+    VolumeMute,
+    VolumeDown,
+    VolumeUp,
+    MediaNextTrack,
+    MediaPreviousTrack,
+    MediaStop,
+    MediaPlayPause,
+    LaunchMail,
+    LaunchMediaSelect,
+    LaunchApp1,
+    LaunchApp2,
+    /// The ';:' key in the US standard keyboard.
+    Oem1,
+    /// The '+' key in any country/region.
+    OemPlus,
+    /// The ',' key in any country/region.
+    OemComma,
+    /// The '-' key in any country/region.
+    OemMinus,
+    /// The '.' key in any country/region.
+    OemPeriod,
+    /// The '/?' key in the US standard keyboard.
+    Oem2,
+    /// The '`~' key in the US standard keyboard.
+    Oem3,
+    /// The '[{' key in the US standard keyboard.
+    Oem4,
+    /// The '\\|' key in the US standard keyboard.
+    Oem5,
+    /// The ']}' key in the US standard keyboard.
+    Oem6,
+    /// The 'single-quote/double-quote' key in the US standard keyboard.
+    Oem7,
+    Oem8,
+    /// The '<>' or '\\|' key in the RT 102-key keyboard.
+    Oem102,
+    /// The IME is processing the key.
+    ProcessKey,
+    /// The key is used to pass Unicode characters as if they were keystrokes.
+    Packet,
+    /// Any other virtual-key code.
+    Other(u8),
+}
+
+impl VirtualKey {
+    pub(crate) fn from_code(code: u8) -> Self {
+        let code = code as i32;
+        match code {
+            VK_CANCEL => VirtualKey::Cancel,
+            VK_BACK => VirtualKey::Backspace,
+            VK_TAB => VirtualKey::Tab,
+            VK_CLEAR => VirtualKey::Clear,
+            VK_RETURN => VirtualKey::Enter,
+            VK_SHIFT => VirtualKey::Shift,
+            VK_CONTROL => VirtualKey::Control,
+            VK_MENU => VirtualKey::Alt,
+            VK_PAUSE => VirtualKey::Pause,
+            VK_CAPITAL => VirtualKey::CapsLock,
+            VK_ESCAPE => VirtualKey::Escape,
+            VK_SPACE => VirtualKey::Space,
+            VK_PRIOR => VirtualKey::PageUp,
+            VK_NEXT => VirtualKey::PageDown,
+            VK_END => VirtualKey::End,
+            VK_HOME => VirtualKey::Home,
+            VK_LEFT => VirtualKey::Left,
+            VK_UP => VirtualKey::Up,
+            VK_RIGHT => VirtualKey::Right,
+            VK_DOWN => VirtualKey::Down,
+            VK_SELECT => VirtualKey::Select,
+            VK_PRINT => VirtualKey::Print,
+            VK_EXECUTE => VirtualKey::Execute,
+            VK_SNAPSHOT => VirtualKey::PrintScreen,
+            VK_INSERT => VirtualKey::Insert,
+            VK_DELETE => VirtualKey::Delete,
+            VK_HELP => VirtualKey::Help,
+            0x30..=0x39 => VirtualKey::Digit((code - 0x30) as u8),
+            0x41..=0x5A => VirtualKey::Letter(code as u8 as char),
+            VK_LWIN => VirtualKey::LeftWindows,
+            VK_RWIN => VirtualKey::RightWindows,
+            VK_APPS => VirtualKey::Applications,
+            VK_SLEEP => VirtualKey::Sleep,
+            VK_NUMPAD0..=VK_NUMPAD9 => VirtualKey::Numpad((code - VK_NUMPAD0) as u8),
+            VK_MULTIPLY => VirtualKey::Multiply,
+            VK_ADD => VirtualKey::Add,
+            VK_SEPARATOR => VirtualKey::Separator,
+            VK_SUBTRACT => VirtualKey::Subtract,
+            VK_DECIMAL => VirtualKey::Decimal,
+            VK_DIVIDE => VirtualKey::Divide,
+            VK_F1..=VK_F24 => VirtualKey::Function((code - VK_F1 + 1) as u8),
+            VK_NUMLOCK => VirtualKey::NumLock,
+            VK_SCROLL => VirtualKey::ScrollLock,
+            VK_LSHIFT => VirtualKey::LeftShift,
+            VK_RSHIFT => VirtualKey::RightShift,
+            VK_LCONTROL => VirtualKey::LeftControl,
+            VK_RCONTROL => VirtualKey::RightControl,
+            VK_LMENU => VirtualKey::LeftAlt,
+            VK_RMENU => VirtualKey::RightAlt,
+            VK_BROWSER_BACK => VirtualKey::BrowserBack,
+            VK_BROWSER_FORWARD => VirtualKey::BrowserForward,
+            VK_BROWSER_REFRESH => VirtualKey::BrowserRefresh,
+            VK_BROWSER_STOP => VirtualKey::BrowserStop,
+            VK_BROWSER_SEARCH => VirtualKey::BrowserSearch,
+            VK_BROWSER_FAVORITES => VirtualKey::BrowserFavorites,
+            VK_BROWSER_HOME => VirtualKey::BrowserHome,
+            VK_VOLUME_MUTE => VirtualKey::VolumeMute,
+            VK_VOLUME_DOWN => VirtualKey::VolumeDown,
+            VK_VOLUME_UP => VirtualKey::VolumeUp,
+            VK_MEDIA_NEXT_TRACK => VirtualKey::MediaNextTrack,
+            VK_MEDIA_PREV_TRACK => VirtualKey::MediaPreviousTrack,
+            VK_MEDIA_STOP => VirtualKey::MediaStop,
+            VK_MEDIA_PLAY_PAUSE => VirtualKey::MediaPlayPause,
+            VK_LAUNCH_MAIL => VirtualKey::LaunchMail,
+            VK_LAUNCH_MEDIA_SELECT => VirtualKey::LaunchMediaSelect,
+            VK_LAUNCH_APP1 => VirtualKey::LaunchApp1,
+            VK_LAUNCH_APP2 => VirtualKey::LaunchApp2,
+            VK_OEM_1 => VirtualKey::Oem1,
+            VK_OEM_PLUS => VirtualKey::OemPlus,
+            VK_OEM_COMMA => VirtualKey::OemComma,
+            VK_OEM_MINUS => VirtualKey::OemMinus,
+            VK_OEM_PERIOD => VirtualKey::OemPeriod,
+            VK_OEM_2 => VirtualKey::Oem2,
+            VK_OEM_3 => VirtualKey::Oem3,
+            VK_OEM_4 => VirtualKey::Oem4,
+            VK_OEM_5 => VirtualKey::Oem5,
+            VK_OEM_6 => VirtualKey::Oem6,
+            VK_OEM_7 => VirtualKey::Oem7,
+            VK_OEM_8 => VirtualKey::Oem8,
+            VK_OEM_102 => VirtualKey::Oem102,
+            VK_PROCESSKEY => VirtualKey::ProcessKey,
+            VK_PACKET => VirtualKey::Packet,
+            _ => VirtualKey::Other(code as u8),
+        }
+    }
+
+    /// The virtual-key code of the key.
+    pub fn code(self) -> u8 {
+        let code = match self {
+            VirtualKey::Cancel => VK_CANCEL,
+            VirtualKey::Backspace => VK_BACK,
+            VirtualKey::Tab => VK_TAB,
+            VirtualKey::Clear => VK_CLEAR,
+            VirtualKey::Enter => VK_RETURN,
+            VirtualKey::Shift => VK_SHIFT,
+            VirtualKey::Control => VK_CONTROL,
+            VirtualKey::Alt => VK_MENU,
+            VirtualKey::Pause => VK_PAUSE,
+            VirtualKey::CapsLock => VK_CAPITAL,
+            VirtualKey::Escape => VK_ESCAPE,
+            VirtualKey::Space => VK_SPACE,
+            VirtualKey::PageUp => VK_PRIOR,
+            VirtualKey::PageDown => VK_NEXT,
+            VirtualKey::End => VK_END,
+            VirtualKey::Home => VK_HOME,
+            VirtualKey::Left => VK_LEFT,
+            VirtualKey::Up => VK_UP,
+            VirtualKey::Right => VK_RIGHT,
+            VirtualKey::Down => VK_DOWN,
+            VirtualKey::Select => VK_SELECT,
+            VirtualKey::Print => VK_PRINT,
+            VirtualKey::Execute => VK_EXECUTE,
+            VirtualKey::PrintScreen => VK_SNAPSHOT,
+            VirtualKey::Insert => VK_INSERT,
+            VirtualKey::Delete => VK_DELETE,
+            VirtualKey::Help => VK_HELP,
+            VirtualKey::Digit(digit) => 0x30 + digit as i32,
+            VirtualKey::Letter(letter) => letter.to_ascii_uppercase() as i32,
+            VirtualKey::LeftWindows => VK_LWIN,
+            VirtualKey::RightWindows => VK_RWIN,
+            VirtualKey::Applications => VK_APPS,
+            VirtualKey::Sleep => VK_SLEEP,
+            VirtualKey::Numpad(digit) => VK_NUMPAD0 + digit as i32,
+            VirtualKey::Multiply => VK_MULTIPLY,
+            VirtualKey::Add => VK_ADD,
+            VirtualKey::Separator => VK_SEPARATOR,
+            VirtualKey::Subtract => VK_SUBTRACT,
+            VirtualKey::Decimal => VK_DECIMAL,
+            VirtualKey::Divide => VK_DIVIDE,
+            VirtualKey::Function(n) => VK_F1 + n as i32 - 1,
+            VirtualKey::NumLock => VK_NUMLOCK,
+            VirtualKey::ScrollLock => VK_SCROLL,
+            VirtualKey::LeftShift => VK_LSHIFT,
+            VirtualKey::RightShift => VK_RSHIFT,
+            VirtualKey::LeftControl => VK_LCONTROL,
+            VirtualKey::RightControl => VK_RCONTROL,
+            VirtualKey::LeftAlt => VK_LMENU,
+            VirtualKey::RightAlt => VK_RMENU,
+            VirtualKey::BrowserBack => VK_BROWSER_BACK,
+            VirtualKey::BrowserForward => VK_BROWSER_FORWARD,
+            VirtualKey::BrowserRefresh => VK_BROWSER_REFRESH,
+            VirtualKey::BrowserStop => VK_BROWSER_STOP,
+            VirtualKey::BrowserSearch => VK_BROWSER_SEARCH,
+            VirtualKey::BrowserFavorites => VK_BROWSER_FAVORITES,
+            VirtualKey::BrowserHome => VK_BROWSER_HOME,
+            VirtualKey::VolumeMute => VK_VOLUME_MUTE,
+            VirtualKey::VolumeDown => VK_VOLUME_DOWN,
+            VirtualKey::VolumeUp => VK_VOLUME_UP,
+            VirtualKey::MediaNextTrack => VK_MEDIA_NEXT_TRACK,
+            VirtualKey::MediaPreviousTrack => VK_MEDIA_PREV_TRACK,
+            VirtualKey::MediaStop => VK_MEDIA_STOP,
+            VirtualKey::MediaPlayPause => VK_MEDIA_PLAY_PAUSE,
+            VirtualKey::LaunchMail => VK_LAUNCH_MAIL,
+            VirtualKey::LaunchMediaSelect => VK_LAUNCH_MEDIA_SELECT,
+            VirtualKey::LaunchApp1 => VK_LAUNCH_APP1,
+            VirtualKey::LaunchApp2 => VK_LAUNCH_APP2,
+            VirtualKey::Oem1 => VK_OEM_1,
+            VirtualKey::OemPlus => VK_OEM_PLUS,
+            VirtualKey::OemComma => VK_OEM_COMMA,
+            VirtualKey::OemMinus => VK_OEM_MINUS,
+            VirtualKey::OemPeriod => VK_OEM_PERIOD,
+            VirtualKey::Oem2 => VK_OEM_2,
+            VirtualKey::Oem3 => VK_OEM_3,
+            VirtualKey::Oem4 => VK_OEM_4,
+            VirtualKey::Oem5 => VK_OEM_5,
+            VirtualKey::Oem6 => VK_OEM_6,
+            VirtualKey::Oem7 => VK_OEM_7,
+            VirtualKey::Oem8 => VK_OEM_8,
+            VirtualKey::Oem102 => VK_OEM_102,
+            VirtualKey::ProcessKey => VK_PROCESSKEY,
+            VirtualKey::Packet => VK_PACKET,
+            VirtualKey::Other(code) => code as i32,
+        };
+        code as u8
+    }
+}
+
+/// A key combination, such as `Ctrl+Shift+S`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub key: VirtualKey,
+    pub control: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Chord {
+    /// A key combination without modifiers.
+    pub fn new(key: VirtualKey) -> Self {
+        Self {
+            key,
+            control: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub fn control(mut self) -> Self {
+        self.control = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// The `fVirt` flags of the `ACCEL` structure for this combination.
+    pub(crate) fn accel_flags(self) -> u8 {
+        let mut flags = FVIRTKEY;
+        if self.control {
+            flags |= FCONTROL;
+        }
+        if self.shift {
+            flags |= FSHIFT;
+        }
+        if self.alt {
+            flags |= FALT;
+        }
+        flags
+    }
+
+    /// Parses a key combination as documented in the `FromStr` implementation.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        // The last character can't be a separator, which allows using `+` as the key.
+        let last = s.chars().last().map_or(0, char::len_utf8);
+        let (modifiers, key) = match s[..s.len() - last].rfind('+') {
+            Some(i) => (Some(&s[..i]), &s[i + 1..]),
+            None => (None, s),
+        };
+
+        let mut chord = Chord::new(parse_key(key.trim())?);
+        for modifier in modifiers.into_iter().flat_map(|m| m.split('+')) {
+            match modifier.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.control = true,
+                "shift" => chord.shift = true,
+                "alt" => chord.alt = true,
+                _ => return None,
+            }
+        }
+
+        Some(chord)
+    }
+}
+
+fn parse_key(key: &str) -> Option<VirtualKey> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return match c {
+            'a'..='z' | 'A'..='Z' => Some(VirtualKey::Letter(c.to_ascii_uppercase())),
+            '0'..='9' => Some(VirtualKey::Digit(c as u8 - b'0')),
+            '+' | '=' => Some(VirtualKey::OemPlus),
+            '-' => Some(VirtualKey::OemMinus),
+            ',' => Some(VirtualKey::OemComma),
+            '.' => Some(VirtualKey::OemPeriod),
+            ';' => Some(VirtualKey::Oem1),
+            '/' => Some(VirtualKey::Oem2),
+            '`' => Some(VirtualKey::Oem3),
+            '[' => Some(VirtualKey::Oem4),
+            '\\' => Some(VirtualKey::Oem5),
+            ']' => Some(VirtualKey::Oem6),
+            '\'' => Some(VirtualKey::Oem7),
+            _ => None,
+        };
+    }
+
+    let key = key.to_ascii_lowercase();
+    let numbered = |prefix: &str, range: std::ops::RangeInclusive<u8>| {
+        key.strip_prefix(prefix)
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|n| range.contains(n))
+    };
+
+    if let Some(n) = numbered("f", 1..=24) {
+        return Some(VirtualKey::Function(n));
+    }
+    if let Some(n) = numbered("num", 0..=9) {
+        return Some(VirtualKey::Numpad(n));
+    }
+
+    Some(match key.as_str() {
+        "backspace" | "back" => VirtualKey::Backspace,
+        "tab" => VirtualKey::Tab,
+        "enter" | "return" => VirtualKey::Enter,
+        "esc" | "escape" => VirtualKey::Escape,
+        "space" => VirtualKey::Space,
+        "pageup" | "pgup" => VirtualKey::PageUp,
+        "pagedown" | "pgdn" => VirtualKey::PageDown,
+        "end" => VirtualKey::End,
+        "home" => VirtualKey::Home,
+        "left" => VirtualKey::Left,
+        "up" => VirtualKey::Up,
+        "right" => VirtualKey::Right,
+        "down" => VirtualKey::Down,
+        "insert" | "ins" => VirtualKey::Insert,
+        "delete" | "del" => VirtualKey::Delete,
+        "pause" => VirtualKey::Pause,
+        "plus" => VirtualKey::OemPlus,
+        "minus" => VirtualKey::OemMinus,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn virtual_key_round_trip() {
+        for code in 0..=u8::MAX {
+            assert_eq!(VirtualKey::from_code(code).code(), code);
+        }
+        assert_eq!(VirtualKey::from_code(b'7'), VirtualKey::Digit(7));
+        assert_eq!(VirtualKey::from_code(b'Q'), VirtualKey::Letter('Q'));
+        assert_eq!(VirtualKey::from_code(0x69), VirtualKey::Numpad(9));
+        assert_eq!(VirtualKey::from_code(0x87), VirtualKey::Function(24));
+        assert_eq!(VirtualKey::Letter('q').code(), b'Q');
+    }
+
+    #[test]
+    fn modifiers() {
+        let key = VirtualKey::Letter('S');
+        assert_eq!(Chord::parse("S"), Some(Chord::new(key)));
+        assert_eq!(Chord::parse("Ctrl+S"), Some(Chord::new(key).control()));
+        assert_eq!(Chord::parse("Control+S"), Some(Chord::new(key).control()));
+        assert_eq!(
+            Chord::parse("Ctrl+Shift+Alt+S"),
+            Some(Chord::new(key).control().shift().alt())
+        );
+        // Order, repetition and spacing around separators don't matter.
+        assert_eq!(
+            Chord::parse("Alt + Ctrl + Alt + S"),
+            Some(Chord::new(key).control().alt())
+        );
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let chord = Chord::new(VirtualKey::PageDown).control().shift();
+        assert_eq!(Chord::parse("ctrl+shift+pagedown"), Some(chord));
+        assert_eq!(Chord::parse("CTRL+SHIFT+PAGEDOWN"), Some(chord));
+        assert_eq!(Chord::parse("cTrL+sHiFt+PgDn"), Some(chord));
+        assert_eq!(
+            Chord::parse("Ctrl+s"),
+            Some(Chord::new(VirtualKey::Letter('S')).control())
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let key = |s| Chord::parse(s).map(|chord| chord.key);
+        assert_eq!(key("7"), Some(VirtualKey::Digit(7)));
+        assert_eq!(key("F1"), Some(VirtualKey::Function(1)));
+        assert_eq!(key("f24"), Some(VirtualKey::Function(24)));
+        assert_eq!(key("Num0"), Some(VirtualKey::Numpad(0)));
+        assert_eq!(key("Esc"), Some(VirtualKey::Escape));
+        assert_eq!(key("Escape"), Some(VirtualKey::Escape));
+        assert_eq!(key(","), Some(VirtualKey::OemComma));
+        assert_eq!(key("+"), Some(VirtualKey::OemPlus));
+        assert_eq!(
+            Chord::parse("Ctrl++"),
+            Some(Chord::new(VirtualKey::OemPlus).control())
+        );
+        assert_eq!(
+            Chord::parse("Ctrl+-"),
+            Some(Chord::new(VirtualKey::OemMinus).control())
+        );
+    }
+
+    #[test]
+    fn unknown_keys() {
+        assert_eq!(Chord::parse("Ctrl+Hyper"), None);
+        assert_eq!(Chord::parse("F0"), None);
+        assert_eq!(Chord::parse("F25"), None);
+        assert_eq!(Chord::parse("Num10"), None);
+        assert_eq!(Chord::parse("ñ"), None);
+        assert_eq!(Chord::parse("Ctrl+ñ"), None);
+        // Modifiers alone are not keys, and unknown modifiers are rejected.
+        assert_eq!(Chord::parse("Ctrl"), None);
+        assert_eq!(Chord::parse("Meta+S"), None);
+    }
+
+    #[test]
+    fn empty_input() {
+        assert_eq!(Chord::parse(""), None);
+        assert_eq!(Chord::parse(" "), None);
+        assert_eq!(Chord::parse("Ctrl+"), None);
+        assert_eq!(Chord::parse("+S"), None);
+        assert_eq!(Chord::parse("Ctrl++S"), None);
+    }
+
+    #[test]
+    fn accel_flags() {
+        let chord = Chord::new(VirtualKey::Letter('S'));
+        assert_eq!(chord.accel_flags(), FVIRTKEY);
+        assert_eq!(chord.control().accel_flags(), FVIRTKEY | FCONTROL);
+        assert_eq!(chord.shift().accel_flags(), FVIRTKEY | FSHIFT);
+        assert_eq!(chord.alt().accel_flags(), FVIRTKEY | FALT);
+        assert_eq!(
+            chord.control().shift().alt().accel_flags(),
+            FVIRTKEY | FCONTROL | FSHIFT | FALT
+        );
+    }
+
+    // The codes are copied so that this module does not depend on the Windows API.
+    #[cfg(windows)]
+    #[test]
+    fn codes_match_the_windows_api() {
+        use winapi::um::winuser;
+
+        macro_rules! assert_same {
+            ($($name:ident),*) => {
+                $(assert_eq!($name, winuser::$name as _, stringify!($name));)*
+            };
+        }
+
+        assert_same!(
+            VK_CANCEL,
+            VK_BACK,
+            VK_TAB,
+            VK_CLEAR,
+            VK_RETURN,
+            VK_SHIFT,
+            VK_CONTROL,
+            VK_MENU,
+            VK_PAUSE,
+            VK_CAPITAL,
+            VK_ESCAPE,
+            VK_SPACE,
+            VK_PRIOR,
+            VK_NEXT,
+            VK_END,
+            VK_HOME,
+            VK_LEFT,
+            VK_UP,
+            VK_RIGHT,
+            VK_DOWN,
+            VK_SELECT,
+            VK_PRINT,
+            VK_EXECUTE,
+            VK_SNAPSHOT,
+            VK_INSERT,
+            VK_DELETE,
+            VK_HELP,
+            VK_LWIN,
+            VK_RWIN,
+            VK_APPS,
+            VK_SLEEP,
+            VK_NUMPAD0,
+            VK_NUMPAD9,
+            VK_MULTIPLY,
+            VK_ADD,
+            VK_SEPARATOR,
+            VK_SUBTRACT,
+            VK_DECIMAL,
+            VK_DIVIDE,
+            VK_F1,
+            VK_F24,
+            VK_NUMLOCK,
+            VK_SCROLL,
+            VK_LSHIFT,
+            VK_RSHIFT,
+            VK_LCONTROL,
+            VK_RCONTROL,
+            VK_LMENU,
+            VK_RMENU,
+            VK_BROWSER_BACK,
+            VK_BROWSER_FORWARD,
+            VK_BROWSER_REFRESH,
+            VK_BROWSER_STOP,
+            VK_BROWSER_SEARCH,
+            VK_BROWSER_FAVORITES,
+            VK_BROWSER_HOME,
+            VK_VOLUME_MUTE,
+            VK_VOLUME_DOWN,
+            VK_VOLUME_UP,
+            VK_MEDIA_NEXT_TRACK,
+            VK_MEDIA_PREV_TRACK,
+            VK_MEDIA_STOP,
+            VK_MEDIA_PLAY_PAUSE,
+            VK_LAUNCH_MAIL,
+            VK_LAUNCH_MEDIA_SELECT,
+            VK_LAUNCH_APP1,
+            VK_LAUNCH_APP2,
+            VK_OEM_1,
+            VK_OEM_PLUS,
+            VK_OEM_COMMA,
+            VK_OEM_MINUS,
+            VK_OEM_PERIOD,
+            VK_OEM_2,
+            VK_OEM_3,
+            VK_OEM_4,
+            VK_OEM_5,
+            VK_OEM_6,
+            VK_OEM_7,
+            VK_OEM_8,
+            VK_OEM_102,
+            VK_PROCESSKEY,
+            VK_PACKET,
+            FVIRTKEY,
+            FSHIFT,
+            FCONTROL,
+            FALT
+        );
+    }
+}
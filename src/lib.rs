@@ -1,32 +1,96 @@
-#![cfg(windows)]
+#[cfg(windows)]
+pub mod accel;
+#[cfg(windows)]
 pub mod class;
+#[cfg(windows)]
+pub mod control;
+#[cfg(windows)]
 pub mod cursor;
+// Decoding message parameters is plain bit manipulation, so it's built for tests on any host.
+#[cfg(any(windows, test))]
+mod decode;
+#[cfg(windows)]
 pub mod dialog;
+#[cfg(windows)]
+pub mod dispatch;
+#[cfg(windows)]
+pub mod error;
+// The executor behind `task` only schedules work through a trait, so it's tested on any host.
+#[cfg(any(windows, test))]
+mod executor;
+#[cfg(windows)]
 pub mod font;
+#[cfg(windows)]
 pub mod gdi;
+#[cfg(windows)]
 pub mod icon;
+// Key codes are fixed numbers and chords are parsed from plain strings, so this is also built for
+// tests on any host.
+#[cfg(any(windows, test))]
+mod key;
+#[cfg(windows)]
 pub mod menu;
+#[cfg(windows)]
 pub mod message;
+#[cfg(windows)]
 pub mod messagebox;
+#[cfg(windows)]
+pub mod notify;
+#[cfg(windows)]
 pub mod rect;
+// Resources are processed at build-time, so this module must work on any host, not just Windows.
+pub mod resource;
+#[cfg(windows)]
+pub mod task;
+#[cfg(windows)]
 pub mod toolbar;
+#[cfg(windows)]
 pub mod window;
 
-use once_cell::sync::Lazy;
-use std::ffi::CString;
+#[cfg(windows)]
+use std::cell::RefCell;
+#[cfg(windows)]
+use std::ffi::OsString;
+#[cfg(windows)]
+use std::os::windows::ffi::OsStringExt;
+#[cfg(windows)]
+use std::path::PathBuf;
+#[cfg(windows)]
 use std::ptr::{self, NonNull};
-use std::{collections::HashMap, sync::Mutex};
+#[cfg(windows)]
+use std::rc::Rc;
+#[cfg(windows)]
+use widestring::U16CString;
+#[cfg(windows)]
 use winapi::shared::minwindef::{BOOL, HINSTANCE, MAX_PATH};
-use winapi::shared::ntdef::LPSTR;
+#[cfg(windows)]
+use winapi::shared::windef::HWND;
+#[cfg(windows)]
 use winapi::um::commctrl::InitCommonControls;
-use winapi::um::libloaderapi::{GetModuleFileNameA, GetModuleHandleA};
+#[cfg(windows)]
+use winapi::um::libloaderapi::{GetModuleFileNameW, GetModuleHandleW};
+#[cfg(windows)]
 use winapi::um::winuser::{
-    DispatchMessageA, GetMessageA, PostQuitMessage, TranslateMessage, LPMSG, MSG,
+    DispatchMessageW, GetMessageW, GetWindowLongPtrW, IsDialogMessageW, PeekMessageW,
+    PostQuitMessage, SetWindowLongPtrW, TranslateAcceleratorW, TranslateMessage, WaitMessage,
+    GWLP_USERDATA, LPMSG, MSG, PM_REMOVE, WM_QUIT,
 };
 
-pub use std::io::{Error, Result};
-pub type MessageCallback = fn(&window::Window, message::Message) -> Option<isize>;
-pub type DialogCallback = fn(&window::Window, message::Message) -> isize;
+#[cfg(windows)]
+pub use error::{Error, Result};
+/// Callback that processes the messages sent to a window. Returning `None` lets the default
+/// window procedure process the message.
+///
+/// The callback may be called again while it is still running, because handling a message can
+/// cause more messages to be sent to the same window (for example, calling `Window::destroy`
+/// while handling `Close` sends `Destroy`). This is why it must be `Fn` and not `FnMut`, so any
+/// state it captures that needs to change should live in a `Cell` or `RefCell`.
+#[cfg(windows)]
+pub type MessageCallback = Box<dyn Fn(&window::Window, message::Message) -> Option<isize>>;
+/// Callback that processes the messages sent to a dialog box. The same considerations as for
+/// `MessageCallback` apply.
+#[cfg(windows)]
+pub type DialogCallback = Box<dyn Fn(&window::Window, message::Message) -> isize>;
 
 // We want to wrap user functions to provide them with a safer interface.
 //
@@ -36,24 +100,100 @@ pub type DialogCallback = fn(&window::Window, message::Message) -> isize;
 // We don't know how many functions they will need ahead of time, so we can't define that many
 // static functions either.
 //
-// The only solution is to have a single static wrapper function that queries a global map (this
-// map) to determine what to call based on the window.
+// The only solution is to have a single static wrapper function that determines what to call
+// based on the window. Each window keeps a pointer to its callback in its user data, which is
+// handed to it during creation (through `lpCreateParams` for windows, and the initialization
+// parameter for dialogs), so no global state is needed.
 //
-// Because messages may be emitted before the pointer is obtained, a special value of 0 is used
-// to indicate "newly created", and is used as a fallback.
-static HWND_TO_CALLBACK: Lazy<Mutex<HashMap<usize, MessageCallback>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
+// The callbacks are reference-counted. The window holds a reference, and so does every call to
+// the callback, because it may cause more messages to be sent to the same window (including the
+// last one, which releases the window's reference). This way, the callback is only dropped once
+// the window is gone and the callback is no longer running.
+
+/// Stores a new reference to the callback in the user data of the window.
+#[cfg(windows)]
+pub(crate) unsafe fn attach_callback<T>(hwnd: HWND, callback: *const T) {
+    Rc::increment_strong_count(callback);
+    SetWindowLongPtrW(hwnd, GWLP_USERDATA, callback as _);
+}
+
+/// Obtains a new reference to the callback stored in the user data of the window, if any.
+#[cfg(windows)]
+pub(crate) unsafe fn window_callback<T>(hwnd: HWND) -> Option<Rc<T>> {
+    let callback = GetWindowLongPtrW(hwnd, GWLP_USERDATA) as *const T;
+    if callback.is_null() {
+        None
+    } else {
+        Rc::increment_strong_count(callback);
+        Some(Rc::from_raw(callback))
+    }
+}
+
+/// Removes the callback from the user data of the window, releasing its reference.
+#[cfg(windows)]
+pub(crate) unsafe fn detach_callback<T>(hwnd: HWND) {
+    let callback = SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0) as *const T;
+    if !callback.is_null() {
+        drop(Rc::from_raw(callback));
+    }
+}
+
+#[cfg(windows)]
+thread_local! {
+    // Modeless dialogs need the message loop to call `IsDialogMessage` for keyboard navigation
+    // (such as Tab, Enter and Esc) to work. Windows belong to the thread that created them, and
+    // so does the message loop that serves them, so a per-thread list is enough.
+    //
+    // The list is only rebuilt when a dialog is registered or unregistered, so that routing each
+    // message only needs to clone the `Rc` and not the list.
+    static MODELESS_DIALOGS: RefCell<Rc<[HWND]>> = RefCell::new(Rc::new([]));
+}
+
+/// Lets the message loop route the keyboard messages of the modeless dialog.
+#[cfg(windows)]
+pub(crate) fn register_dialog(hwnd: HWND) {
+    MODELESS_DIALOGS.with(|dialogs| {
+        let mut dialogs = dialogs.borrow_mut();
+        *dialogs = dialogs.iter().copied().chain(Some(hwnd)).collect();
+    });
+}
 
-static HWND_TO_DLG_CALLBACK: Lazy<Mutex<HashMap<usize, DialogCallback>>> =
-    Lazy::new(|| Mutex::new(HashMap::new()));
+/// Stops routing messages to the dialog. Does nothing if the dialog was not registered.
+#[cfg(windows)]
+pub(crate) fn unregister_dialog(hwnd: HWND) {
+    MODELESS_DIALOGS.with(|dialogs| {
+        let mut dialogs = dialogs.borrow_mut();
+        if dialogs.contains(&hwnd) {
+            *dialogs = dialogs
+                .iter()
+                .copied()
+                .filter(|&dialog| dialog != hwnd)
+                .collect();
+        }
+    });
+}
+
+/// Gives every modeless dialog a chance to process the message, returning `true` if one did
+/// (in which case the message must not be dispatched).
+#[cfg(windows)]
+unsafe fn is_dialog_message(msg: &mut MSG) -> bool {
+    // Processing the message can create or destroy dialogs, so the list can't stay borrowed.
+    // Those replace the list instead of modifying it, so the snapshot stays valid.
+    let dialogs = MODELESS_DIALOGS.with(|dialogs| Rc::clone(&dialogs.borrow()));
+    dialogs
+        .iter()
+        .any(|&dialog| IsDialogMessageW(dialog, msg as LPMSG) != 0)
+}
 
 /// Obtains the `hInstance` parameter from `WinMain`.
+#[cfg(windows)]
 pub(crate) fn base_instance() -> HINSTANCE {
-    unsafe { GetModuleHandleA(std::ptr::null()) }
+    unsafe { GetModuleHandleW(std::ptr::null()) }
 }
 
 /// Registers and initializes certain common control window classes.
 /// This method must be called early in the program if common controls are used.
+#[cfg(windows)]
 pub fn init_common_controls() {
     unsafe {
         InitCommonControls();
@@ -62,18 +202,18 @@ pub fn init_common_controls() {
 
 /// Retrieves the fully qualified path for the file that contains the specified module.
 /// The module must have been loaded by the current process.
-pub fn module_file_name() -> Result<CString> {
+#[cfg(windows)]
+pub fn module_file_name() -> Result<PathBuf> {
     let module = base_instance();
-    let mut buffer = vec![0u8; MAX_PATH];
+    let mut buffer = vec![0u16; MAX_PATH];
 
-    let result =
-        unsafe { GetModuleFileNameA(module, buffer.as_mut_ptr() as LPSTR, buffer.len() as u32) };
+    let result = unsafe { GetModuleFileNameW(module, buffer.as_mut_ptr(), buffer.len() as u32) };
 
     if result == 0 {
         Err(Error::last_os_error())
     } else {
         buffer.truncate(result as usize);
-        Ok(CString::new(buffer)?)
+        Ok(OsString::from_wide(&buffer).into())
     }
 }
 
@@ -81,26 +221,131 @@ pub fn module_file_name() -> Result<CString> {
 /// It is typically used in response to a `Destroy` message.
 ///
 /// The application exit code is used as the wParam parameter of the `Quit` message.
+#[cfg(windows)]
 pub fn post_quit_message(exit_code: i32) {
     unsafe { PostQuitMessage(exit_code) }
 }
 
+/// Translates and dispatches a message retrieved from the queue, unless a modeless dialog
+/// processes it first.
+#[cfg(windows)]
+unsafe fn dispatch_message(msg: &mut MSG) {
+    if is_dialog_message(msg) {
+        return;
+    }
+    TranslateMessage(msg as LPMSG);
+    // This effectively looks up the window corresponding to the message's window handle
+    // and calls its window procedure. Alternatively `GetWindowLong` can be used to do
+    // the same, but manually (http://winprog.org/tutorial/message_loop.html).
+    DispatchMessageW(msg as LPMSG);
+}
+
+#[cfg(windows)]
 pub fn message_loop() -> i32 {
     unsafe {
         let mut msg: MSG = std::mem::zeroed();
-        while GetMessageA(&mut msg as LPMSG, ptr::null_mut(), 0, 0) > 0 {
-            TranslateMessage(&mut msg as LPMSG);
-            // This effectively looks up the window corresponding to the message's window handle
-            // and calls its window procedure. Alternatively `GetWindowLong` can be used to do
-            // the same, but manually (http://winprog.org/tutorial/message_loop.html).
-            DispatchMessageA(&mut msg as LPMSG);
+        while GetMessageW(&mut msg as LPMSG, ptr::null_mut(), 0, 0) > 0 {
+            dispatch_message(&mut msg);
         }
         msg.wParam as i32
     }
 }
 
+/// Like `message_loop`, but keystrokes are first translated through the accelerator table, so
+/// that matching key combinations are sent to the window as `Command` messages instead.
+#[cfg(windows)]
+pub fn accelerator_message_loop(window: &window::Window, table: &accel::Table) -> i32 {
+    unsafe {
+        let mut msg: MSG = std::mem::zeroed();
+        while GetMessageW(&mut msg as LPMSG, ptr::null_mut(), 0, 0) > 0 {
+            // The accelerator's message is sent directly to the window procedure, so the
+            // keystroke must not be dispatched when it was translated.
+            if TranslateAcceleratorW(window.hwnd_ptr(), table.as_ptr(), &mut msg as LPMSG) == 0 {
+                dispatch_message(&mut msg);
+            }
+        }
+        msg.wParam as i32
+    }
+}
+
+/// Dispatches every message currently in the queue without waiting for more, and returns.
+///
+/// If a `Quit` message is found, processing stops and its exit code is returned. The caller
+/// should then stop pumping messages.
+#[cfg(windows)]
+pub fn pump_pending() -> Option<i32> {
+    unsafe {
+        let mut msg: MSG = std::mem::zeroed();
+        while PeekMessageW(&mut msg as LPMSG, ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
+            if msg.message == WM_QUIT {
+                return Some(msg.wParam as i32);
+            }
+            dispatch_message(&mut msg);
+        }
+        None
+    }
+}
+
+/// Message loop that does not block while the queue is empty. Instead, `on_idle` is called every
+/// time all pending messages have been processed, which is useful to drive animations or games
+/// at a higher rate than the `Timer` message allows.
+///
+/// The loop ends when a `Quit` message is received, returning `Some` with its exit code, or when
+/// `on_idle` returns `false`, returning `None`. In the latter case, the remaining messages are
+/// left in the queue, so the caller can resume pumping them later.
+///
+/// `on_idle` is called continuously for as long as the queue is empty, so if it has nothing to
+/// do, it should call `wait_message` to avoid using the CPU needlessly.
+#[cfg(windows)]
+pub fn idle_message_loop<F: FnMut() -> bool>(mut on_idle: F) -> Option<i32> {
+    loop {
+        if let Some(exit_code) = pump_pending() {
+            break Some(exit_code);
+        }
+        if !on_idle() {
+            break None;
+        }
+    }
+}
+
+/// Blocks until a new message is placed in the queue.
+#[cfg(windows)]
+pub fn wait_message() -> Result<()> {
+    let result = unsafe { WaitMessage() };
+    ok_or_last_err(result)
+}
+
+/// Converts the string into a null-terminated wide string for the Windows API. Fails if the
+/// string contains a null character, since the text would otherwise be cut short.
+#[cfg(windows)]
+pub(crate) fn to_wide(s: &str) -> Result<U16CString> {
+    Ok(U16CString::from_str(s)?)
+}
+
+/// Converts a wide string returned by the Windows API into a string, up to the first null
+/// character (if any). Unpaired surrogates, which Windows allows, are replaced with U+FFFD.
+#[cfg(windows)]
+pub(crate) fn from_wide(s: &[u16]) -> String {
+    let len = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+    String::from_utf16_lossy(&s[..len])
+}
+
+/// Converts a null-terminated ASCII string into a wide string. This is meant for the names the
+/// crate itself uses, so they can be defined at compile time.
+#[cfg(windows)]
+pub(crate) const fn ascii_to_wide<const N: usize>(s: &[u8; N]) -> [u16; N] {
+    let mut wide = [0; N];
+    let mut i = 0;
+    while i < N {
+        wide[i] = s[i] as u16;
+        i += 1;
+    }
+    wide
+}
+
 /// Checks the resulting return value of a function. If it's `true`, `Ok` is returned. Otherwise,
 /// the last OS error is returned in the `Err` variant.
+#[cfg(windows)]
 pub(crate) fn ok_or_last_err(result: BOOL) -> Result<()> {
     if result != 0 {
         Ok(())
@@ -109,6 +354,7 @@ pub(crate) fn ok_or_last_err(result: BOOL) -> Result<()> {
     }
 }
 
+#[cfg(windows)]
 pub(crate) fn non_null_or_err<T>(value: *mut T) -> Result<NonNull<T>> {
     NonNull::new(value).ok_or_else(|| Error::last_os_error())
 }
@@ -1,9 +1,8 @@
-use crate::{non_null_or_err, ok_or_last_err, Result};
-use std::ffi::CString;
+use crate::{non_null_or_err, ok_or_last_err, to_wide, Result};
 use std::ptr::{self, NonNull};
 use winapi::shared::windef::{HMENU, HMENU__};
 use winapi::um::winuser::{
-    AppendMenuA, CreateMenu, CreatePopupMenu, MF_POPUP, MF_SEPARATOR, MF_STRING,
+    AppendMenuW, CreateMenu, CreatePopupMenu, MF_POPUP, MF_SEPARATOR, MF_STRING,
 };
 
 pub struct Menu {
@@ -34,9 +33,9 @@ impl Menu {
     /// shortcut menu. You can use this function to specify the content, appearance, and behavior
     /// of the menu item.
     pub fn append_item(&self, name: &str, value: u16) -> Result<()> {
-        let name = CString::new(name)?;
+        let name = to_wide(name)?;
         let result =
-            unsafe { AppendMenuA(self.menu.as_ptr(), MF_STRING, value as usize, name.as_ptr()) };
+            unsafe { AppendMenuW(self.menu.as_ptr(), MF_STRING, value as usize, name.as_ptr()) };
 
         ok_or_last_err(result)
     }
@@ -44,7 +43,7 @@ impl Menu {
     /// Creates an inactive menu item that serves as a dividing bar between two active menu items
     /// on a menu.
     pub fn append_separator(&self) -> Result<()> {
-        let result = unsafe { AppendMenuA(self.menu.as_ptr(), MF_SEPARATOR, 0, ptr::null_mut()) };
+        let result = unsafe { AppendMenuW(self.menu.as_ptr(), MF_SEPARATOR, 0, ptr::null_mut()) };
         ok_or_last_err(result)
     }
 
@@ -52,9 +51,9 @@ impl Menu {
     /// shortcut menu. You can use this function to specify the content, appearance, and behavior
     /// of the menu item.
     pub fn append_menu(&self, name: &str, value: Menu) -> Result<()> {
-        let name = CString::new(name)?;
+        let name = to_wide(name)?;
         let result = unsafe {
-            AppendMenuA(
+            AppendMenuW(
                 self.menu.as_ptr(),
                 MF_STRING | MF_POPUP,
                 value.as_ptr() as usize,
@@ -1,18 +1,35 @@
-use crate::{messagebox, window};
+use crate::control::trackbar;
+use crate::decode;
+use crate::{messagebox, notify, window, Error, Result};
 use std::ptr::NonNull;
 use winapi::shared::minwindef::{HIWORD, LOWORD, LPARAM, UINT, WPARAM};
 use winapi::shared::windef::{HDC, HWND};
+use winapi::um::commctrl::{
+    TB_BOTTOM, TB_ENDTRACK, TB_LINEDOWN, TB_LINEUP, TB_PAGEDOWN, TB_PAGEUP, TB_THUMBPOSITION,
+    TB_THUMBTRACK, TB_TOP,
+};
 use winapi::um::wingdi::{
     GetBValue, GetGValue, GetRValue, SetBkMode, SetTextColor, CLR_INVALID, OPAQUE, RGB, TRANSPARENT,
 };
 use winapi::um::winuser::{
-    LBN_SELCHANGE, MK_CONTROL, MK_LBUTTON, MK_MBUTTON, MK_RBUTTON, MK_SHIFT, MK_XBUTTON1,
-    MK_XBUTTON2, SIZE_MAXHIDE, SIZE_MAXIMIZED, SIZE_MAXSHOW, SIZE_MINIMIZED, SIZE_RESTORED,
-    WM_CLOSE, WM_COMMAND, WM_CREATE, WM_CTLCOLORDLG, WM_CTLCOLORSTATIC, WM_DESTROY, WM_INITDIALOG,
-    WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_PAINT, WM_RBUTTONDOWN,
-    WM_RBUTTONUP, WM_SIZE, WM_TIMER,
+    BN_CLICKED, BN_DOUBLECLICKED, BN_KILLFOCUS, BN_SETFOCUS, CBN_CLOSEUP, CBN_DBLCLK, CBN_DROPDOWN,
+    CBN_EDITCHANGE, CBN_EDITUPDATE, CBN_ERRSPACE, CBN_KILLFOCUS, CBN_SELCHANGE, CBN_SELENDCANCEL,
+    CBN_SELENDOK, CBN_SETFOCUS, EN_CHANGE, EN_ERRSPACE, EN_HSCROLL, EN_KILLFOCUS, EN_MAXTEXT,
+    EN_SETFOCUS, EN_UPDATE, EN_VSCROLL, LBN_DBLCLK, LBN_ERRSPACE, LBN_KILLFOCUS, LBN_SELCANCEL,
+    LBN_SELCHANGE, LBN_SETFOCUS, MK_CONTROL, MK_LBUTTON, MK_MBUTTON, MK_RBUTTON, MK_SHIFT,
+    MK_XBUTTON1, MK_XBUTTON2, SIZE_MAXHIDE, SIZE_MAXIMIZED, SIZE_MAXSHOW, SIZE_MINIMIZED,
+    SIZE_RESTORED, WM_CHAR, WM_CLOSE, WM_COMMAND, WM_CREATE, WM_CTLCOLORDLG, WM_CTLCOLORSTATIC,
+    WM_DEADCHAR, WM_DESTROY, WM_HSCROLL, WM_INITDIALOG, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDBLCLK,
+    WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDBLCLK, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHOVER,
+    WM_MOUSEHWHEEL, WM_MOUSELEAVE, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_NOTIFY, WM_PAINT,
+    WM_RBUTTONDBLCLK, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SIZE, WM_SYSCHAR, WM_SYSDEADCHAR,
+    WM_SYSKEYDOWN, WM_SYSKEYUP, WM_TIMER, WM_VSCROLL, WM_XBUTTONDBLCLK, WM_XBUTTONDOWN,
+    WM_XBUTTONUP,
 };
 
+pub use crate::key::VirtualKey;
+pub use decode::CharDecoder;
+
 #[derive(Debug)]
 pub struct SizeData {
     wparam: WPARAM,
@@ -31,6 +48,24 @@ pub struct MouseData {
     lparam: LPARAM,
 }
 
+#[derive(Debug)]
+pub struct WheelData {
+    wparam: WPARAM,
+    lparam: LPARAM,
+}
+
+#[derive(Debug)]
+pub struct KeyData {
+    wparam: WPARAM,
+    lparam: LPARAM,
+}
+
+#[derive(Debug)]
+pub struct CharData {
+    wparam: WPARAM,
+    lparam: LPARAM,
+}
+
 #[derive(Debug)]
 pub struct CommandData {
     wparam: WPARAM,
@@ -53,7 +88,15 @@ pub struct ColorData {
 }
 
 #[derive(Debug)]
-pub enum Message {
+pub struct ScrollData {
+    wparam: WPARAM,
+    lparam: LPARAM,
+}
+
+/// A message sent to a window. Some messages borrow data that only lives while the message is
+/// being handled, hence the lifetime.
+#[derive(Debug)]
+pub enum Message<'a> {
     Create,
     Size(SizeData),
     Destroy,
@@ -61,13 +104,47 @@ pub enum Message {
     InitDialog,
     Paint,
     Timer(TimerData),
+    KeyDown(KeyData),
+    KeyUp(KeyData),
+    SystemKeyDown(KeyData),
+    SystemKeyUp(KeyData),
+    Char(CharData),
+    DeadChar(CharData),
+    SystemChar(CharData),
+    SystemDeadChar(CharData),
+    MouseMove(MouseData),
     LeftMouseButtonDown(MouseData),
     RightMouseButtonDown(MouseData),
     MiddleMouseButtonDown(MouseData),
+    XButtonDown(MouseData),
     LeftMouseButtonUp(MouseData),
     RightMouseButtonUp(MouseData),
     MiddleMouseButtonUp(MouseData),
+    XButtonUp(MouseData),
+    /// Only sent if the window class has the `DoubleClicks` style.
+    LeftMouseButtonDoubleClick(MouseData),
+    /// Only sent if the window class has the `DoubleClicks` style.
+    RightMouseButtonDoubleClick(MouseData),
+    /// Only sent if the window class has the `DoubleClicks` style.
+    MiddleMouseButtonDoubleClick(MouseData),
+    /// Only sent if the window class has the `DoubleClicks` style.
+    XButtonDoubleClick(MouseData),
+    MouseWheel(WheelData),
+    MouseHWheel(WheelData),
+    /// Only sent if requested by `Window::track_mouse`.
+    MouseHover(MouseData),
+    /// Only sent if requested by `Window::track_mouse`.
+    MouseLeave,
     Command(CommandData),
+    /// Sent by a common control to its parent window when an event has occurred or the control
+    /// requires some information.
+    Notify(notify::NotifyData<'a>),
+    /// Sent when the horizontal scroll bar of the window, or a horizontal scroll bar control or
+    /// trackbar, is scrolled.
+    HorizontalScroll(ScrollData),
+    /// Sent when the vertical scroll bar of the window, or a vertical scroll bar control or
+    /// trackbar, is scrolled.
+    VerticalScroll(ScrollData),
     ControlColorDialog(ColorData),
     ControlColorStatic(ColorData),
     Other {
@@ -77,10 +154,119 @@ pub enum Message {
     },
 }
 
+/// Notifications sent by a button https://docs.microsoft.com/en-us/windows/win32/controls/bumper-button-control-reference-notifications.
+/// Other than `Clicked`, they are only sent if the button has the `Notify` style.
+#[derive(Debug)]
+pub enum ButtonMessage {
+    /// The user clicked the button, or the button was clicked with the keyboard.
+    Clicked,
+    /// The user double-clicked the button. Check boxes and radio buttons also send this without
+    /// the `Notify` style.
+    DoubleClicked,
+    SetFocus,
+    KillFocus,
+    Other {
+        code: u16,
+    },
+}
+
+/// Notifications sent by a trackbar https://docs.microsoft.com/en-us/windows/win32/controls/bumper-trackbar-control-reference-notifications,
+/// as the request of a `HorizontalScroll` or `VerticalScroll` message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrackbarMessage {
+    /// The user pressed the up or left arrow key.
+    LineUp,
+    /// The user pressed the down or right arrow key.
+    LineDown,
+    /// The user pressed the page up key, or clicked the channel above or left of the slider.
+    PageUp,
+    /// The user pressed the page down key, or clicked the channel below or right of the slider.
+    PageDown,
+    /// The user released the slider after dragging it.
+    ThumbPosition,
+    /// The user is dragging the slider.
+    ThumbTrack,
+    /// The user pressed the home key.
+    Top,
+    /// The user pressed the end key.
+    Bottom,
+    /// The user released the key or mouse button that moved the slider.
+    EndTrack,
+    Other {
+        code: u16,
+    },
+}
+
+/// Notifications sent by an edit control https://docs.microsoft.com/en-us/windows/win32/controls/bumper-edit-control-reference-notifications.
+#[derive(Debug)]
+pub enum EditMessage {
+    /// The user has taken an action that may have altered the text, after the system updates
+    /// the screen.
+    Change,
+    /// The control is about to redraw itself after its text was altered, before the system
+    /// updates the screen.
+    Update,
+    /// The text the user typed was truncated because it exceeded the limit of the control.
+    MaxText,
+    SetFocus,
+    KillFocus,
+    /// The control cannot allocate enough memory to meet a specific request.
+    OutOfSpace,
+    /// The user has clicked the horizontal scroll bar.
+    HorizontalScroll,
+    /// The user has clicked the vertical scroll bar.
+    VerticalScroll,
+    Other {
+        code: u16,
+    },
+}
+
+/// Notifications sent by a combo box https://docs.microsoft.com/en-us/windows/win32/controls/bumper-combobox-control-reference-notifications.
+#[derive(Debug)]
+pub enum ComboBoxMessage {
+    /// The user has changed the selection in the list box, or the selection was changed with
+    /// the keyboard.
+    SelectionChange,
+    /// The user has selected an item, and then either closed the list or selected another
+    /// control, so the selection is final.
+    SelectionEndOk,
+    /// The user has selected an item, but then selected another control or closed the dialog
+    /// box, so the selection should be ignored.
+    SelectionEndCancel,
+    /// The user has altered the text in the edit control, after the system updates the screen.
+    EditChange,
+    /// The edit control is about to display altered text, before the system updates the screen.
+    EditUpdate,
+    /// The list box is about to be made visible.
+    DropDown,
+    /// The list box has been closed.
+    CloseUp,
+    /// Only sent by combo boxes with the `Simple` style.
+    DoubleClick,
+    SetFocus,
+    KillFocus,
+    /// The combo box cannot allocate enough memory to meet a specific request.
+    OutOfSpace,
+    Other {
+        code: u16,
+    },
+}
+
+/// Notifications sent by a list box https://docs.microsoft.com/en-us/windows/win32/controls/bumper-list-box-control-reference-notifications.
+/// They are only sent if the list box has the `Notify` style.
 #[derive(Debug)]
 pub enum ListBoxMessage {
     SelectionChange,
-    Other { code: u16 },
+    /// The user has canceled the selection.
+    SelectionCancel,
+    DoubleClick,
+    SetFocus,
+    KillFocus,
+    /// The list box cannot allocate enough memory to meet a specific request.
+    OutOfSpace,
+    Other {
+        code: u16,
+    },
 }
 
 // https://docs.microsoft.com/en-us/windows/win32/winmsg/wm-size
@@ -132,13 +318,18 @@ impl TimerData {
 // https://docs.microsoft.com/en-us/windows/win32/inputdev/wm-lbuttondown
 impl MouseData {
     /// The x-coordinate of the cursor. The coordinate is relative to the upper-left corner of the client area.
-    pub fn x(&self) -> u16 {
-        LOWORD(self.lparam as u32)
+    pub fn x(&self) -> i32 {
+        decode::x_coordinate(self.lparam)
     }
 
     /// The y-coordinate of the cursor. The coordinate is relative to the upper-left corner of the client area.
-    pub fn y(&self) -> u16 {
-        HIWORD(self.lparam as u32)
+    pub fn y(&self) -> i32 {
+        decode::y_coordinate(self.lparam)
+    }
+
+    /// Which X button was pressed or released (1 or 2), for the X button messages.
+    pub fn x_button(&self) -> u16 {
+        HIWORD(self.wparam as u32)
     }
 
     /// Whether the CTRL key is down.
@@ -177,6 +368,157 @@ impl MouseData {
     }
 }
 
+// https://docs.microsoft.com/en-us/windows/win32/inputdev/wm-mousewheel
+impl WheelData {
+    /// The distance the wheel is rotated, expressed in multiples or divisions of `WHEEL_DELTA`,
+    /// which is 120. A positive value indicates that the wheel was rotated forward (or to the
+    /// right, for the horizontal wheel), away from the user.
+    pub fn delta(&self) -> i16 {
+        decode::wheel_delta(self.wparam)
+    }
+
+    /// The x-coordinate of the cursor, relative to the upper-left corner of the screen.
+    pub fn x(&self) -> i32 {
+        decode::x_coordinate(self.lparam)
+    }
+
+    /// The y-coordinate of the cursor, relative to the upper-left corner of the screen.
+    pub fn y(&self) -> i32 {
+        decode::y_coordinate(self.lparam)
+    }
+
+    /// Whether the CTRL key is down.
+    pub fn control(&self) -> bool {
+        (self.wparam & MK_CONTROL) != 0
+    }
+
+    /// Whether the left mouse button is down.
+    pub fn lmb(&self) -> bool {
+        (self.wparam & MK_LBUTTON) != 0
+    }
+
+    /// Whether the middle mouse button is down.
+    pub fn mmb(&self) -> bool {
+        (self.wparam & MK_MBUTTON) != 0
+    }
+
+    /// Whether the right mouse button is down.
+    pub fn rmb(&self) -> bool {
+        (self.wparam & MK_RBUTTON) != 0
+    }
+
+    /// Whether the SHIFT key is down.
+    pub fn shift(&self) -> bool {
+        (self.wparam & MK_SHIFT) != 0
+    }
+
+    /// Whether the first X button is down.
+    pub fn xbutton1(&self) -> bool {
+        (self.wparam & MK_XBUTTON1) != 0
+    }
+
+    /// Whether the second X button is down.
+    pub fn xbutton2(&self) -> bool {
+        (self.wparam & MK_XBUTTON2) != 0
+    }
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/inputdev/wm-keydown
+impl KeyData {
+    /// The virtual-key code of the key.
+    pub fn virtual_key(&self) -> VirtualKey {
+        VirtualKey::from_code(self.wparam as u8)
+    }
+
+    /// The number of times the keystroke is autorepeated as a result of the user holding down
+    /// the key. The repeat count is always 1 for key up messages.
+    pub fn repeat_count(&self) -> u16 {
+        decode::repeat_count(self.lparam)
+    }
+
+    /// The scan code. The value depends on the OEM.
+    pub fn scan_code(&self) -> u8 {
+        decode::scan_code(self.lparam)
+    }
+
+    /// Whether the key is an extended key, such as the right-hand ALT and CTRL keys that appear
+    /// on an enhanced 101- or 102-key keyboard.
+    pub fn extended(&self) -> bool {
+        decode::extended(self.lparam)
+    }
+
+    /// Whether the ALT key is held down. Always `false` for messages that are not system keys.
+    pub fn alt(&self) -> bool {
+        decode::context_code(self.lparam)
+    }
+
+    /// Whether the key was down before the message was sent.
+    pub fn previous_state(&self) -> bool {
+        decode::previous_state(self.lparam)
+    }
+
+    /// Whether the key is being released (`false` if it's being pressed).
+    pub fn transition_state(&self) -> bool {
+        decode::transition_state(self.lparam)
+    }
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/inputdev/wm-char
+impl CharData {
+    /// The UTF-16 code unit of the character produced by the key.
+    pub fn code_unit(&self) -> u16 {
+        self.wparam as u16
+    }
+
+    /// The character produced by the key. This is `None` for characters outside the Basic
+    /// Multilingual Plane, because these are sent as UTF-16 surrogate pairs, one half in each
+    /// message. Use `decode` to assemble those.
+    pub fn char(&self) -> Option<char> {
+        std::char::from_u32(self.code_unit() as u32)
+    }
+
+    /// Feeds the code unit to the decoder, which keeps the first half of a surrogate pair until
+    /// the message with the second one arrives. Returns the character once it's complete.
+    ///
+    /// The decoder should be kept alongside the window (for example, in a `Cell` captured by
+    /// its callback), and only be given its `Char` or `SystemChar` messages.
+    pub fn decode(&self, decoder: &mut CharDecoder) -> Option<char> {
+        decoder.decode(self.code_unit())
+    }
+
+    /// The number of times the keystroke is autorepeated as a result of the user holding down
+    /// the key.
+    pub fn repeat_count(&self) -> u16 {
+        decode::repeat_count(self.lparam)
+    }
+
+    /// The scan code. The value depends on the OEM.
+    pub fn scan_code(&self) -> u8 {
+        decode::scan_code(self.lparam)
+    }
+
+    /// Whether the key is an extended key, such as the right-hand ALT and CTRL keys that appear
+    /// on an enhanced 101- or 102-key keyboard.
+    pub fn extended(&self) -> bool {
+        decode::extended(self.lparam)
+    }
+
+    /// Whether the ALT key is held down.
+    pub fn alt(&self) -> bool {
+        decode::context_code(self.lparam)
+    }
+
+    /// Whether the key was down before the message was sent.
+    pub fn previous_state(&self) -> bool {
+        decode::previous_state(self.lparam)
+    }
+
+    /// Whether the key is being released (`false` if it's being pressed).
+    pub fn transition_state(&self) -> bool {
+        decode::transition_state(self.lparam)
+    }
+}
+
 // https://docs.microsoft.com/en-us/windows/win32/menurc/wm-command
 impl CommandData {
     /// The selected menu identifier if the message source is a menu.
@@ -221,10 +563,51 @@ impl ControlData<'_> {
         }
     }
 
+    /// Interpret the `code` as if it was a notification emitted by a button.
+    pub fn button_code(&self) -> ButtonMessage {
+        ButtonMessage::from_raw(self.code)
+    }
+
     /// Interpret the `code` as if it was a notification emitted by a list box.
     pub fn list_box_code(&self) -> ListBoxMessage {
         ListBoxMessage::from_raw(self.code)
     }
+
+    /// Interpret the `code` as if it was a notification emitted by a combo box.
+    pub fn combo_box_code(&self) -> ComboBoxMessage {
+        ComboBoxMessage::from_raw(self.code)
+    }
+
+    /// Interpret the `code` as if it was a notification emitted by an edit control.
+    pub fn edit_code(&self) -> EditMessage {
+        EditMessage::from_raw(self.code)
+    }
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/controls/wm-hscroll
+// https://docs.microsoft.com/en-us/windows/win32/controls/wm-vscroll
+impl ScrollData {
+    /// The control that was scrolled, or `None` if it was the scroll bar of the window itself.
+    pub fn control(&self) -> Option<window::Window<'_>> {
+        NonNull::new(self.lparam as HWND).map(|hwnd| window::Window::Borrowed { hwnd })
+    }
+
+    /// The raw scrolling request.
+    pub fn code(&self) -> u16 {
+        LOWORD(self.wparam as u32)
+    }
+
+    /// Interpret the `code` as if it was a notification emitted by a trackbar.
+    pub fn trackbar_code(&self) -> TrackbarMessage {
+        TrackbarMessage::from_raw(self.code())
+    }
+
+    /// Interpret the message as a change in the position of a trackbar. Returns `None` if it was
+    /// sent by something other than a trackbar (such as a scroll bar), or for `EndTrack`, which
+    /// follows the change.
+    pub fn trackbar_change(&self) -> Option<trackbar::ValueChanged<'_>> {
+        trackbar::ValueChanged::from_scroll(self.control()?, self.trackbar_code())
+    }
 }
 
 // https://docs.microsoft.com/en-us/windows/win32/dlgbox/wm-ctlcolordlg
@@ -234,17 +617,17 @@ impl ColorData {
         self.wparam as HDC
     }
 
-    pub fn set_text_color(&self, r: u8, g: u8, b: u8) -> std::result::Result<(u8, u8, u8), ()> {
+    pub fn set_text_color(&self, r: u8, g: u8, b: u8) -> Result<(u8, u8, u8)> {
         // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-settextcolor
         let result = unsafe { SetTextColor(self.hdc(), RGB(r, g, b)) };
         if result != CLR_INVALID {
             Ok((GetRValue(result), GetGValue(result), GetBValue(result)))
         } else {
-            Err(())
+            Err(Error::Gdi("SetTextColor"))
         }
     }
 
-    pub fn set_background_transparency(&self, transparent: bool) -> std::result::Result<bool, ()> {
+    pub fn set_background_transparency(&self, transparent: bool) -> Result<bool> {
         // https://docs.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-setbkmode
         let value = if transparent { TRANSPARENT } else { OPAQUE };
 
@@ -252,14 +635,17 @@ impl ColorData {
         match result as u32 {
             TRANSPARENT => Ok(true),
             OPAQUE => Ok(false),
-            0 => Err(()),
-            _ => panic!("invalid return value from SetBkMode"),
+            _ => Err(Error::Gdi("SetBkMode")),
         }
     }
 }
 
-impl Message {
-    pub(crate) fn from_raw(msg: UINT, wparam: WPARAM, lparam: LPARAM) -> Self {
+impl Message<'_> {
+    /// # Safety
+    ///
+    /// The parameters must be those of a message that is being sent to a window procedure, and
+    /// the message may not outlive the call to the procedure.
+    pub(crate) unsafe fn from_raw(msg: UINT, wparam: WPARAM, lparam: LPARAM) -> Self {
         match msg {
             WM_CREATE => Message::Create,
             WM_SIZE => Message::Size(SizeData { wparam, lparam }),
@@ -268,13 +654,37 @@ impl Message {
             WM_INITDIALOG => Message::InitDialog,
             WM_PAINT => Message::Paint,
             WM_TIMER => Message::Timer(TimerData { wparam, lparam }),
+            WM_KEYDOWN => Message::KeyDown(KeyData { wparam, lparam }),
+            WM_KEYUP => Message::KeyUp(KeyData { wparam, lparam }),
+            WM_SYSKEYDOWN => Message::SystemKeyDown(KeyData { wparam, lparam }),
+            WM_SYSKEYUP => Message::SystemKeyUp(KeyData { wparam, lparam }),
+            WM_CHAR => Message::Char(CharData { wparam, lparam }),
+            WM_DEADCHAR => Message::DeadChar(CharData { wparam, lparam }),
+            WM_SYSCHAR => Message::SystemChar(CharData { wparam, lparam }),
+            WM_SYSDEADCHAR => Message::SystemDeadChar(CharData { wparam, lparam }),
+            WM_MOUSEMOVE => Message::MouseMove(MouseData { wparam, lparam }),
             WM_LBUTTONDOWN => Message::LeftMouseButtonDown(MouseData { wparam, lparam }),
             WM_RBUTTONDOWN => Message::RightMouseButtonDown(MouseData { wparam, lparam }),
             WM_MBUTTONDOWN => Message::MiddleMouseButtonDown(MouseData { wparam, lparam }),
+            WM_XBUTTONDOWN => Message::XButtonDown(MouseData { wparam, lparam }),
             WM_LBUTTONUP => Message::LeftMouseButtonUp(MouseData { wparam, lparam }),
             WM_RBUTTONUP => Message::RightMouseButtonUp(MouseData { wparam, lparam }),
             WM_MBUTTONUP => Message::MiddleMouseButtonUp(MouseData { wparam, lparam }),
+            WM_XBUTTONUP => Message::XButtonUp(MouseData { wparam, lparam }),
+            WM_LBUTTONDBLCLK => Message::LeftMouseButtonDoubleClick(MouseData { wparam, lparam }),
+            WM_RBUTTONDBLCLK => Message::RightMouseButtonDoubleClick(MouseData { wparam, lparam }),
+            WM_MBUTTONDBLCLK => Message::MiddleMouseButtonDoubleClick(MouseData { wparam, lparam }),
+            WM_XBUTTONDBLCLK => Message::XButtonDoubleClick(MouseData { wparam, lparam }),
+            WM_MOUSEWHEEL => Message::MouseWheel(WheelData { wparam, lparam }),
+            WM_MOUSEHWHEEL => Message::MouseHWheel(WheelData { wparam, lparam }),
+            WM_MOUSEHOVER => Message::MouseHover(MouseData { wparam, lparam }),
+            WM_MOUSELEAVE => Message::MouseLeave,
             WM_COMMAND => Message::Command(CommandData { wparam, lparam }),
+            WM_NOTIFY if lparam != 0 => {
+                Message::Notify(notify::NotifyData::new(NonNull::new_unchecked(lparam as _)))
+            }
+            WM_HSCROLL => Message::HorizontalScroll(ScrollData { wparam, lparam }),
+            WM_VSCROLL => Message::VerticalScroll(ScrollData { wparam, lparam }),
             WM_CTLCOLORDLG => Message::ControlColorDialog(ColorData { wparam, lparam }),
             WM_CTLCOLORSTATIC => Message::ControlColorStatic(ColorData { wparam, lparam }),
             _ => Message::Other {
@@ -286,11 +696,123 @@ impl Message {
     }
 }
 
+impl ButtonMessage {
+    pub(crate) fn from_raw(code: u16) -> Self {
+        match code {
+            BN_CLICKED => ButtonMessage::Clicked,
+            BN_DOUBLECLICKED => ButtonMessage::DoubleClicked,
+            BN_SETFOCUS => ButtonMessage::SetFocus,
+            BN_KILLFOCUS => ButtonMessage::KillFocus,
+            _ => ButtonMessage::Other { code },
+        }
+    }
+}
+
+impl TrackbarMessage {
+    pub(crate) fn from_raw(code: u16) -> Self {
+        match code as WPARAM {
+            TB_LINEUP => TrackbarMessage::LineUp,
+            TB_LINEDOWN => TrackbarMessage::LineDown,
+            TB_PAGEUP => TrackbarMessage::PageUp,
+            TB_PAGEDOWN => TrackbarMessage::PageDown,
+            TB_THUMBPOSITION => TrackbarMessage::ThumbPosition,
+            TB_THUMBTRACK => TrackbarMessage::ThumbTrack,
+            TB_TOP => TrackbarMessage::Top,
+            TB_BOTTOM => TrackbarMessage::Bottom,
+            TB_ENDTRACK => TrackbarMessage::EndTrack,
+            _ => TrackbarMessage::Other { code },
+        }
+    }
+}
+
+impl ComboBoxMessage {
+    pub(crate) fn from_raw(code: u16) -> Self {
+        match code {
+            CBN_SELCHANGE => ComboBoxMessage::SelectionChange,
+            CBN_SELENDOK => ComboBoxMessage::SelectionEndOk,
+            CBN_SELENDCANCEL => ComboBoxMessage::SelectionEndCancel,
+            CBN_EDITCHANGE => ComboBoxMessage::EditChange,
+            CBN_EDITUPDATE => ComboBoxMessage::EditUpdate,
+            CBN_DROPDOWN => ComboBoxMessage::DropDown,
+            CBN_CLOSEUP => ComboBoxMessage::CloseUp,
+            CBN_DBLCLK => ComboBoxMessage::DoubleClick,
+            CBN_SETFOCUS => ComboBoxMessage::SetFocus,
+            CBN_KILLFOCUS => ComboBoxMessage::KillFocus,
+            CBN_ERRSPACE => ComboBoxMessage::OutOfSpace,
+            _ => ComboBoxMessage::Other { code },
+        }
+    }
+}
+
+impl EditMessage {
+    pub(crate) fn from_raw(code: u16) -> Self {
+        match code {
+            EN_CHANGE => EditMessage::Change,
+            EN_UPDATE => EditMessage::Update,
+            EN_MAXTEXT => EditMessage::MaxText,
+            EN_SETFOCUS => EditMessage::SetFocus,
+            EN_KILLFOCUS => EditMessage::KillFocus,
+            EN_ERRSPACE => EditMessage::OutOfSpace,
+            EN_HSCROLL => EditMessage::HorizontalScroll,
+            EN_VSCROLL => EditMessage::VerticalScroll,
+            _ => EditMessage::Other { code },
+        }
+    }
+}
+
 impl ListBoxMessage {
     pub(crate) fn from_raw(code: u16) -> Self {
         match code {
             LBN_SELCHANGE => ListBoxMessage::SelectionChange,
+            LBN_SELCANCEL => ListBoxMessage::SelectionCancel,
+            LBN_DBLCLK => ListBoxMessage::DoubleClick,
+            LBN_SETFOCUS => ListBoxMessage::SetFocus,
+            LBN_KILLFOCUS => ListBoxMessage::KillFocus,
+            LBN_ERRSPACE => ListBoxMessage::OutOfSpace,
             _ => ListBoxMessage::Other { code },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_messages_do_not_share_state() {
+        let mut units = [0; 2];
+        '😀'.encode_utf16(&mut units);
+        let high = CharData {
+            wparam: units[0] as WPARAM,
+            lparam: 1,
+        };
+        let low = CharData {
+            wparam: units[1] as WPARAM,
+            lparam: 1,
+        };
+
+        assert_eq!(high.char(), None);
+        assert_eq!(low.char(), None);
+        assert_eq!(
+            CharData {
+                wparam: 'a' as WPARAM,
+                lparam: 1
+            }
+            .char(),
+            Some('a')
+        );
+
+        // A dead character in between does not lose the pending half.
+        let mut decoder = CharDecoder::new();
+        assert_eq!(high.decode(&mut decoder), None);
+        assert_eq!(
+            CharData {
+                wparam: '´' as WPARAM,
+                lparam: 1
+            }
+            .char(),
+            Some('´')
+        );
+        assert_eq!(low.decode(&mut decoder), Some('😀'));
+    }
+}
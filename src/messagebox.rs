@@ -1,9 +1,9 @@
-use crate::{Error, Result};
-use std::ffi::CString;
+use crate::{to_wide, Error, Result};
 use std::ptr;
 use winapi::shared::minwindef::UINT;
+use winapi::shared::winerror::ERROR_INVALID_DATA;
 use winapi::um::winuser::{
-    MessageBoxA, IDABORT, IDCANCEL, IDCONTINUE, IDIGNORE, IDNO, IDOK, IDRETRY, IDTRYAGAIN, IDYES,
+    MessageBoxW, IDABORT, IDCANCEL, IDCONTINUE, IDIGNORE, IDNO, IDOK, IDRETRY, IDTRYAGAIN, IDYES,
     MB_ABORTRETRYIGNORE, MB_APPLMODAL, MB_CANCELTRYCONTINUE, MB_DEFAULT_DESKTOP_ONLY,
     MB_DEFBUTTON2, MB_DEFBUTTON3, MB_DEFBUTTON4, MB_HELP, MB_ICONERROR, MB_ICONINFORMATION,
     MB_ICONQUESTION, MB_ICONWARNING, MB_OKCANCEL, MB_RETRYCANCEL, MB_RIGHT, MB_RTLREADING,
@@ -126,11 +126,11 @@ impl Button {
 /// If no config is provided, the message box defaults to containing one push button: OK.
 /// The first button is the default button
 pub fn message_box(caption: &str, text: &str, config: &[Config]) -> Result<Button> {
-    let caption = CString::new(caption)?;
-    let text = CString::new(text)?;
+    let caption = to_wide(caption)?;
+    let text = to_wide(text)?;
     let result = unsafe {
         // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-messagebox
-        MessageBoxA(
+        MessageBoxW(
             ptr::null_mut(),
             text.as_ptr(),
             caption.as_ptr(),
@@ -138,6 +138,9 @@ pub fn message_box(caption: &str, text: &str, config: &[Config]) -> Result<Butto
         )
     };
 
-    Button::from_id(result)
-        .map(|x| x.expect(&format!("invalid return code from message box: {}", result)))
+    // The message box can only be closed with one of the buttons it was configured to show.
+    Button::from_id(result)?.ok_or_else(|| {
+        debug_assert!(false, "invalid return code from message box: {}", result);
+        Error::from_code(ERROR_INVALID_DATA)
+    })
 }
@@ -0,0 +1,878 @@
+//! Notifications sent by common controls to their parent through the `Notify` message
+//! https://docs.microsoft.com/en-us/windows/win32/controls/wm-notify.
+//!
+//! Every notification starts with the same header (`NMHDR`), which tells what control sent it and
+//! the notification code. The code determines what structure the header is actually part of, so
+//! `NotifyData::notification` maps the codes to variants with the right data.
+use crate::control::tree_view::Node;
+use crate::message::VirtualKey;
+use crate::window;
+use std::marker::PhantomData;
+use std::ptr::{self, NonNull};
+use widestring::U16CStr;
+use winapi::um::commctrl::{
+    BCN_DROPDOWN, LVIS_FOCUSED, LVIS_SELECTED, LVN_BEGINDRAG, LVN_BEGINLABELEDITW, LVN_BEGINRDRAG,
+    LVN_COLUMNCLICK, LVN_DELETEALLITEMS, LVN_DELETEITEM, LVN_ENDLABELEDITW, LVN_INSERTITEM,
+    LVN_ITEMACTIVATE, LVN_ITEMCHANGED, LVN_ITEMCHANGING, LVN_KEYDOWN, NMLISTVIEW, NMLVDISPINFOW,
+    NMLVKEYDOWN, NMTCKEYDOWN, NMTOOLBARA, NMTREEVIEWA, NMTTDISPINFOA, NMTTDISPINFOW, NMTVDISPINFOW,
+    NMTVKEYDOWN, NM_CLICK, NM_DBLCLK, NM_HOVER, NM_KILLFOCUS, NM_OUTOFMEMORY, NM_RCLICK,
+    NM_RDBLCLK, NM_RELEASEDCAPTURE, NM_RETURN, NM_SETFOCUS, SBN_SIMPLEMODECHANGE, TBN_BEGINDRAG,
+    TBN_DROPDOWN, TBN_ENDDRAG, TCN_FOCUSCHANGE, TCN_KEYDOWN, TCN_SELCHANGE, TCN_SELCHANGING,
+    TTN_GETDISPINFOA, TTN_GETDISPINFOW, TTN_LINKCLICK, TTN_POP, TTN_SHOW, TVE_EXPAND,
+    TVN_BEGINDRAGA, TVN_BEGINDRAGW, TVN_BEGINLABELEDITW, TVN_BEGINRDRAGA, TVN_BEGINRDRAGW,
+    TVN_DELETEITEMA, TVN_DELETEITEMW, TVN_ENDLABELEDITW, TVN_ITEMEXPANDEDA, TVN_ITEMEXPANDEDW,
+    TVN_ITEMEXPANDINGA, TVN_ITEMEXPANDINGW, TVN_KEYDOWN, TVN_SELCHANGEDA, TVN_SELCHANGEDW,
+    TVN_SELCHANGINGA, TVN_SELCHANGINGW,
+};
+use winapi::um::stringapiset::WideCharToMultiByte;
+use winapi::um::winnls::CP_ACP;
+use winapi::um::winuser::NMHDR;
+
+/// The data of a `Notify` message. It borrows the structure sent by the control, which only
+/// lives while the message is being handled.
+#[derive(Debug)]
+pub struct NotifyData<'a> {
+    header: NonNull<NMHDR>,
+    // Some notifications are answered by filling in the structure.
+    _structure: PhantomData<&'a mut NMHDR>,
+}
+
+/// A notification, with the data specific to the control that sent it.
+#[derive(Debug)]
+pub enum Notification<'a> {
+    /// The user has clicked the left mouse button within the control.
+    Click,
+    /// The user has double-clicked the left mouse button within the control.
+    DoubleClick,
+    /// The user has clicked the right mouse button within the control.
+    RightClick,
+    /// The user has double-clicked the right mouse button within the control.
+    RightDoubleClick,
+    /// The control has the input focus and the user has pressed the ENTER key.
+    Return,
+    /// The control has received the input focus.
+    SetFocus,
+    /// The control has lost the input focus.
+    KillFocus,
+    /// The user has hovered the mouse over the control.
+    Hover,
+    /// The control could not complete an operation because there was not enough memory.
+    OutOfMemory,
+    /// The control is releasing mouse capture.
+    ReleasedCapture,
+    /// The user clicked the drop-down arrow of a split button.
+    SplitButtonDropDown,
+    ListView(ListViewNotification<'a>),
+    TreeView(TreeViewNotification<'a>),
+    Tab(TabNotification),
+    Toolbar(ToolbarNotification<'a>),
+    ToolTip(ToolTipNotification<'a>),
+    StatusBar(StatusBarNotification),
+    /// Any other notification, identified by its code.
+    Other(u32),
+}
+
+/// Notifications sent by a list view https://docs.microsoft.com/en-us/windows/win32/controls/bumper-list-view-control-reference-notifications.
+#[derive(Debug)]
+pub enum ListViewNotification<'a> {
+    /// An item is changing. Return `1` to prevent the change.
+    ItemChanging(ListViewData<'a>),
+    /// An item has changed.
+    ItemChanged(ListViewData<'a>),
+    /// A new item was inserted.
+    InsertItem(ListViewData<'a>),
+    /// An item is about to be deleted.
+    DeleteItem(ListViewData<'a>),
+    /// All items in the control are about to be deleted. Return `1` to prevent receiving
+    /// `DeleteItem` for each of them.
+    DeleteAllItems,
+    /// The user clicked a column header.
+    ColumnClick(ListViewData<'a>),
+    /// A drag-and-drop operation involving the left mouse button is being initiated.
+    BeginDrag(ListViewData<'a>),
+    /// A drag-and-drop operation involving the right mouse button is being initiated.
+    BeginRightDrag(ListViewData<'a>),
+    /// The user activates an item.
+    ItemActivate(ListViewData<'a>),
+    /// A key has been pressed.
+    KeyDown(VirtualKey),
+    /// The user is about to edit the text of an item. Return `1` to prevent it.
+    BeginLabelEdit(ListViewLabel<'a>),
+    /// The user has finished editing the text of an item. Return `1` to accept the new text.
+    EndLabelEdit(ListViewLabel<'a>),
+    Other(u32),
+}
+
+/// Notifications sent by a tree view https://docs.microsoft.com/en-us/windows/win32/controls/bumper-tree-view-control-reference-notifications.
+#[derive(Debug)]
+pub enum TreeViewNotification<'a> {
+    /// The selection is about to change. Return `1` to prevent the change.
+    SelectionChanging(TreeViewData<'a>),
+    /// The selection has changed.
+    SelectionChanged(TreeViewData<'a>),
+    /// An item is about to expand or collapse. Return `1` to prevent it.
+    ItemExpanding(TreeViewData<'a>),
+    /// An item has expanded or collapsed.
+    ItemExpanded(TreeViewData<'a>),
+    /// An item is being deleted.
+    DeleteItem(TreeViewData<'a>),
+    /// A drag-and-drop operation involving the left mouse button is being initiated.
+    BeginDrag(TreeViewData<'a>),
+    /// A drag-and-drop operation involving the right mouse button is being initiated.
+    BeginRightDrag(TreeViewData<'a>),
+    /// A key has been pressed.
+    KeyDown(VirtualKey),
+    /// The user is about to edit the text of an item. Return `1` to prevent it.
+    BeginLabelEdit(TreeViewLabel<'a>),
+    /// The user has finished editing the text of an item. Return `1` to accept the new text.
+    EndLabelEdit(TreeViewLabel<'a>),
+    Other(u32),
+}
+
+/// Notifications sent by a tab control https://docs.microsoft.com/en-us/windows/win32/controls/bumper-tab-control-reference-notifications.
+#[derive(Debug)]
+pub enum TabNotification {
+    /// The currently selected tab is about to change. Return `1` to prevent the change.
+    SelectionChanging,
+    /// The currently selected tab has changed.
+    SelectionChange,
+    /// The focus has changed between tabs.
+    FocusChange,
+    /// A key has been pressed.
+    KeyDown(VirtualKey),
+    Other(u32),
+}
+
+/// Notifications sent by a toolbar https://docs.microsoft.com/en-us/windows/win32/controls/bumper-toolbar-control-reference-notifications.
+#[derive(Debug)]
+pub enum ToolbarNotification<'a> {
+    /// The user clicked a drop-down button.
+    DropDown(ToolbarData<'a>),
+    /// The user has begun dragging a button.
+    BeginDrag(ToolbarData<'a>),
+    /// The user has stopped dragging a button.
+    EndDrag(ToolbarData<'a>),
+    Other(u32),
+}
+
+/// Notifications sent by a tooltip https://docs.microsoft.com/en-us/windows/win32/controls/bumper-tooltip-control-reference-notifications.
+#[derive(Debug)]
+pub enum ToolTipNotification<'a> {
+    /// The tooltip needs the text to display.
+    GetDisplayInfo(ToolTipText<'a>),
+    /// The tooltip is about to be displayed.
+    Show,
+    /// The tooltip is about to be hidden.
+    Pop,
+    /// A link in the tooltip has been clicked.
+    LinkClick,
+    Other(u32),
+}
+
+/// Notifications sent by a status bar https://docs.microsoft.com/en-us/windows/win32/controls/bumper-status-bars-reference-notifications.
+#[derive(Debug)]
+pub enum StatusBarNotification {
+    /// The simple mode changed.
+    SimpleModeChange,
+    Other(u32),
+}
+
+/// Information about an item or sub-item of a list view (`NMLISTVIEW`).
+#[derive(Debug)]
+pub struct ListViewData<'a> {
+    data: &'a NotifyData<'a>,
+}
+
+/// Information about the item whose text is being edited in a list view (`NMLVDISPINFO`).
+#[derive(Debug)]
+pub struct ListViewLabel<'a> {
+    data: &'a NotifyData<'a>,
+}
+
+/// Information about the items involved in a tree view notification (`NMTREEVIEW`).
+#[derive(Debug)]
+pub struct TreeViewData<'a> {
+    data: &'a NotifyData<'a>,
+}
+
+/// Information about the item whose text is being edited in a tree view (`NMTVDISPINFO`).
+#[derive(Debug)]
+pub struct TreeViewLabel<'a> {
+    data: &'a NotifyData<'a>,
+}
+
+/// Information about the button involved in a toolbar notification (`NMTOOLBAR`).
+#[derive(Debug)]
+pub struct ToolbarData<'a> {
+    data: &'a NotifyData<'a>,
+}
+
+/// Request of the text a tooltip should display (`NMTTDISPINFO`).
+#[derive(Debug)]
+pub struct ToolTipText<'a> {
+    data: &'a NotifyData<'a>,
+}
+
+impl<'a> NotifyData<'a> {
+    /// # Safety
+    ///
+    /// The header must be part of the structure corresponding to its notification code, and the
+    /// structure must be valid, and not be used elsewhere, for as long as `'a` lasts.
+    pub(crate) unsafe fn new(header: NonNull<NMHDR>) -> Self {
+        Self {
+            header,
+            _structure: PhantomData,
+        }
+    }
+
+    fn header(&self) -> &NMHDR {
+        unsafe { self.header.as_ref() }
+    }
+
+    /// Reinterprets the header as the structure it's part of.
+    ///
+    /// # Safety
+    ///
+    /// The notification code must correspond to the structure `T`.
+    unsafe fn cast<T>(&self) -> &T {
+        &*(self.header.as_ptr() as *const T)
+    }
+
+    /// Like `cast`, but to fill in the structure.
+    ///
+    /// # Safety
+    ///
+    /// The notification code must correspond to the structure `T`, and no reference to the
+    /// structure may be alive while the pointer is written through.
+    unsafe fn cast_mut<T>(&self) -> *mut T {
+        self.header.as_ptr() as *mut T
+    }
+
+    /// The control sending the notification.
+    pub fn window(&self) -> Option<window::Window<'_>> {
+        NonNull::new(self.header().hwndFrom).map(|hwnd| window::Window::Borrowed { hwnd })
+    }
+
+    /// Identifier of the control sending the notification.
+    pub fn id(&self) -> usize {
+        self.header().idFrom
+    }
+
+    /// The raw notification code.
+    pub fn code(&self) -> u32 {
+        self.header().code
+    }
+
+    /// Interpret the data as that of a list view notification. This is useful for the generic
+    /// notifications sent by list views, such as `Click` or `DoubleClick`, which also carry the
+    /// item involved (`NMITEMACTIVATE`).
+    pub fn list_view(&self) -> ListViewData<'_> {
+        ListViewData { data: self }
+    }
+
+    /// Interpret the notification based on its code.
+    pub fn notification(&self) -> Notification<'_> {
+        // Codes for the controls that also send text have an ANSI and a Unicode variant, which
+        // are both handled. The data they carry is read the same way for both.
+        let list_view = ListViewData { data: self };
+        let tree_view = TreeViewData { data: self };
+        let toolbar = ToolbarData { data: self };
+        unsafe {
+            match self.code() {
+                NM_CLICK => Notification::Click,
+                NM_DBLCLK => Notification::DoubleClick,
+                NM_RCLICK => Notification::RightClick,
+                NM_RDBLCLK => Notification::RightDoubleClick,
+                NM_RETURN => Notification::Return,
+                NM_SETFOCUS => Notification::SetFocus,
+                NM_KILLFOCUS => Notification::KillFocus,
+                NM_HOVER => Notification::Hover,
+                NM_OUTOFMEMORY => Notification::OutOfMemory,
+                NM_RELEASEDCAPTURE => Notification::ReleasedCapture,
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/bcn-dropdown
+                BCN_DROPDOWN => Notification::SplitButtonDropDown,
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/lvn-itemchanged
+                LVN_ITEMCHANGING => {
+                    Notification::ListView(ListViewNotification::ItemChanging(list_view))
+                }
+                LVN_ITEMCHANGED => {
+                    Notification::ListView(ListViewNotification::ItemChanged(list_view))
+                }
+                LVN_INSERTITEM => {
+                    Notification::ListView(ListViewNotification::InsertItem(list_view))
+                }
+                LVN_DELETEITEM => {
+                    Notification::ListView(ListViewNotification::DeleteItem(list_view))
+                }
+                LVN_DELETEALLITEMS => Notification::ListView(ListViewNotification::DeleteAllItems),
+                LVN_COLUMNCLICK => {
+                    Notification::ListView(ListViewNotification::ColumnClick(list_view))
+                }
+                LVN_BEGINDRAG => Notification::ListView(ListViewNotification::BeginDrag(list_view)),
+                LVN_BEGINRDRAG => {
+                    Notification::ListView(ListViewNotification::BeginRightDrag(list_view))
+                }
+                // `NMITEMACTIVATE` starts with the same fields as `NMLISTVIEW`.
+                LVN_ITEMACTIVATE => {
+                    Notification::ListView(ListViewNotification::ItemActivate(list_view))
+                }
+                LVN_KEYDOWN => Notification::ListView(ListViewNotification::KeyDown(
+                    VirtualKey::from_code(self.cast::<NMLVKEYDOWN>().wVKey as u8),
+                )),
+                // Only the Unicode variants are sent, because the control was created with
+                // `CreateWindowExW`.
+                LVN_BEGINLABELEDITW => {
+                    Notification::ListView(ListViewNotification::BeginLabelEdit(ListViewLabel {
+                        data: self,
+                    }))
+                }
+                LVN_ENDLABELEDITW => {
+                    Notification::ListView(ListViewNotification::EndLabelEdit(ListViewLabel {
+                        data: self,
+                    }))
+                }
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/tvn-selchanged
+                TVN_SELCHANGINGA | TVN_SELCHANGINGW => {
+                    Notification::TreeView(TreeViewNotification::SelectionChanging(tree_view))
+                }
+                TVN_SELCHANGEDA | TVN_SELCHANGEDW => {
+                    Notification::TreeView(TreeViewNotification::SelectionChanged(tree_view))
+                }
+                TVN_ITEMEXPANDINGA | TVN_ITEMEXPANDINGW => {
+                    Notification::TreeView(TreeViewNotification::ItemExpanding(tree_view))
+                }
+                TVN_ITEMEXPANDEDA | TVN_ITEMEXPANDEDW => {
+                    Notification::TreeView(TreeViewNotification::ItemExpanded(tree_view))
+                }
+                TVN_DELETEITEMA | TVN_DELETEITEMW => {
+                    Notification::TreeView(TreeViewNotification::DeleteItem(tree_view))
+                }
+                TVN_BEGINDRAGA | TVN_BEGINDRAGW => {
+                    Notification::TreeView(TreeViewNotification::BeginDrag(tree_view))
+                }
+                TVN_BEGINRDRAGA | TVN_BEGINRDRAGW => {
+                    Notification::TreeView(TreeViewNotification::BeginRightDrag(tree_view))
+                }
+                TVN_KEYDOWN => Notification::TreeView(TreeViewNotification::KeyDown(
+                    VirtualKey::from_code(self.cast::<NMTVKEYDOWN>().wVKey as u8),
+                )),
+                // Only the Unicode variants are sent, because the control was created with
+                // `CreateWindowExW`.
+                TVN_BEGINLABELEDITW => {
+                    Notification::TreeView(TreeViewNotification::BeginLabelEdit(TreeViewLabel {
+                        data: self,
+                    }))
+                }
+                TVN_ENDLABELEDITW => {
+                    Notification::TreeView(TreeViewNotification::EndLabelEdit(TreeViewLabel {
+                        data: self,
+                    }))
+                }
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/tcn-selchange
+                TCN_SELCHANGING => Notification::Tab(TabNotification::SelectionChanging),
+                TCN_SELCHANGE => Notification::Tab(TabNotification::SelectionChange),
+                TCN_FOCUSCHANGE => Notification::Tab(TabNotification::FocusChange),
+                TCN_KEYDOWN => Notification::Tab(TabNotification::KeyDown(VirtualKey::from_code(
+                    self.cast::<NMTCKEYDOWN>().wVKey as u8,
+                ))),
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/tbn-dropdown
+                TBN_DROPDOWN => Notification::Toolbar(ToolbarNotification::DropDown(toolbar)),
+                TBN_BEGINDRAG => Notification::Toolbar(ToolbarNotification::BeginDrag(toolbar)),
+                TBN_ENDDRAG => Notification::Toolbar(ToolbarNotification::EndDrag(toolbar)),
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/ttn-getdispinfo
+                TTN_GETDISPINFOA | TTN_GETDISPINFOW => {
+                    Notification::ToolTip(ToolTipNotification::GetDisplayInfo(ToolTipText {
+                        data: self,
+                    }))
+                }
+                TTN_SHOW => Notification::ToolTip(ToolTipNotification::Show),
+                TTN_POP => Notification::ToolTip(ToolTipNotification::Pop),
+                TTN_LINKCLICK => Notification::ToolTip(ToolTipNotification::LinkClick),
+
+                // https://docs.microsoft.com/en-us/windows/win32/controls/sbn-simplemodechange
+                SBN_SIMPLEMODECHANGE => {
+                    Notification::StatusBar(StatusBarNotification::SimpleModeChange)
+                }
+
+                code => Notification::Other(code),
+            }
+        }
+    }
+}
+
+impl ListViewData<'_> {
+    fn info(&self) -> &NMLISTVIEW {
+        unsafe { self.data.cast() }
+    }
+
+    /// Index of the item, or `None` if the notification is not about a specific item.
+    pub fn item(&self) -> Option<usize> {
+        if self.info().iItem < 0 {
+            None
+        } else {
+            Some(self.info().iItem as usize)
+        }
+    }
+
+    /// Index of the sub-item (or column, for `ColumnClick`).
+    pub fn sub_item(&self) -> usize {
+        self.info().iSubItem as usize
+    }
+
+    /// Whether the item is now selected.
+    pub fn selected(&self) -> bool {
+        (self.info().uNewState & LVIS_SELECTED) != 0
+    }
+
+    /// Whether the item was selected before the change.
+    pub fn was_selected(&self) -> bool {
+        (self.info().uOldState & LVIS_SELECTED) != 0
+    }
+
+    /// Whether the item now has the focus.
+    pub fn focused(&self) -> bool {
+        (self.info().uNewState & LVIS_FOCUSED) != 0
+    }
+
+    /// Whether the item had the focus before the change.
+    pub fn was_focused(&self) -> bool {
+        (self.info().uOldState & LVIS_FOCUSED) != 0
+    }
+
+    /// The application-defined value associated with the item.
+    pub fn param(&self) -> isize {
+        self.info().lParam
+    }
+}
+
+impl ListViewLabel<'_> {
+    fn info(&self) -> &NMLVDISPINFOW {
+        unsafe { self.data.cast() }
+    }
+
+    /// Index of the item being edited.
+    pub fn item(&self) -> usize {
+        self.info().item.iItem as usize
+    }
+
+    /// The application-defined value associated with the item.
+    pub fn param(&self) -> isize {
+        self.info().item.lParam
+    }
+
+    /// The new text of the item, for `EndLabelEdit`. It's `None` if the user canceled editing.
+    pub fn text(&self) -> Option<String> {
+        let text = self.info().item.pszText;
+        if text.is_null() {
+            None
+        } else {
+            Some(unsafe { U16CStr::from_ptr_str(text) }.to_string_lossy())
+        }
+    }
+}
+
+impl TreeViewData<'_> {
+    // `NMTREEVIEWA` and `NMTREEVIEWW` only differ in the type of the text pointers.
+    fn info(&self) -> &NMTREEVIEWA {
+        unsafe { self.data.cast() }
+    }
+
+    /// Whether the item is being expanded (as opposed to collapsed), for the expansion
+    /// notifications.
+    pub fn expanding(&self) -> bool {
+        (self.info().action as usize & TVE_EXPAND) != 0
+    }
+
+    /// The new item, such as the item being selected, expanded, collapsed or dragged.
+    pub fn new_item(&self) -> Option<Node> {
+        Node::from_raw(self.info().itemNew.hItem)
+    }
+
+    /// The application-defined value associated with the new item.
+    pub fn new_param(&self) -> isize {
+        self.info().itemNew.lParam
+    }
+
+    /// The old item, such as the item that was selected, or the one being deleted.
+    pub fn old_item(&self) -> Option<Node> {
+        Node::from_raw(self.info().itemOld.hItem)
+    }
+
+    /// The application-defined value associated with the old item.
+    pub fn old_param(&self) -> isize {
+        self.info().itemOld.lParam
+    }
+
+    /// The x coordinate of the mouse where dragging began, in client coordinates, for the
+    /// drag notifications.
+    pub fn drag_x(&self) -> i32 {
+        self.info().ptDrag.x
+    }
+
+    /// The y coordinate of the mouse where dragging began, in client coordinates, for the
+    /// drag notifications.
+    pub fn drag_y(&self) -> i32 {
+        self.info().ptDrag.y
+    }
+}
+
+impl TreeViewLabel<'_> {
+    fn info(&self) -> &NMTVDISPINFOW {
+        unsafe { self.data.cast() }
+    }
+
+    /// The item being edited.
+    pub fn item(&self) -> Option<Node> {
+        Node::from_raw(self.info().item.hItem)
+    }
+
+    /// The application-defined value associated with the item.
+    pub fn param(&self) -> isize {
+        self.info().item.lParam
+    }
+
+    /// The new text of the item, for `EndLabelEdit`. It's `None` if the user canceled editing.
+    pub fn text(&self) -> Option<String> {
+        let text = self.info().item.pszText;
+        if text.is_null() {
+            None
+        } else {
+            Some(unsafe { U16CStr::from_ptr_str(text) }.to_string_lossy())
+        }
+    }
+}
+
+impl ToolbarData<'_> {
+    fn info(&self) -> &NMTOOLBARA {
+        unsafe { self.data.cast() }
+    }
+
+    /// Command identifier of the button.
+    pub fn button_id(&self) -> i32 {
+        self.info().iItem
+    }
+}
+
+impl ToolTipText<'_> {
+    /// Identifier of the tool the tooltip is for (or its window handle, if the tool was added
+    /// with `TTF_IDISHWND`).
+    pub fn tool_id(&self) -> usize {
+        self.data.id()
+    }
+
+    /// Sets the text the tooltip should display. The text is cut short, without splitting any
+    /// character, if it does not fit in the 79 code units the tooltip has room for.
+    pub fn set_text(&self, text: &str) {
+        // The text is copied to the buffer in the structure, so it does not need to outlive
+        // the notification.
+        unsafe {
+            if self.data.code() == TTN_GETDISPINFOW {
+                let info = self.data.cast_mut::<NMTTDISPINFOW>();
+                let buffer = &mut (*info).szText;
+                let len = encode_wide_prefix(text, &mut buffer[..]);
+                buffer[len] = 0;
+                (*info).lpszText = buffer.as_mut_ptr();
+                (*info).hinst = ptr::null_mut();
+            } else {
+                let info = self.data.cast_mut::<NMTTDISPINFOA>();
+                let buffer = &mut (*info).szText;
+                let len = encode_ansi_prefix(text, &mut buffer[..]);
+                buffer[len] = 0;
+                (*info).lpszText = buffer.as_mut_ptr();
+                (*info).hinst = ptr::null_mut();
+            }
+        }
+    }
+}
+
+/// Encodes as many whole characters of the text as fit in the buffer as UTF-16, leaving room for
+/// the null terminator. Returns the number of code units written.
+pub(crate) fn encode_wide_prefix(text: &str, buffer: &mut [u16]) -> usize {
+    let capacity = buffer.len() - 1;
+    let mut len = 0;
+    for c in text.chars() {
+        if len + c.len_utf16() > capacity {
+            break;
+        }
+        len += c.encode_utf16(&mut buffer[len..]).len();
+    }
+    len
+}
+
+/// Encodes as many whole characters of the text as fit in the buffer in the ANSI code page,
+/// leaving room for the null terminator. Returns the number of bytes written.
+fn encode_ansi_prefix(text: &str, buffer: &mut [i8]) -> usize {
+    // Every character takes at least one byte, so more than these can't fit.
+    let capacity = buffer.len() - 1;
+    let wide = text
+        .chars()
+        .take(capacity)
+        .collect::<String>()
+        .encode_utf16()
+        .collect::<Vec<_>>();
+    let mut end = wide.len();
+
+    // Characters may take more than one byte, in which case the conversion fails if they don't
+    // all fit, so the last character is dropped until they do.
+    while end > 0 {
+        let written = unsafe {
+            WideCharToMultiByte(
+                CP_ACP,
+                0,
+                wide.as_ptr(),
+                end as i32,
+                buffer.as_mut_ptr(),
+                capacity as i32,
+                ptr::null(),
+                ptr::null_mut(),
+            )
+        };
+        if written > 0 {
+            return written as usize;
+        }
+        let last_is_pair = end >= 2 && (0xDC00..=0xDFFF).contains(&wide[end - 1]);
+        end -= if last_is_pair { 2 } else { 1 };
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use winapi::um::commctrl::{
+        LVN_BEGINLABELEDITA, NMTOOLBARW, NM_FIRST, TBN_FIRST, TCN_FIRST, TVN_FIRST,
+    };
+    use winapi::um::winuser::{VK_DELETE, VK_F2, VK_RETURN};
+
+    // Large enough for any of the structures the notifications are part of.
+    #[repr(C)]
+    struct Structure {
+        header: NMHDR,
+        rest: [usize; 64],
+    }
+
+    fn notify(code: u32, check: impl FnOnce(Notification)) {
+        let mut structure = Structure {
+            header: unsafe { std::mem::zeroed() },
+            rest: [0; 64],
+        };
+        structure.header.code = code;
+        structure.header.idFrom = 42;
+        let data = unsafe { NotifyData::new(NonNull::from(&mut structure).cast()) };
+        assert_eq!(data.code(), code);
+        assert_eq!(data.id(), 42);
+        assert!(data.window().is_none());
+        check(data.notification());
+    }
+
+    macro_rules! assert_maps {
+        ($($code:expr => $pattern:pat),* $(,)?) => {$(
+            notify($code, |n| assert!(matches!(n, $pattern), "{} mapped to {:?}", stringify!($code), n));
+        )*};
+    }
+
+    #[test]
+    fn generic_codes() {
+        assert_maps! {
+            NM_CLICK => Notification::Click,
+            NM_DBLCLK => Notification::DoubleClick,
+            NM_RCLICK => Notification::RightClick,
+            NM_RDBLCLK => Notification::RightDoubleClick,
+            NM_RETURN => Notification::Return,
+            NM_SETFOCUS => Notification::SetFocus,
+            NM_KILLFOCUS => Notification::KillFocus,
+            NM_HOVER => Notification::Hover,
+            NM_OUTOFMEMORY => Notification::OutOfMemory,
+            NM_RELEASEDCAPTURE => Notification::ReleasedCapture,
+            NM_FIRST.wrapping_sub(98) => Notification::Other(_),
+        }
+    }
+
+    #[test]
+    fn button_codes() {
+        assert_maps! {
+            BCN_DROPDOWN => Notification::SplitButtonDropDown,
+        }
+    }
+
+    #[test]
+    fn list_view_codes() {
+        use ListViewNotification as N;
+        assert_maps! {
+            LVN_ITEMCHANGING => Notification::ListView(N::ItemChanging(_)),
+            LVN_ITEMCHANGED => Notification::ListView(N::ItemChanged(_)),
+            LVN_INSERTITEM => Notification::ListView(N::InsertItem(_)),
+            LVN_DELETEITEM => Notification::ListView(N::DeleteItem(_)),
+            LVN_DELETEALLITEMS => Notification::ListView(N::DeleteAllItems),
+            LVN_COLUMNCLICK => Notification::ListView(N::ColumnClick(_)),
+            LVN_BEGINDRAG => Notification::ListView(N::BeginDrag(_)),
+            LVN_BEGINRDRAG => Notification::ListView(N::BeginRightDrag(_)),
+            LVN_ITEMACTIVATE => Notification::ListView(N::ItemActivate(_)),
+            LVN_KEYDOWN => Notification::ListView(N::KeyDown(_)),
+            LVN_BEGINLABELEDITW => Notification::ListView(N::BeginLabelEdit(_)),
+            LVN_ENDLABELEDITW => Notification::ListView(N::EndLabelEdit(_)),
+            // Never sent to Unicode windows, so they're not recognized.
+            LVN_BEGINLABELEDITA => Notification::Other(_),
+        }
+    }
+
+    #[test]
+    fn tree_view_codes() {
+        use TreeViewNotification as N;
+        assert_maps! {
+            TVN_SELCHANGINGA => Notification::TreeView(N::SelectionChanging(_)),
+            TVN_SELCHANGINGW => Notification::TreeView(N::SelectionChanging(_)),
+            TVN_SELCHANGEDA => Notification::TreeView(N::SelectionChanged(_)),
+            TVN_SELCHANGEDW => Notification::TreeView(N::SelectionChanged(_)),
+            TVN_ITEMEXPANDINGA => Notification::TreeView(N::ItemExpanding(_)),
+            TVN_ITEMEXPANDINGW => Notification::TreeView(N::ItemExpanding(_)),
+            TVN_ITEMEXPANDEDA => Notification::TreeView(N::ItemExpanded(_)),
+            TVN_ITEMEXPANDEDW => Notification::TreeView(N::ItemExpanded(_)),
+            TVN_DELETEITEMA => Notification::TreeView(N::DeleteItem(_)),
+            TVN_DELETEITEMW => Notification::TreeView(N::DeleteItem(_)),
+            TVN_BEGINDRAGA => Notification::TreeView(N::BeginDrag(_)),
+            TVN_BEGINDRAGW => Notification::TreeView(N::BeginDrag(_)),
+            TVN_BEGINRDRAGA => Notification::TreeView(N::BeginRightDrag(_)),
+            TVN_BEGINRDRAGW => Notification::TreeView(N::BeginRightDrag(_)),
+            TVN_KEYDOWN => Notification::TreeView(N::KeyDown(_)),
+            TVN_BEGINLABELEDITW => Notification::TreeView(N::BeginLabelEdit(_)),
+            TVN_ENDLABELEDITW => Notification::TreeView(N::EndLabelEdit(_)),
+            TVN_FIRST - 99 => Notification::Other(_),
+        }
+    }
+
+    #[test]
+    fn tab_codes() {
+        use TabNotification as N;
+        assert_maps! {
+            TCN_SELCHANGING => Notification::Tab(N::SelectionChanging),
+            TCN_SELCHANGE => Notification::Tab(N::SelectionChange),
+            TCN_FOCUSCHANGE => Notification::Tab(N::FocusChange),
+            TCN_KEYDOWN => Notification::Tab(N::KeyDown(_)),
+            TCN_FIRST - 9 => Notification::Other(_),
+        }
+    }
+
+    #[test]
+    fn toolbar_codes() {
+        use ToolbarNotification as N;
+        assert_maps! {
+            TBN_DROPDOWN => Notification::Toolbar(N::DropDown(_)),
+            TBN_BEGINDRAG => Notification::Toolbar(N::BeginDrag(_)),
+            TBN_ENDDRAG => Notification::Toolbar(N::EndDrag(_)),
+            TBN_FIRST - 99 => Notification::Other(_),
+        }
+    }
+
+    #[test]
+    fn tooltip_codes() {
+        use ToolTipNotification as N;
+        assert_maps! {
+            TTN_GETDISPINFOA => Notification::ToolTip(N::GetDisplayInfo(_)),
+            TTN_GETDISPINFOW => Notification::ToolTip(N::GetDisplayInfo(_)),
+            TTN_SHOW => Notification::ToolTip(N::Show),
+            TTN_POP => Notification::ToolTip(N::Pop),
+            TTN_LINKCLICK => Notification::ToolTip(N::LinkClick),
+        }
+    }
+
+    #[test]
+    fn status_bar_codes() {
+        assert_maps! {
+            SBN_SIMPLEMODECHANGE => Notification::StatusBar(StatusBarNotification::SimpleModeChange),
+        }
+    }
+
+    #[test]
+    fn key_down_reads_the_key() {
+        let mut list_view: NMLVKEYDOWN = unsafe { std::mem::zeroed() };
+        list_view.hdr.code = LVN_KEYDOWN;
+        list_view.wVKey = VK_DELETE as u16;
+        let data = unsafe { NotifyData::new(NonNull::from(&mut list_view).cast()) };
+        assert!(matches!(
+            data.notification(),
+            Notification::ListView(ListViewNotification::KeyDown(VirtualKey::Delete))
+        ));
+
+        let mut tree_view: NMTVKEYDOWN = unsafe { std::mem::zeroed() };
+        tree_view.hdr.code = TVN_KEYDOWN;
+        tree_view.wVKey = VK_F2 as u16;
+        let data = unsafe { NotifyData::new(NonNull::from(&mut tree_view).cast()) };
+        assert!(matches!(
+            data.notification(),
+            Notification::TreeView(TreeViewNotification::KeyDown(VirtualKey::Function(2)))
+        ));
+
+        let mut tab: NMTCKEYDOWN = unsafe { std::mem::zeroed() };
+        tab.hdr.code = TCN_KEYDOWN;
+        tab.wVKey = VK_RETURN as u16;
+        let data = unsafe { NotifyData::new(NonNull::from(&mut tab).cast()) };
+        assert!(matches!(
+            data.notification(),
+            Notification::Tab(TabNotification::KeyDown(VirtualKey::Enter))
+        ));
+    }
+
+    #[test]
+    fn toolbar_button() {
+        let mut toolbar: NMTOOLBARW = unsafe { std::mem::zeroed() };
+        toolbar.hdr.code = TBN_DROPDOWN;
+        toolbar.iItem = 7;
+        let data = unsafe { NotifyData::new(NonNull::from(&mut toolbar).cast()) };
+        match data.notification() {
+            Notification::Toolbar(ToolbarNotification::DropDown(button)) => {
+                assert_eq!(button.button_id(), 7)
+            }
+            n => panic!("unexpected {:?}", n),
+        }
+    }
+
+    #[test]
+    fn tooltip_text_is_cut_at_character_boundaries() {
+        let mut info: NMTTDISPINFOW = unsafe { std::mem::zeroed() };
+        info.hdr.code = TTN_GETDISPINFOW;
+        let data = unsafe { NotifyData::new(NonNull::from(&mut info).cast()) };
+        let text = format!("{}😀", "a".repeat(78));
+        match data.notification() {
+            Notification::ToolTip(ToolTipNotification::GetDisplayInfo(tooltip)) => {
+                tooltip.set_text(&text)
+            }
+            n => panic!("unexpected {:?}", n),
+        }
+        assert_eq!(info.lpszText, info.szText.as_mut_ptr());
+        let len = info.szText.iter().position(|&c| c == 0).unwrap();
+        assert_eq!(
+            String::from_utf16(&info.szText[..len]).unwrap(),
+            "a".repeat(78)
+        );
+    }
+
+    #[test]
+    fn wide_prefix() {
+        let mut buffer = [0xFFFFu16; 5];
+        assert_eq!(encode_wide_prefix("abc", &mut buffer), 3);
+        assert_eq!(&buffer[..3], &[b'a' as u16, b'b' as u16, b'c' as u16]);
+        assert_eq!(encode_wide_prefix("abcdef", &mut buffer), 4);
+        // The pair would take the last two units, leaving no room for the terminator.
+        assert_eq!(encode_wide_prefix("abc😀", &mut buffer), 3);
+        assert_eq!(encode_wide_prefix("ab😀", &mut buffer), 4);
+        assert_eq!(encode_wide_prefix("", &mut buffer), 0);
+    }
+
+    #[test]
+    fn ansi_prefix() {
+        let mut buffer = [0i8; 5];
+        assert_eq!(encode_ansi_prefix("abcdef", &mut buffer), 4);
+        assert_eq!(
+            &buffer[..4],
+            &[b'a' as i8, b'b' as i8, b'c' as i8, b'd' as i8]
+        );
+        assert_eq!(encode_ansi_prefix("", &mut buffer), 0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Generation of Rust constants from the identifiers in a resource header (`resource.h`).
+//!
+//! Resource scripts and the code using them need to agree on the numeric identifiers of
+//! resources and controls. Generating the constants from the same header the script includes
+//! means they can't drift apart.
+use super::script::{self, Error};
+use super::{symbols, Define, Script};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Reads the `#define`s in a resource header and generates Rust source code with a `u16`
+/// constant for each one that evaluates to a number, such as `(IDC_FIRST + 1)`.
+///
+/// Function-like macros and macros without a body (such as include guards) are not identifiers,
+/// so they are ignored. Names the system headers already define with the same value, such as
+/// `IDC_STATIC` or `IDOK`, are ignored too, since they are not specific to the application.
+///
+/// Fails if a macro cannot be evaluated, if a name is redefined with a different value, or if two
+/// names with the same prefix (the part up to and including the first underscore, such as `IDC_`)
+/// share the same value. Names with different prefixes may share values, since resources of
+/// different kinds (such as a menu and a dialog) live in separate namespaces.
+pub fn generate<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    constants(&script::parse_file(path)?)
+}
+
+fn constants(header: &Script) -> Result<String, Error> {
+    let mut names = HashMap::<&str, &Define>::new();
+    let mut values = HashMap::<(&str, u16), &Define>::new();
+    let mut source = String::from("// Generated from a resource header. Do not edit.\n");
+
+    for define in header.defines.iter() {
+        let error = |message| Err(Error::at_line(define.file.clone(), define.line, message));
+        let value = match (define.value, &define.error) {
+            (Some(value), _) => value,
+            (None, Some(message)) => {
+                return error(format!(
+                    "`{}` cannot be evaluated: {}",
+                    define.name, message
+                ))
+            }
+            (None, None) => continue,
+        };
+        if symbols::lookup(&define.name) == Some(value) {
+            continue;
+        }
+
+        let id = match u16::try_from(value) {
+            Ok(id) => id,
+            Err(_) => return error(format!("`{}` is out of range: {}", define.name, value)),
+        };
+
+        if let Some(previous) = names.insert(&define.name, define) {
+            if previous.value == define.value {
+                continue;
+            }
+            return error(format!(
+                "`{}` redefined with a different value (previously defined at line {})",
+                define.name, previous.line
+            ));
+        }
+
+        let prefix = match define.name.find('_') {
+            Some(end) => &define.name[..=end],
+            None => &define.name,
+        };
+        if let Some(previous) = values.insert((prefix, id), define) {
+            return error(format!(
+                "`{}` has the same value as `{}` ({})",
+                define.name, previous.name, id
+            ));
+        }
+
+        writeln!(source, "#[allow(dead_code)]").unwrap();
+        writeln!(source, "pub const {}: u16 = {};", define.name, id).unwrap();
+    }
+
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate_source(header: &str) -> Result<String, Error> {
+        constants(&script::parse(header)?)
+    }
+
+    fn constant_lines(source: &str) -> Vec<&str> {
+        source
+            .lines()
+            .filter(|l| l.starts_with("pub const"))
+            .collect()
+    }
+
+    #[test]
+    fn example_header() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/dlg_one/resource.h");
+        let source = generate(path).unwrap();
+        assert!(source.starts_with("// Generated from a resource header. Do not edit.\n"));
+        assert_eq!(
+            constant_lines(&source),
+            vec![
+                "pub const IDR_MYMENU: u16 = 101;",
+                "pub const IDD_ABOUT: u16 = 102;",
+                "pub const ID_FILE_EXIT: u16 = 40001;",
+                "pub const ID_HELP_ABOUT: u16 = 40002;",
+            ]
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let source = generate_source(
+            "#define IDC_FIRST 1000\n\
+             #define IDC_SECOND (IDC_FIRST + 1)\n\
+             #define IDC_LAST IDC_FIRST + 2 * 5 - (8 >> 1)\n\
+             #define IDC_MASK (0x10 | 0x01)\n",
+        )
+        .unwrap();
+        assert_eq!(
+            constant_lines(&source),
+            vec![
+                "pub const IDC_FIRST: u16 = 1000;",
+                "pub const IDC_SECOND: u16 = 1001;",
+                "pub const IDC_LAST: u16 = 1006;",
+                "pub const IDC_MASK: u16 = 17;",
+            ]
+        );
+    }
+
+    #[test]
+    fn ignored_defines() {
+        let source = generate_source(
+            "#ifndef RESOURCE_H\n\
+             #define RESOURCE_H\n\
+             #define MAKE_ID(x) (x + 1)\n\
+             #define IDC_STATIC -1\n\
+             #define IDOK 1\n\
+             #define IDC_TEXT 1000\n\
+             #endif\n",
+        )
+        .unwrap();
+        assert_eq!(
+            constant_lines(&source),
+            vec!["pub const IDC_TEXT: u16 = 1000;"]
+        );
+    }
+
+    #[test]
+    fn system_names_with_other_values_are_kept() {
+        let source = generate_source("#define IDOK 7\n").unwrap();
+        assert_eq!(constant_lines(&source), vec!["pub const IDOK: u16 = 7;"]);
+    }
+
+    #[test]
+    fn identical_redefinition() {
+        let source = generate_source("#define IDC_A 5\n#define IDC_A (4 + 1)\n").unwrap();
+        assert_eq!(constant_lines(&source), vec!["pub const IDC_A: u16 = 5;"]);
+    }
+
+    #[test]
+    fn shared_values_across_prefixes() {
+        let source = generate_source("#define IDD_MAIN 101\n#define IDR_MENU 101\n").unwrap();
+        assert_eq!(constant_lines(&source).len(), 2);
+    }
+
+    #[test]
+    fn duplicate_values() {
+        let error = generate_source("#define IDC_A 5\n#define IDC_B 5\n").unwrap_err();
+        assert_eq!(error.message(), "`IDC_B` has the same value as `IDC_A` (5)");
+        assert_eq!(error.line(), 2);
+    }
+
+    #[test]
+    fn conflicting_redefinition() {
+        let error =
+            generate_source("#define IDC_A 5\n#undef IDC_A\n#define IDC_A 6\n").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "`IDC_A` redefined with a different value (previously defined at line 1)"
+        );
+        assert_eq!(error.line(), 3);
+    }
+
+    #[test]
+    fn out_of_range() {
+        let error = generate_source("#define IDC_BIG 70000\n").unwrap_err();
+        assert_eq!(error.message(), "`IDC_BIG` is out of range: 70000");
+
+        let error = generate_source("#define IDC_NEGATIVE -2\n").unwrap_err();
+        assert_eq!(error.message(), "`IDC_NEGATIVE` is out of range: -2");
+    }
+
+    #[test]
+    fn unevaluable_defines() {
+        let error = generate_source("#define IDC_A 1\n#define IDC_B 1/0\n").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "`IDC_B` cannot be evaluated: division by zero"
+        );
+        assert_eq!(error.line(), 2);
+
+        let error = generate_source("#define IDC_A (IDC_UNKNOWN + 1)\n").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "`IDC_A` cannot be evaluated: undefined symbol `IDC_UNKNOWN`"
+        );
+
+        let error = generate_source("#define APP_NAME \"app\"\n").unwrap_err();
+        assert_eq!(
+            error.message(),
+            "`APP_NAME` cannot be evaluated: expected expression, found \"app\""
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Resources https://docs.microsoft.com/en-us/windows/win32/menurc/resources.
+//!
+//! The types in this module describe the resources an application can embed in its executable
+//! (dialogs, menus, icons, bitmaps, string tables and accelerators). They can be obtained by
+//! parsing a resource script (`.rc`) with the [`script`] module, and compiled with the [`res`]
+//! module. Build scripts will usually want to use [`compile`], which does both, and
+//! [`generate_ids`], to share the identifiers in the script's header with the Rust code.
+//!
+//! Unlike the rest of the crate, nothing in here calls into the Windows API, so it can be used
+//! from build scripts running on any host.
+pub mod header;
+pub mod res;
+pub mod script;
+mod symbols;
+
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// The language resources are assigned when the script does not specify otherwise
+/// (`LANG_ENGLISH`, `SUBLANG_ENGLISH_US`).
+pub const DEFAULT_LANGUAGE: u16 = 0x0409;
+
+/// Identifies a resource, or a class or menu referenced by a dialog, either by number or by name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Id {
+    Ordinal(u16),
+    Name(String),
+}
+
+/// A parsed resource script.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Script {
+    /// Every file included by the script, in the order they were found. Headers that were read
+    /// are listed by the path they were found at, and system headers (such as `windows.h`, whose
+    /// definitions are built-in) by the name they were included with.
+    pub includes: Vec<PathBuf>,
+    /// Every object-like macro defined while processing the script, in definition order.
+    pub defines: Vec<Define>,
+    /// The resources defined by the script, in definition order.
+    pub resources: Vec<Resource>,
+    /// The string tables defined by the script, in definition order.
+    pub string_tables: Vec<StringTable>,
+}
+
+/// A `#define` directive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Define {
+    pub name: String,
+    /// The numeric value of the macro, or `None` if it does not evaluate to a number.
+    pub value: Option<i64>,
+    /// Why the body of the macro could not be evaluated. `None` if it was evaluated, or if it's a
+    /// function-like macro or has no body, since those are not meant to be numbers.
+    pub error: Option<String>,
+    /// Path to the file containing the definition, if it was read from disk.
+    pub file: Option<PathBuf>,
+    /// One-based line where the definition was found.
+    pub line: usize,
+}
+
+/// A single named resource.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resource {
+    pub name: Id,
+    /// The language identifier (`MAKELANGID(primary, sub)`) of the resource.
+    pub language: u16,
+    pub data: Data,
+}
+
+/// The contents of a resource.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Data {
+    Dialog(Dialog),
+    Menu(Menu),
+    /// An icon stored in a `.ico` file.
+    Icon(PathBuf),
+    /// A bitmap stored in a `.bmp` file.
+    Bitmap(PathBuf),
+    Accelerators(Vec<Accelerator>),
+    Version(VersionInfo),
+}
+
+/// Dialog box template https://docs.microsoft.com/en-us/windows/win32/menurc/dialog-resource.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dialog {
+    /// Whether this is an extended (`DIALOGEX`) template.
+    pub extended: bool,
+    pub style: u32,
+    pub extended_style: u32,
+    /// Context help identifier. Only used by extended templates.
+    pub help_id: u32,
+    /// Position and size, in dialog units.
+    pub x: i16,
+    pub y: i16,
+    pub width: i16,
+    pub height: i16,
+    pub caption: String,
+    /// Custom window class for the dialog, if it does not use the default one.
+    pub class: Option<Id>,
+    pub menu: Option<Id>,
+    pub font: Option<Font>,
+    pub controls: Vec<Control>,
+}
+
+/// The font used by the text in a dialog box and its controls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Font {
+    pub point_size: u16,
+    /// Only used by extended templates.
+    pub weight: u16,
+    /// Only used by extended templates.
+    pub italic: bool,
+    /// Only used by extended templates.
+    pub charset: u8,
+    pub face: String,
+}
+
+/// A control inside a dialog box template.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Control {
+    /// The window class of the control. System classes are referred to by ordinal (see the
+    /// associated constants such as [`Control::BUTTON`]).
+    pub class: Id,
+    /// The initial text of the control, or the resource it displays (such as an icon).
+    pub text: Id,
+    /// Control identifier. Non-extended templates only keep the low word.
+    pub id: u32,
+    /// Position and size, in dialog units.
+    pub x: i16,
+    pub y: i16,
+    pub width: i16,
+    pub height: i16,
+    pub style: u32,
+    pub extended_style: u32,
+    /// Context help identifier. Only used by extended templates.
+    pub help_id: u32,
+}
+
+/// Menu template https://docs.microsoft.com/en-us/windows/win32/menurc/menu-resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Menu {
+    /// Whether this is an extended (`MENUEX`) template.
+    pub extended: bool,
+    pub items: Vec<MenuItem>,
+}
+
+/// An item in a menu template, which may open a sub-menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MenuItem {
+    pub text: String,
+    /// Command identifier sent when the item is selected.
+    pub id: u32,
+    /// `MF_*` option flags in normal templates, or `MFT_*` type flags in extended templates.
+    pub flags: u32,
+    /// `MFS_*` state flags. Only used by extended templates.
+    pub state: u32,
+    /// Context help identifier. Only used by pop-up items in extended templates.
+    pub help_id: u32,
+    /// Items of the sub-menu opened by this item, if it's a pop-up.
+    pub popup: Option<Vec<MenuItem>>,
+}
+
+/// A group of strings https://docs.microsoft.com/en-us/windows/win32/menurc/stringtable-resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StringTable {
+    pub language: u16,
+    /// Pairs of string identifier and string.
+    pub strings: Vec<(u16, String)>,
+}
+
+/// Keyboard accelerator https://docs.microsoft.com/en-us/windows/win32/menurc/accelerators-resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Accelerator {
+    /// `F*` flags (`FVIRTKEY`, `FNOINVERT`, `FSHIFT`, `FCONTROL` and `FALT`).
+    pub flags: u16,
+    /// The virtual-key code if `FVIRTKEY` is set, or the character code otherwise.
+    pub key: u16,
+    /// Command identifier sent when the accelerator is used.
+    pub id: u16,
+}
+
+/// Version information https://docs.microsoft.com/en-us/windows/win32/menurc/versioninfo-resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionInfo {
+    /// The four parts of the file version, most significant first.
+    pub file_version: [u16; 4],
+    /// The four parts of the product version, most significant first.
+    pub product_version: [u16; 4],
+    /// `VS_FF_*` bits that are valid in `file_flags`.
+    pub file_flags_mask: u32,
+    /// `VS_FF_*` attributes of the file.
+    pub file_flags: u32,
+    /// `VOS_*` operating system the file was designed for.
+    pub file_os: u32,
+    /// `VFT_*` general type of the file.
+    pub file_type: u32,
+    /// `VFT2_*` function of the file, for drivers and fonts.
+    pub file_subtype: u32,
+    /// The `StringFileInfo` and `VarFileInfo` blocks.
+    pub entries: Vec<VersionEntry>,
+}
+
+/// A block or value inside version information.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VersionEntry {
+    Block {
+        key: String,
+        entries: Vec<VersionEntry>,
+    },
+    Value {
+        key: String,
+        values: Vec<VersionValue>,
+    },
+}
+
+/// A single piece of data in a version information value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VersionValue {
+    String(String),
+    Word(u16),
+    DoubleWord(u32),
+}
+
+/// Compiles a resource script and links the resources into the binaries of the package.
+///
+/// This is meant to be called from `build.rs`, and does nothing when the target is not Windows.
+/// Cargo is told to re-run the build script whenever the script or any file it uses changes.
+///
+/// # Panics
+///
+/// Panics if the script cannot be parsed or compiled, which fails the build with the reason.
+pub fn compile<P: AsRef<Path>>(path: P) {
+    let path = path.as_ref();
+    if env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("windows") {
+        return;
+    }
+
+    let arch = env::var("CARGO_CFG_TARGET_ARCH").expect("compile must be called from build.rs");
+    let machine = res::Machine::from_target_arch(&arch)
+        .unwrap_or_else(|| panic!("unsupported target architecture: {}", arch));
+    let script = script::parse_file(path).unwrap_or_else(|e| panic!("{}", e));
+
+    println!("cargo:rerun-if-changed={}", path.display());
+    for include in script.includes.iter().filter(|p| p.is_file()) {
+        println!("cargo:rerun-if-changed={}", include.display());
+    }
+    for resource in script.resources.iter() {
+        if let Data::Icon(file) | Data::Bitmap(file) = &resource.data {
+            println!("cargo:rerun-if-changed={}", file.display());
+        }
+    }
+
+    let out_dir =
+        PathBuf::from(env::var_os("OUT_DIR").expect("compile must be called from build.rs"));
+    let stem = path.file_stem().unwrap_or_else(|| "resources".as_ref());
+    let object = out_dir.join(stem).with_extension("res.o");
+    File::create(&object)
+        .map(BufWriter::new)
+        .and_then(|file| res::write_coff(&script, machine, file))
+        .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+
+    println!("cargo:rustc-link-arg-bins={}", object.display());
+}
+
+/// Generates Rust constants for the identifiers defined in a resource header, so that they can
+/// be included in the package's code.
+///
+/// This is meant to be called from `build.rs`. The constants are written to a file named after
+/// the header in the output directory, and can be included with:
+///
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/resource.rs"));
+/// ```
+///
+/// # Panics
+///
+/// Panics if the header cannot be processed, which fails the build with the reason.
+pub fn generate_ids<P: AsRef<Path>>(path: P) {
+    let path = path.as_ref();
+    let source = header::generate(path).unwrap_or_else(|e| panic!("{}", e));
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let out_dir =
+        PathBuf::from(env::var_os("OUT_DIR").expect("generate_ids must be called from build.rs"));
+    let stem = path.file_stem().unwrap_or_else(|| "resource".as_ref());
+    let file = out_dir.join(stem).with_extension("rs");
+    fs::write(&file, source).unwrap_or_else(|e| panic!("{}: {}", file.display(), e));
+}
+
+impl Control {
+    /// Ordinal of the system button class.
+    pub const BUTTON: u16 = 0x0080;
+    /// Ordinal of the system edit control class.
+    pub const EDIT: u16 = 0x0081;
+    /// Ordinal of the system static control class.
+    pub const STATIC: u16 = 0x0082;
+    /// Ordinal of the system list box class.
+    pub const LIST_BOX: u16 = 0x0083;
+    /// Ordinal of the system scroll bar class.
+    pub const SCROLL_BAR: u16 = 0x0084;
+    /// Ordinal of the system combo box class.
+    pub const COMBO_BOX: u16 = 0x0085;
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Id::Ordinal(n) => write!(f, "{}", n),
+            Id::Name(name) => f.write_str(name),
+        }
+    }
+}
+
+impl From<u16> for Id {
+    fn from(ordinal: u16) -> Self {
+        Id::Ordinal(ordinal)
+    }
+}
+
+impl From<&str> for Id {
+    fn from(name: &str) -> Self {
+        Id::Name(name.to_owned())
+    }
+}
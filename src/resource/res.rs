@@ -0,0 +1,1032 @@
+//! Writer for compiled resources https://docs.microsoft.com/en-us/windows/win32/menurc/resource-file-formats.
+//!
+//! Resources can be written in the `.res` format produced by `rc.exe`, or as a COFF object file
+//! with a `.rsrc` section, which both the MSVC and GNU linkers accept as-is.
+use super::{
+    Accelerator, Data, Dialog, Id, Menu, MenuItem, Script, VersionEntry, VersionInfo, VersionValue,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+const RT_BITMAP: u16 = 2;
+const RT_ICON: u16 = 3;
+const RT_MENU: u16 = 4;
+const RT_DIALOG: u16 = 5;
+const RT_STRING: u16 = 6;
+const RT_ACCELERATOR: u16 = 9;
+const RT_GROUP_ICON: u16 = 14;
+const RT_VERSION: u16 = 16;
+
+// Memory flags are meaningless in 32-bit Windows, but rc still writes these.
+const MOVEABLE_PURE: u16 = 0x0030;
+const MOVEABLE_PURE_DISCARDABLE: u16 = 0x1030;
+const MOVEABLE_DISCARDABLE: u16 = 0x1010;
+
+const DS_SETFONT: u32 = 0x0040;
+const MF_POPUP: u16 = 0x0010;
+const MF_END: u16 = 0x0080;
+const MF_SEPARATOR: u16 = 0x0800;
+
+const IMAGE_SCN_CNT_INITIALIZED_DATA: u32 = 0x0000_0040;
+const IMAGE_SCN_MEM_READ: u32 = 0x4000_0000;
+const IMAGE_SYM_CLASS_STATIC: u8 = 3;
+
+/// The architecture an object file is meant for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Machine {
+    X86,
+    X64,
+    Arm,
+    Arm64,
+}
+
+/// A single compiled resource.
+struct Entry {
+    kind: u16,
+    name: Id,
+    language: u16,
+    flags: u16,
+    data: Vec<u8>,
+}
+
+impl Machine {
+    /// Determines the machine from the name of a target architecture, as found in Cargo's
+    /// `CARGO_CFG_TARGET_ARCH` environment variable.
+    pub fn from_target_arch(arch: &str) -> Option<Self> {
+        Some(match arch {
+            "x86" => Machine::X86,
+            "x86_64" => Machine::X64,
+            "arm" => Machine::Arm,
+            "aarch64" => Machine::Arm64,
+            _ => return None,
+        })
+    }
+
+    fn id(self) -> u16 {
+        match self {
+            Machine::X86 => 0x014C,
+            Machine::X64 => 0x8664,
+            Machine::Arm => 0x01C4,
+            Machine::Arm64 => 0xAA64,
+        }
+    }
+
+    /// Relocation type for a 32-bit address relative to the image base.
+    fn rva_relocation(self) -> u16 {
+        match self {
+            Machine::X86 => 0x0007,   // IMAGE_REL_I386_DIR32NB
+            Machine::X64 => 0x0003,   // IMAGE_REL_AMD64_ADDR32NB
+            Machine::Arm => 0x0002,   // IMAGE_REL_ARM_ADDR32NB
+            Machine::Arm64 => 0x0002, // IMAGE_REL_ARM64_ADDR32NB
+        }
+    }
+}
+
+/// Writes the resources defined by the script in the `.res` format.
+///
+/// Files referenced by the script, such as icons, are read at this point.
+pub fn write_res<W: Write>(script: &Script, mut out: W) -> io::Result<()> {
+    let mut buffer = Vec::new();
+
+    // A leading empty resource tells 32-bit resource files apart from 16-bit ones.
+    let empty = Entry {
+        kind: 0,
+        name: Id::Ordinal(0),
+        language: 0,
+        flags: 0,
+        data: Vec::new(),
+    };
+    for entry in std::iter::once(empty).chain(entries(script)?) {
+        put_u32(&mut buffer, entry.data.len() as u32);
+        let header_start = buffer.len();
+        put_u32(&mut buffer, 0);
+        put_id(&mut buffer, &Id::Ordinal(entry.kind));
+        put_id(&mut buffer, &entry.name);
+        align(&mut buffer, 4);
+        put_u32(&mut buffer, 0); // data version
+        put_u16(&mut buffer, entry.flags);
+        put_u16(&mut buffer, entry.language);
+        put_u32(&mut buffer, 0); // version
+        put_u32(&mut buffer, 0); // characteristics
+
+        let header_size = (buffer.len() - header_start + 4) as u32;
+        buffer[header_start..header_start + 4].copy_from_slice(&header_size.to_le_bytes());
+        buffer.extend_from_slice(&entry.data);
+        align(&mut buffer, 4);
+    }
+
+    out.write_all(&buffer)
+}
+
+/// Writes the resources defined by the script as a COFF object file for the given machine.
+///
+/// Files referenced by the script, such as icons, are read at this point.
+pub fn write_coff<W: Write>(script: &Script, machine: Machine, mut out: W) -> io::Result<()> {
+    let entries = entries(script)?;
+
+    // The resource directory is a tree of type, name and language, each level sorted with named
+    // entries first (case-insensitively) and then by ordinal.
+    let mut tree = BTreeMap::<u16, BTreeMap<(u8, Vec<u16>, u16), BTreeMap<u16, usize>>>::new();
+    for (i, entry) in entries.iter().enumerate() {
+        let name_key = match &entry.name {
+            Id::Name(name) => (0, name.to_uppercase().encode_utf16().collect(), 0),
+            Id::Ordinal(ordinal) => (1, Vec::new(), *ordinal),
+        };
+        tree.entry(entry.kind)
+            .or_default()
+            .entry(name_key)
+            .or_default()
+            .insert(entry.language, i);
+    }
+
+    let leaves = tree
+        .values()
+        .flat_map(|names| names.values())
+        .flat_map(|languages| languages.values().copied())
+        .collect::<Vec<_>>();
+
+    // Lay out every table first, so that each can refer to the offset of the others.
+    let mut offset = directory_size(tree.len());
+    let mut name_directories = Vec::new();
+    for names in tree.values() {
+        name_directories.push(offset);
+        offset += directory_size(names.len());
+    }
+    let mut language_directories = Vec::new();
+    for languages in tree.values().flat_map(|names| names.values()) {
+        language_directories.push(offset);
+        offset += directory_size(languages.len());
+    }
+    let mut strings = Vec::new();
+    for (kind, name, _) in tree.values().flat_map(|names| names.keys()) {
+        if *kind == 0 {
+            strings.push(offset);
+            offset += 2 + 2 * name.len();
+        }
+    }
+    offset = (offset + 3) & !3;
+    let descriptions = offset;
+    offset += 16 * entries.len();
+    let mut data = Vec::new();
+    for &i in leaves.iter() {
+        offset = (offset + 7) & !7;
+        data.push(offset);
+        offset += entries[i].data.len();
+    }
+
+    let mut section = Vec::with_capacity(offset);
+    let (mut name_directory, mut language_directory, mut string, mut leaf) = (0, 0, 0, 0);
+
+    put_directory_header(&mut section, 0, tree.len());
+    for kind in tree.keys() {
+        put_u32(&mut section, *kind as u32);
+        put_u32(
+            &mut section,
+            0x8000_0000 | name_directories[name_directory] as u32,
+        );
+        name_directory += 1;
+    }
+    for names in tree.values() {
+        let named = names.keys().filter(|(kind, _, _)| *kind == 0).count();
+        put_directory_header(&mut section, named, names.len() - named);
+        for (kind, _, ordinal) in names.keys() {
+            if *kind == 0 {
+                put_u32(&mut section, 0x8000_0000 | strings[string] as u32);
+                string += 1;
+            } else {
+                put_u32(&mut section, *ordinal as u32);
+            }
+            put_u32(
+                &mut section,
+                0x8000_0000 | language_directories[language_directory] as u32,
+            );
+            language_directory += 1;
+        }
+    }
+    for languages in tree.values().flat_map(|names| names.values()) {
+        put_directory_header(&mut section, 0, languages.len());
+        for language in languages.keys() {
+            put_u32(&mut section, *language as u32);
+            put_u32(&mut section, (descriptions + 16 * leaf) as u32);
+            leaf += 1;
+        }
+    }
+    for (kind, name, _) in tree.values().flat_map(|names| names.keys()) {
+        if *kind == 0 {
+            put_u16(&mut section, name.len() as u16);
+            name.iter().for_each(|c| put_u16(&mut section, *c));
+        }
+    }
+    align(&mut section, 4);
+
+    let mut relocations = Vec::new();
+    for (&i, offset) in leaves.iter().zip(data.iter()) {
+        // The offset is relative to the section, and the linker turns it into an RVA.
+        relocations.push(section.len() as u32);
+        put_u32(&mut section, *offset as u32);
+        put_u32(&mut section, entries[i].data.len() as u32);
+        put_u32(&mut section, 0); // code page
+        put_u32(&mut section, 0); // reserved
+    }
+    for &i in leaves.iter() {
+        align(&mut section, 8);
+        section.extend_from_slice(&entries[i].data);
+    }
+    align(&mut section, 4);
+
+    if relocations.len() > u16::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "too many resources for a single object file",
+        ));
+    }
+
+    let mut object = Vec::new();
+    let relocations_offset = 20 + 40 + section.len();
+    let symbols_offset = relocations_offset + 10 * relocations.len();
+
+    // File header.
+    put_u16(&mut object, machine.id());
+    put_u16(&mut object, 1); // number of sections
+    put_u32(&mut object, 0); // time stamp
+    put_u32(&mut object, symbols_offset as u32);
+    put_u32(&mut object, 2); // number of symbols
+    put_u16(&mut object, 0); // size of optional header
+    put_u16(&mut object, 0); // characteristics
+
+    // Section header.
+    object.extend_from_slice(b".rsrc\0\0\0");
+    put_u32(&mut object, 0); // virtual size
+    put_u32(&mut object, 0); // virtual address
+    put_u32(&mut object, section.len() as u32);
+    put_u32(&mut object, 20 + 40);
+    put_u32(&mut object, relocations_offset as u32);
+    put_u32(&mut object, 0); // line numbers
+    put_u16(&mut object, relocations.len() as u16);
+    put_u16(&mut object, 0); // number of line numbers
+    put_u32(
+        &mut object,
+        IMAGE_SCN_CNT_INITIALIZED_DATA | IMAGE_SCN_MEM_READ,
+    );
+
+    object.extend_from_slice(&section);
+    for address in relocations.iter() {
+        put_u32(&mut object, *address);
+        put_u32(&mut object, 0); // symbol index
+        put_u16(&mut object, machine.rva_relocation());
+    }
+
+    // Section symbol, and its auxiliary section definition record.
+    object.extend_from_slice(b".rsrc\0\0\0");
+    put_u32(&mut object, 0); // value
+    put_u16(&mut object, 1); // section number
+    put_u16(&mut object, 0); // type
+    object.push(IMAGE_SYM_CLASS_STATIC);
+    object.push(1); // number of auxiliary symbols
+    put_u32(&mut object, section.len() as u32);
+    put_u16(&mut object, relocations.len() as u16);
+    put_u16(&mut object, 0); // number of line numbers
+    put_u32(&mut object, 0); // checksum
+    put_u16(&mut object, 0); // number
+    object.extend_from_slice(&[0; 4]); // selection and padding
+
+    // Empty string table.
+    put_u32(&mut object, 4);
+
+    out.write_all(&object)
+}
+
+/// Serializes a dialog into a `DLGTEMPLATE`, or a `DLGTEMPLATEEX` if it's extended.
+pub(crate) fn dialog_template(dialog: &Dialog) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    let style = if dialog.font.is_some() {
+        dialog.style | DS_SETFONT
+    } else {
+        dialog.style
+    };
+
+    if dialog.extended {
+        put_u16(&mut buffer, 1); // version
+        put_u16(&mut buffer, 0xFFFF); // signature
+        put_u32(&mut buffer, dialog.help_id);
+        put_u32(&mut buffer, dialog.extended_style);
+        put_u32(&mut buffer, style);
+    } else {
+        put_u32(&mut buffer, style);
+        put_u32(&mut buffer, dialog.extended_style);
+    }
+    put_u16(&mut buffer, dialog.controls.len() as u16);
+    put_u16(&mut buffer, dialog.x as u16);
+    put_u16(&mut buffer, dialog.y as u16);
+    put_u16(&mut buffer, dialog.width as u16);
+    put_u16(&mut buffer, dialog.height as u16);
+    put_optional_id(&mut buffer, dialog.menu.as_ref());
+    put_optional_id(&mut buffer, dialog.class.as_ref());
+    put_str(&mut buffer, &dialog.caption);
+
+    if let Some(font) = &dialog.font {
+        put_u16(&mut buffer, font.point_size);
+        if dialog.extended {
+            put_u16(&mut buffer, font.weight);
+            buffer.push(font.italic as u8);
+            buffer.push(font.charset);
+        }
+        put_str(&mut buffer, &font.face);
+    }
+
+    for control in dialog.controls.iter() {
+        align(&mut buffer, 4);
+        if dialog.extended {
+            put_u32(&mut buffer, control.help_id);
+            put_u32(&mut buffer, control.extended_style);
+            put_u32(&mut buffer, control.style);
+        } else {
+            put_u32(&mut buffer, control.style);
+            put_u32(&mut buffer, control.extended_style);
+        }
+        put_u16(&mut buffer, control.x as u16);
+        put_u16(&mut buffer, control.y as u16);
+        put_u16(&mut buffer, control.width as u16);
+        put_u16(&mut buffer, control.height as u16);
+        if dialog.extended {
+            put_u32(&mut buffer, control.id);
+        } else {
+            put_u16(&mut buffer, control.id as u16);
+        }
+        put_id(&mut buffer, &control.class);
+        put_id(&mut buffer, &control.text);
+        put_u16(&mut buffer, 0); // creation data
+    }
+
+    buffer
+}
+
+fn entries(script: &Script) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut next_icon = 1;
+
+    for resource in script.resources.iter() {
+        let (kind, flags, data) = match &resource.data {
+            Data::Dialog(dialog) => (
+                RT_DIALOG,
+                MOVEABLE_PURE_DISCARDABLE,
+                dialog_template(dialog),
+            ),
+            Data::Menu(menu) => (RT_MENU, MOVEABLE_PURE_DISCARDABLE, menu_template(menu)),
+            Data::Icon(path) => {
+                let file = read(path)?;
+                let group = icon_group(&file, next_icon, |id, image| {
+                    entries.push(Entry {
+                        kind: RT_ICON,
+                        name: Id::Ordinal(id),
+                        language: resource.language,
+                        flags: MOVEABLE_DISCARDABLE,
+                        data: image.to_vec(),
+                    })
+                })
+                .ok_or_else(|| invalid_file(path, "icon"))?;
+                // `idCount` in the group header is the number of images that were given an identifier.
+                next_icon += u16::from_le_bytes([group[4], group[5]]);
+                (RT_GROUP_ICON, MOVEABLE_PURE_DISCARDABLE, group)
+            }
+            Data::Bitmap(path) => {
+                let file = read(path)?;
+                // Bitmap resources are the same as the file, without the `BITMAPFILEHEADER`.
+                if file.len() < 14 || !file.starts_with(b"BM") {
+                    return Err(invalid_file(path, "bitmap"));
+                }
+                (RT_BITMAP, MOVEABLE_PURE, file[14..].to_vec())
+            }
+            Data::Accelerators(accelerators) => (
+                RT_ACCELERATOR,
+                MOVEABLE_PURE,
+                accelerator_table(accelerators),
+            ),
+            Data::Version(info) => (RT_VERSION, MOVEABLE_PURE, version_info(info)),
+        };
+
+        entries.push(Entry {
+            kind,
+            name: resource.name.clone(),
+            language: resource.language,
+            flags,
+            data,
+        });
+    }
+
+    // Strings are stored in blocks of sixteen, named after the upper twelve bits of their
+    // identifier plus one.
+    let mut blocks = Vec::<(u16, u16, Vec<&str>)>::new();
+    for table in script.string_tables.iter() {
+        for (id, string) in table.strings.iter() {
+            let key = (id / 16 + 1, table.language);
+            let index = match blocks.iter().position(|(b, l, _)| (*b, *l) == key) {
+                Some(index) => index,
+                None => {
+                    blocks.push((key.0, key.1, vec![""; 16]));
+                    blocks.len() - 1
+                }
+            };
+            blocks[index].2[(id % 16) as usize] = string;
+        }
+    }
+    for (block, language, strings) in blocks {
+        let mut data = Vec::new();
+        for string in strings {
+            let string = string.encode_utf16().collect::<Vec<_>>();
+            put_u16(&mut data, string.len() as u16);
+            string.into_iter().for_each(|c| put_u16(&mut data, c));
+        }
+        entries.push(Entry {
+            kind: RT_STRING,
+            name: Id::Ordinal(block),
+            language,
+            flags: MOVEABLE_PURE_DISCARDABLE,
+            data,
+        });
+    }
+
+    let mut seen = HashSet::new();
+    for entry in entries.iter() {
+        if !seen.insert((entry.kind, &entry.name, entry.language)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("duplicate resource `{}` of type {}", entry.name, entry.kind),
+            ));
+        }
+    }
+
+    Ok(entries)
+}
+
+fn read(path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+}
+
+fn invalid_file(path: &Path, kind: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: not a valid {} file", path.display(), kind),
+    )
+}
+
+/// Splits an `.ico` file into its images, which become separate resources numbered from
+/// `first_id`, and returns the group that refers to them (`GRPICONDIR`).
+fn icon_group<F: FnMut(u16, &[u8])>(file: &[u8], first_id: u16, mut image: F) -> Option<Vec<u8>> {
+    let word = |offset: usize| {
+        Some(u16::from_le_bytes([
+            *file.get(offset)?,
+            *file.get(offset + 1)?,
+        ]))
+    };
+    let dword = |offset: usize| Some(word(offset)? as u32 | (word(offset + 2)? as u32) << 16);
+
+    if word(0)? != 0 || word(2)? != 1 {
+        return None;
+    }
+    let count = word(4)?;
+
+    let mut group = Vec::new();
+    put_u16(&mut group, 0); // reserved
+    put_u16(&mut group, 1); // type
+    put_u16(&mut group, count);
+    for i in 0..count {
+        let entry = 6 + 16 * i as usize;
+        let size = dword(entry + 8)? as usize;
+        let offset = dword(entry + 12)? as usize;
+        let data = file.get(offset..offset.checked_add(size)?)?;
+
+        group.extend_from_slice(file.get(entry..entry + 8)?);
+        put_u32(&mut group, size as u32);
+        put_u16(&mut group, first_id + i);
+        image(first_id + i, data);
+    }
+
+    Some(group)
+}
+
+fn menu_template(menu: &Menu) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    if menu.extended {
+        put_u16(&mut buffer, 1); // version
+        put_u16(&mut buffer, 4); // offset to the items
+        put_u32(&mut buffer, 0); // help identifier
+        put_extended_menu_items(&mut buffer, &menu.items);
+    } else {
+        put_u16(&mut buffer, 0); // version
+        put_u16(&mut buffer, 0); // header size
+        put_menu_items(&mut buffer, &menu.items);
+    }
+    buffer
+}
+
+fn put_menu_items(buffer: &mut Vec<u8>, items: &[MenuItem]) {
+    for (i, item) in items.iter().enumerate() {
+        let mut flags = item.flags as u16;
+        if i + 1 == items.len() {
+            flags |= MF_END;
+        }
+
+        match &item.popup {
+            Some(popup) => {
+                put_u16(buffer, flags | MF_POPUP);
+                put_str(buffer, &item.text);
+                put_menu_items(buffer, popup);
+            }
+            // Separators are written as empty items with no identifier, like rc does.
+            None if flags & MF_SEPARATOR != 0 => {
+                put_u16(buffer, flags & !MF_SEPARATOR);
+                put_u16(buffer, 0);
+                put_str(buffer, "");
+            }
+            None => {
+                put_u16(buffer, flags);
+                put_u16(buffer, item.id as u16);
+                put_str(buffer, &item.text);
+            }
+        }
+    }
+}
+
+fn put_extended_menu_items(buffer: &mut Vec<u8>, items: &[MenuItem]) {
+    for (i, item) in items.iter().enumerate() {
+        align(buffer, 4);
+        put_u32(buffer, item.flags);
+        put_u32(buffer, item.state);
+        put_u32(buffer, item.id);
+
+        let mut info = 0;
+        if item.popup.is_some() {
+            info |= 0x01;
+        }
+        if i + 1 == items.len() {
+            info |= MF_END;
+        }
+        put_u16(buffer, info);
+        put_str(buffer, &item.text);
+
+        if let Some(popup) = &item.popup {
+            align(buffer, 4);
+            put_u32(buffer, item.help_id);
+            put_extended_menu_items(buffer, popup);
+        }
+    }
+}
+
+fn accelerator_table(accelerators: &[Accelerator]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for (i, accelerator) in accelerators.iter().enumerate() {
+        let mut flags = accelerator.flags;
+        if i + 1 == accelerators.len() {
+            flags |= 0x80;
+        }
+        put_u16(&mut buffer, flags);
+        put_u16(&mut buffer, accelerator.key);
+        put_u16(&mut buffer, accelerator.id);
+        put_u16(&mut buffer, 0); // padding
+    }
+    buffer
+}
+
+fn version_info(info: &VersionInfo) -> Vec<u8> {
+    let version = |v: [u16; 4]| {
+        [
+            (v[0] as u32) << 16 | v[1] as u32,
+            (v[2] as u32) << 16 | v[3] as u32,
+        ]
+    };
+
+    // `VS_FIXEDFILEINFO`.
+    let mut fixed = Vec::new();
+    put_u32(&mut fixed, 0xFEEF_04BD); // signature
+    put_u32(&mut fixed, 0x0001_0000); // structure version
+    version(info.file_version)
+        .iter()
+        .for_each(|v| put_u32(&mut fixed, *v));
+    version(info.product_version)
+        .iter()
+        .for_each(|v| put_u32(&mut fixed, *v));
+    put_u32(&mut fixed, info.file_flags_mask);
+    put_u32(&mut fixed, info.file_flags);
+    put_u32(&mut fixed, info.file_os);
+    put_u32(&mut fixed, info.file_type);
+    put_u32(&mut fixed, info.file_subtype);
+    put_u32(&mut fixed, 0); // date
+    put_u32(&mut fixed, 0);
+
+    let mut buffer = Vec::new();
+    put_version_node(
+        &mut buffer,
+        "VS_VERSION_INFO",
+        &fixed,
+        fixed.len() as u16,
+        false,
+        |b| put_version_entries(b, &info.entries),
+    );
+    buffer
+}
+
+fn put_version_entries(buffer: &mut Vec<u8>, entries: &[VersionEntry]) {
+    for entry in entries {
+        match entry {
+            VersionEntry::Block { key, entries } => {
+                put_version_node(buffer, key, &[], 0, true, |b| {
+                    put_version_entries(b, entries)
+                });
+            }
+            VersionEntry::Value { key, values } => {
+                let mut value = Vec::new();
+                let mut text = false;
+                for v in values {
+                    match v {
+                        VersionValue::String(s) => {
+                            text = true;
+                            put_str(&mut value, s);
+                        }
+                        VersionValue::Word(n) => put_u16(&mut value, *n),
+                        VersionValue::DoubleWord(n) => put_u32(&mut value, *n),
+                    }
+                }
+                // The length of text values is measured in characters rather than bytes.
+                let length = if text { value.len() / 2 } else { value.len() };
+                put_version_node(buffer, key, &value, length as u16, text, |_| {});
+            }
+        }
+    }
+}
+
+fn put_version_node<F: FnOnce(&mut Vec<u8>)>(
+    buffer: &mut Vec<u8>,
+    key: &str,
+    value: &[u8],
+    value_length: u16,
+    text: bool,
+    children: F,
+) {
+    align(buffer, 4);
+    let start = buffer.len();
+    put_u16(buffer, 0);
+    put_u16(buffer, value_length);
+    put_u16(buffer, text as u16);
+    put_str(buffer, key);
+    align(buffer, 4);
+    buffer.extend_from_slice(value);
+    children(buffer);
+
+    let length = (buffer.len() - start) as u16;
+    buffer[start..start + 2].copy_from_slice(&length.to_le_bytes());
+}
+
+fn put_directory_header(buffer: &mut Vec<u8>, named: usize, ids: usize) {
+    put_u32(buffer, 0); // characteristics
+    put_u32(buffer, 0); // time stamp
+    put_u16(buffer, 0); // major version
+    put_u16(buffer, 0); // minor version
+    put_u16(buffer, named as u16);
+    put_u16(buffer, ids as u16);
+}
+
+fn directory_size(entries: usize) -> usize {
+    16 + 8 * entries
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Writes a null-terminated UTF-16 string.
+fn put_str(buffer: &mut Vec<u8>, s: &str) {
+    s.encode_utf16().for_each(|c| put_u16(buffer, c));
+    put_u16(buffer, 0);
+}
+
+fn put_id(buffer: &mut Vec<u8>, id: &Id) {
+    match id {
+        Id::Ordinal(ordinal) => {
+            put_u16(buffer, 0xFFFF);
+            put_u16(buffer, *ordinal);
+        }
+        Id::Name(name) => put_str(buffer, name),
+    }
+}
+
+fn put_optional_id(buffer: &mut Vec<u8>, id: Option<&Id>) {
+    match id {
+        Some(id) => put_id(buffer, id),
+        None => put_u16(buffer, 0),
+    }
+}
+
+fn align(buffer: &mut Vec<u8>, alignment: usize) {
+    let padding = (alignment - buffer.len() % alignment) % alignment;
+    buffer.resize(buffer.len() + padding, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::{script, Control, Font};
+    use std::path::PathBuf;
+
+    // The reference files were compiled with `llvm-rc`, from the scripts after running them
+    // through the C preprocessor with the definitions from `windows.h` they need.
+    fn assert_same_res(script: PathBuf, expected: &[u8]) {
+        let script = script::parse_file(&script).unwrap_or_else(|e| panic!("{}", e));
+        let mut actual = Vec::new();
+        write_res(&script, &mut actual).unwrap();
+        if let Some(i) = actual.iter().zip(expected).position(|(a, e)| a != e) {
+            panic!(
+                "first difference at offset {:#x}: {:02x?} != {:02x?}",
+                i,
+                &actual[i..actual.len().min(i + 16)],
+                &expected[i..expected.len().min(i + 16)],
+            );
+        }
+        assert_eq!(actual.len(), expected.len());
+    }
+
+    fn example(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("examples")
+            .join(name)
+            .join(name)
+            .with_extension("rc")
+    }
+
+    fn testdata(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/resource/testdata")
+            .join(name)
+    }
+
+    macro_rules! example_tests {
+        ($($name:ident),*) => {$(
+            #[test]
+            fn $name() {
+                assert_same_res(
+                    example(stringify!($name)),
+                    include_bytes!(concat!("testdata/", stringify!($name), ".res")),
+                );
+            }
+        )*};
+    }
+
+    example_tests!(bmp_one, ctl_combo, ctl_one, dlg_one, dlg_three, dlg_two, menu_one);
+
+    #[test]
+    fn misc() {
+        assert_same_res(testdata("misc.rc"), include_bytes!("testdata/misc.res"));
+    }
+
+    #[test]
+    fn icons_are_numbered_across_groups() {
+        let script = script::parse_file(testdata("misc.rc")).unwrap();
+        let icons = entries(&script)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.kind == RT_ICON)
+            .map(|e| e.name)
+            .collect::<Vec<_>>();
+        assert_eq!(icons, (1..=4).map(Id::Ordinal).collect::<Vec<_>>());
+    }
+
+    #[derive(Default)]
+    struct Bytes(Vec<u8>);
+
+    impl Bytes {
+        fn b(mut self, value: u8) -> Self {
+            self.0.push(value);
+            self
+        }
+
+        fn w(mut self, value: u16) -> Self {
+            put_u16(&mut self.0, value);
+            self
+        }
+
+        fn d(mut self, value: u32) -> Self {
+            put_u32(&mut self.0, value);
+            self
+        }
+
+        fn s(mut self, value: &str) -> Self {
+            put_str(&mut self.0, value);
+            self
+        }
+    }
+
+    fn template_dialog(extended: bool) -> Dialog {
+        Dialog {
+            extended,
+            style: 0x80C0_0000, // WS_POPUP | WS_CAPTION
+            extended_style: 0x0000_0080,
+            help_id: 9,
+            x: 1,
+            y: -2,
+            width: 100,
+            height: 50,
+            caption: "Hi".to_owned(),
+            class: None,
+            menu: Some(Id::Ordinal(3)),
+            font: Some(Font {
+                point_size: 9,
+                weight: 700,
+                italic: true,
+                charset: 1,
+                face: "Tahoma".to_owned(),
+            }),
+            controls: vec![
+                Control {
+                    class: Id::Ordinal(Control::BUTTON),
+                    text: Id::from("OK"),
+                    id: 1,
+                    x: 5,
+                    y: 6,
+                    width: 40,
+                    height: 14,
+                    style: 0x5001_0001,
+                    extended_style: 0,
+                    help_id: 7,
+                },
+                Control {
+                    class: Id::from("SysListView32"),
+                    text: Id::Ordinal(101),
+                    id: 0x0001_2345,
+                    x: 5,
+                    y: 25,
+                    width: 90,
+                    height: 20,
+                    style: 0x5000_0000,
+                    extended_style: 0x0000_0200,
+                    help_id: 0,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn extended_dialog_template() {
+        let expected = Bytes::default()
+            // DLGTEMPLATEEX
+            .w(1)
+            .w(0xFFFF)
+            .d(9)
+            .d(0x80)
+            .d(0x80C0_0000 | DS_SETFONT)
+            .w(2)
+            .w(1)
+            .w(-2i16 as u16)
+            .w(100)
+            .w(50)
+            .w(0xFFFF)
+            .w(3) // menu ordinal
+            .w(0) // default class
+            .s("Hi")
+            .w(9)
+            .w(700)
+            .b(1)
+            .b(1)
+            .s("Tahoma")
+            .w(0) // padding to the next DWORD
+            // DLGITEMTEMPLATEEX
+            .d(7)
+            .d(0)
+            .d(0x5001_0001)
+            .w(5)
+            .w(6)
+            .w(40)
+            .w(14)
+            .d(1)
+            .w(0xFFFF)
+            .w(Control::BUTTON)
+            .s("OK")
+            .w(0) // creation data
+            // DLGITEMTEMPLATEEX, already aligned
+            .d(0)
+            .d(0x200)
+            .d(0x5000_0000)
+            .w(5)
+            .w(25)
+            .w(90)
+            .w(20)
+            .d(0x0001_2345)
+            .s("SysListView32")
+            .w(0xFFFF)
+            .w(101) // title ordinal
+            .w(0);
+
+        let template = dialog_template(&template_dialog(true));
+        assert_eq!(template, expected.0);
+    }
+
+    #[test]
+    fn normal_dialog_template() {
+        let expected = Bytes::default()
+            // DLGTEMPLATE
+            .d(0x80C0_0000 | DS_SETFONT)
+            .d(0x80)
+            .w(2)
+            .w(1)
+            .w(-2i16 as u16)
+            .w(100)
+            .w(50)
+            .w(0xFFFF)
+            .w(3)
+            .w(0)
+            .s("Hi")
+            .w(9) // only the size and face
+            .s("Tahoma")
+            .w(0) // padding to the next DWORD
+            // DLGITEMTEMPLATE
+            .d(0x5001_0001)
+            .d(0)
+            .w(5)
+            .w(6)
+            .w(40)
+            .w(14)
+            .w(1)
+            .w(0xFFFF)
+            .w(Control::BUTTON)
+            .s("OK")
+            .w(0)
+            .w(0) // padding to the next DWORD
+            // DLGITEMTEMPLATE, only the low word of the identifier
+            .d(0x5000_0000)
+            .d(0x200)
+            .w(5)
+            .w(25)
+            .w(90)
+            .w(20)
+            .w(0x2345)
+            .s("SysListView32")
+            .w(0xFFFF)
+            .w(101)
+            .w(0);
+
+        let template = dialog_template(&template_dialog(false));
+        assert_eq!(template, expected.0);
+    }
+
+    #[test]
+    fn dialog_template_without_font() {
+        let mut dialog = template_dialog(true);
+        dialog.font = None;
+        dialog.menu = None;
+        dialog.class = Some(Id::from("Dlg"));
+        dialog.controls.truncate(1);
+
+        let template = dialog_template(&dialog);
+        let expected = Bytes::default()
+            .w(1)
+            .w(0xFFFF)
+            .d(9)
+            .d(0x80)
+            .d(0x80C0_0000) // no DS_SETFONT
+            .w(1)
+            .w(1)
+            .w(-2i16 as u16)
+            .w(100)
+            .w(50)
+            .w(0) // no menu
+            .s("Dlg")
+            .s("Hi");
+        assert_eq!(&template[..expected.0.len()], expected.0.as_slice());
+
+        // The header is 42 bytes long, so the item starts after the padding at 44.
+        assert_eq!(expected.0.len(), 42);
+        assert_eq!(&template[42..44], &[0, 0]);
+        assert_eq!(&template[44..48], &7u32.to_le_bytes());
+    }
+
+    #[test]
+    fn duplicate_resources_are_rejected() {
+        let script =
+            script::parse("1 ACCELERATORS\nBEGIN\nEND\n1 ACCELERATORS\nBEGIN\nEND\n").unwrap();
+        let error = write_res(&script, Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "duplicate resource `1` of type 9");
+    }
+
+    #[test]
+    fn coff_contains_the_resources() {
+        let script = script::parse_file(example("dlg_three")).unwrap();
+        let mut object = Vec::new();
+        write_coff(&script, Machine::X64, &mut object).unwrap();
+        assert_eq!(&object[..2], &0x8664u16.to_le_bytes());
+        assert_eq!(&object[20..28], b".rsrc\0\0\0");
+
+        let template = match &script.resources[0].data {
+            Data::Dialog(dialog) => dialog_template(dialog),
+            _ => unreachable!(),
+        };
+        assert!(object
+            .windows(template.len())
+            .any(|w| w == template.as_slice()));
+    }
+}
@@ -0,0 +1,2202 @@
+//! Parser for resource scripts (`.rc` files) https://docs.microsoft.com/en-us/windows/win32/menurc/about-resource-files.
+//!
+//! The parser understands the preprocessor directives commonly found in resource scripts and
+//! their headers (`#include`, `#define`, `#undef` and conditionals), and the `DIALOG`,
+//! `DIALOGEX`, `MENU`, `MENUEX`, `ICON`, `BITMAP`, `STRINGTABLE`, `ACCELERATORS`, `VERSIONINFO`
+//! and `LANGUAGE` statements.
+//!
+//! Definitions from system headers such as `windows.h` (window styles, control styles, virtual
+//! keys...) are built-in, so those headers are never read.
+use super::{
+    symbols, Accelerator, Control, Data, Define, Dialog, Font, Id, Menu, MenuItem, Resource,
+    Script, StringTable, VersionEntry, VersionInfo, VersionValue, DEFAULT_LANGUAGE,
+};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Nested includes beyond this point are most likely a header including itself.
+const MAX_INCLUDE_DEPTH: usize = 32;
+
+const WS_POPUP: u32 = 0x8000_0000;
+const WS_CHILD: u32 = 0x4000_0000;
+const WS_VISIBLE: u32 = 0x1000_0000;
+const WS_CAPTION: u32 = 0x00C0_0000;
+const WS_BORDER: u32 = 0x0080_0000;
+const WS_SYSMENU: u32 = 0x0008_0000;
+const WS_GROUP: u32 = 0x0002_0000;
+const WS_TABSTOP: u32 = 0x0001_0000;
+const DS_SETFONT: u32 = 0x0040;
+
+const MF_GRAYED: u32 = 0x0001;
+const MF_DISABLED: u32 = 0x0002;
+const MF_CHECKED: u32 = 0x0008;
+const MF_MENUBARBREAK: u32 = 0x0020;
+const MF_MENUBREAK: u32 = 0x0040;
+const MF_SEPARATOR: u32 = 0x0800;
+const MF_HELP: u32 = 0x4000;
+
+const FVIRTKEY: u16 = 0x01;
+const FNOINVERT: u16 = 0x02;
+const FSHIFT: u16 = 0x04;
+const FCONTROL: u16 = 0x08;
+const FALT: u16 = 0x10;
+
+/// An error found while processing a resource script, along with where it happened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    file: Option<PathBuf>,
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+// Errors are reported without the file table first, and converted into `Error` at the very end.
+type Fallible<T> = std::result::Result<T, (Pos, String)>;
+
+#[derive(Clone, Copy, Debug)]
+struct Pos {
+    file: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Ident(String),
+    Number(i64),
+    Str(String),
+    /// The `<name>` in `#include <name>`.
+    HeaderName(String),
+    Punct(&'static str),
+    Hash,
+    Newline,
+    Other(char),
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    tok: Tok,
+    pos: Pos,
+    space_before: bool,
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    index: usize,
+    pos: Pos,
+}
+
+struct Macro {
+    body: Vec<Token>,
+    function: bool,
+}
+
+struct Condition {
+    pos: Pos,
+    /// Whether the current branch is being processed.
+    active: bool,
+    /// Whether any branch so far has been processed (including the current one).
+    taken: bool,
+}
+
+struct Preprocessor {
+    files: Vec<Option<PathBuf>>,
+    macros: HashMap<String, Macro>,
+    includes: Vec<PathBuf>,
+    defines: Vec<Define>,
+    output: Vec<Token>,
+    depth: usize,
+}
+
+struct Cursor {
+    tokens: Vec<Token>,
+    index: usize,
+    end: Pos,
+}
+
+struct Parser<'f> {
+    cursor: Cursor,
+    files: &'f [Option<PathBuf>],
+    language: u16,
+    /// Language set by a `LANGUAGE` statement inside the resource being parsed.
+    resource_language: Option<u16>,
+    strings: HashSet<(u16, u16)>,
+    script: Script,
+}
+
+/// Parses the source code of a resource script.
+///
+/// Because there is no file to resolve paths against, only system headers may be included.
+pub fn parse(source: &str) -> Result<Script, Error> {
+    let mut preprocessor = Preprocessor::new(None);
+    let result = preprocessor.run(source, 0);
+    preprocessor.finish(result)
+}
+
+/// Reads and parses a resource script file.
+///
+/// Included headers and the files referenced by resources are looked up relative to the file
+/// that mentions them. The file may be encoded in UTF-8, or UTF-16 with a byte order mark.
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Script, Error> {
+    let path = path.as_ref();
+    let mut preprocessor = Preprocessor::new(Some(path.to_path_buf()));
+    let result = read_source(path)
+        .map_err(|message| (Pos::start(0), message))
+        .and_then(|source| preprocessor.run(&source, 0));
+    preprocessor.finish(result)
+}
+
+fn read_source(path: &Path) -> std::result::Result<String, String> {
+    let bytes =
+        fs::read(path).map_err(|e| format!("cannot read file `{}`: {}", path.display(), e))?;
+
+    let utf16 = |bytes: &[u8], decode: fn([u8; 2]) -> u16| {
+        let units = bytes.chunks_exact(2).map(|c| decode([c[0], c[1]]));
+        char::decode_utf16(units)
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect::<String>()
+    };
+
+    Ok(if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        utf16(rest, u16::from_be_bytes)
+    } else {
+        let bytes = bytes
+            .strip_prefix(&[0xEF, 0xBB, 0xBF])
+            .map(|rest| rest.to_vec())
+            .unwrap_or(bytes);
+
+        // Scripts not in UTF-8 are most likely in some Windows code page. Latin-1 is a close
+        // enough approximation that at least keeps the text readable.
+        match String::from_utf8(bytes) {
+            Ok(source) => source,
+            Err(e) => e.into_bytes().into_iter().map(char::from).collect(),
+        }
+    })
+}
+
+impl Error {
+    /// Creates an error pointing at the start of a line, for problems found after parsing.
+    pub(super) fn at_line(file: Option<PathBuf>, line: usize, message: String) -> Self {
+        Error {
+            file,
+            line,
+            column: 1,
+            message,
+        }
+    }
+
+    /// The file where the error was found, if the script was read from disk.
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// One-based line where the error was found.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// One-based column where the error was found.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Pos {
+    fn start(file: usize) -> Self {
+        Pos {
+            file,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tok::Ident(name) => write!(f, "`{}`", name),
+            Tok::Number(n) => write!(f, "`{}`", n),
+            Tok::Str(s) => write!(f, "{:?}", s),
+            Tok::HeaderName(name) => write!(f, "`<{}>`", name),
+            Tok::Punct(p) => write!(f, "`{}`", p),
+            Tok::Hash => f.write_str("`#`"),
+            Tok::Newline => f.write_str("end of line"),
+            Tok::Other(c) => write!(f, "`{}`", c),
+        }
+    }
+}
+
+impl Lexer {
+    fn tokenize(source: &str, file: usize) -> Fallible<Vec<Token>> {
+        let mut lexer = Lexer {
+            chars: source.chars().collect(),
+            index: 0,
+            pos: Pos::start(file),
+        };
+
+        let mut tokens = Vec::<Token>::new();
+        let mut space_before = true;
+        while let Some(c) = lexer.peek(0) {
+            let pos = lexer.pos;
+            let tok = match c {
+                '\n' => {
+                    lexer.bump();
+                    Tok::Newline
+                }
+                _ if c.is_whitespace() => {
+                    lexer.bump();
+                    space_before = true;
+                    continue;
+                }
+                '\\' if matches!(lexer.peek(1), Some('\n') | Some('\r')) => {
+                    // Line continuation.
+                    lexer.bump();
+                    lexer.eat('\r');
+                    lexer.eat('\n');
+                    space_before = true;
+                    continue;
+                }
+                '/' if lexer.peek(1) == Some('/') => {
+                    while !matches!(lexer.peek(0), Some('\n') | None) {
+                        lexer.bump();
+                    }
+                    space_before = true;
+                    continue;
+                }
+                '/' if lexer.peek(1) == Some('*') => {
+                    lexer.bump();
+                    lexer.bump();
+                    loop {
+                        match lexer.bump() {
+                            Some('*') if lexer.eat('/') => break,
+                            Some(_) => {}
+                            None => return Err((pos, "unterminated comment".to_owned())),
+                        }
+                    }
+                    space_before = true;
+                    continue;
+                }
+                '<' if Self::expects_header_name(&tokens) => lexer.header_name()?,
+                '"' => lexer.string()?,
+                'L' if lexer.peek(1) == Some('"') => {
+                    lexer.bump();
+                    lexer.string()?
+                }
+                '0'..='9' => lexer.number()?,
+                _ if c == '_' || c.is_ascii_alphabetic() => {
+                    let mut ident = String::new();
+                    while let Some(c) = lexer.peek(0) {
+                        if c == '_' || c.is_ascii_alphanumeric() {
+                            ident.push(c);
+                            lexer.bump();
+                        } else {
+                            break;
+                        }
+                    }
+                    Tok::Ident(ident)
+                }
+                '#' => {
+                    lexer.bump();
+                    Tok::Hash
+                }
+                _ => lexer.punct(),
+            };
+
+            tokens.push(Token {
+                tok,
+                pos,
+                space_before,
+            });
+            space_before = false;
+        }
+
+        Ok(tokens)
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.index + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.index += 1;
+        if c == '\n' {
+            self.pos.line += 1;
+            self.pos.column = 1;
+        } else {
+            self.pos.column += 1;
+        }
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek(0) == Some(c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expects_header_name(tokens: &[Token]) -> bool {
+        match tokens {
+            [.., hash, include] => {
+                hash.tok == Tok::Hash && include.tok == Tok::Ident("include".to_owned())
+            }
+            _ => false,
+        }
+    }
+
+    fn header_name(&mut self) -> Fallible<Tok> {
+        let pos = self.pos;
+        self.bump();
+        let mut name = String::new();
+        loop {
+            match self.bump() {
+                Some('>') => break Ok(Tok::HeaderName(name)),
+                Some('\n') | None => break Err((pos, "unterminated header name".to_owned())),
+                Some(c) => name.push(c),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Fallible<Tok> {
+        let pos = self.pos;
+        self.bump();
+        let mut value = String::new();
+        loop {
+            let c = match self.bump() {
+                Some('"') if self.eat('"') => '"',
+                Some('"') => break Ok(Tok::Str(value)),
+                Some('\n') | None => break Err((pos, "unterminated string".to_owned())),
+                Some('\\') => match self.bump() {
+                    Some('a') => '\x07',
+                    Some('b') => '\x08',
+                    Some('f') => '\x0C',
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('v') => '\x0B',
+                    Some(c @ '0'..='7') => {
+                        let mut code = c.to_digit(8).unwrap();
+                        for _ in 0..2 {
+                            match self.peek(0).and_then(|c| c.to_digit(8)) {
+                                Some(digit) => {
+                                    code = code * 8 + digit;
+                                    self.bump();
+                                }
+                                None => break,
+                            }
+                        }
+                        char::from_u32(code).unwrap()
+                    }
+                    Some('x') => {
+                        let mut code = 0;
+                        for _ in 0..4 {
+                            match self.peek(0).and_then(|c| c.to_digit(16)) {
+                                Some(digit) => {
+                                    code = code * 16 + digit;
+                                    self.bump();
+                                }
+                                None => break,
+                            }
+                        }
+                        char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+                    }
+                    Some('\n') | None => break Err((pos, "unterminated string".to_owned())),
+                    // This includes `\\`, `\"`, `\'` and `\?`.
+                    Some(c) => c,
+                },
+                Some(c) => c,
+            };
+            value.push(c);
+        }
+    }
+
+    fn number(&mut self) -> Fallible<Tok> {
+        let pos = self.pos;
+        let mut radix = 10;
+        if self.peek(0) == Some('0') {
+            match self.peek(1) {
+                Some('x') | Some('X') => radix = 16,
+                Some('o') | Some('O') => radix = 8,
+                _ => {}
+            }
+            if radix != 10 {
+                self.bump();
+                self.bump();
+            }
+        }
+
+        let mut digits = String::new();
+        while let Some(c) = self.peek(0) {
+            if c.is_digit(radix) {
+                digits.push(c);
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        while matches!(self.peek(0), Some('L') | Some('l') | Some('U') | Some('u')) {
+            self.bump();
+        }
+
+        match self.peek(0) {
+            Some(c) if c == '_' || c.is_ascii_alphanumeric() => {
+                return Err((pos, "invalid number".to_owned()));
+            }
+            _ => {}
+        }
+
+        i64::from_str_radix(&digits, radix)
+            .map(Tok::Number)
+            .map_err(|_| (pos, "invalid number".to_owned()))
+    }
+
+    fn punct(&mut self) -> Tok {
+        const PUNCTS: [&str; 27] = [
+            "&&", "||", "==", "!=", "<=", ">=", "<<", ">>", ",", "|", "&", "^", "+", "-", "*", "/",
+            "%", "(", ")", "~", "!", "<", ">", "{", "}", "?", ":",
+        ];
+
+        for punct in PUNCTS.iter() {
+            if punct
+                .chars()
+                .enumerate()
+                .all(|(i, c)| self.peek(i) == Some(c))
+            {
+                for _ in 0..punct.len() {
+                    self.bump();
+                }
+                return Tok::Punct(punct);
+            }
+        }
+
+        Tok::Other(self.bump().unwrap())
+    }
+}
+
+impl Preprocessor {
+    fn new(path: Option<PathBuf>) -> Self {
+        let mut macros = HashMap::new();
+        macros.insert(
+            "RC_INVOKED".to_owned(),
+            Macro {
+                body: vec![Token {
+                    tok: Tok::Number(1),
+                    pos: Pos::start(0),
+                    space_before: true,
+                }],
+                function: false,
+            },
+        );
+
+        Preprocessor {
+            files: vec![path],
+            macros,
+            includes: Vec::new(),
+            defines: Vec::new(),
+            output: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Parses the preprocessed tokens and reports any error from either step.
+    fn finish(self, result: Fallible<()>) -> Result<Script, Error> {
+        let Preprocessor {
+            files,
+            includes,
+            defines,
+            output,
+            ..
+        } = self;
+
+        let result = result.and_then(|()| {
+            let end = output
+                .last()
+                .map(|t| t.pos)
+                .unwrap_or_else(|| Pos::start(0));
+
+            Parser {
+                cursor: Cursor {
+                    tokens: output,
+                    index: 0,
+                    end,
+                },
+                files: &files,
+                language: DEFAULT_LANGUAGE,
+                resource_language: None,
+                strings: HashSet::new(),
+                script: Script {
+                    includes,
+                    defines,
+                    ..Script::default()
+                },
+            }
+            .script()
+        });
+
+        result.map_err(|(pos, message)| Error {
+            file: files.get(pos.file).cloned().flatten(),
+            line: pos.line,
+            column: pos.column,
+            message,
+        })
+    }
+
+    fn run(&mut self, source: &str, file: usize) -> Fallible<()> {
+        let tokens = Lexer::tokenize(source, file)?;
+        let mut conditions = Vec::<Condition>::new();
+        let mut line_start = true;
+        let mut i = 0;
+
+        while i < tokens.len() {
+            match tokens[i].tok {
+                Tok::Newline => {
+                    line_start = true;
+                    i += 1;
+                    continue;
+                }
+                Tok::Hash if line_start => {
+                    let end = tokens[i..]
+                        .iter()
+                        .position(|t| t.tok == Tok::Newline)
+                        .map(|n| i + n)
+                        .unwrap_or(tokens.len());
+
+                    self.directive(&tokens[i], &tokens[i + 1..end], &mut conditions)?;
+                    i = end;
+                    continue;
+                }
+                _ => {}
+            }
+
+            line_start = false;
+            if conditions.iter().all(|c| c.active) {
+                let mut output = std::mem::take(&mut self.output);
+                let result = self.expand(tokens[i].clone(), &mut output, &mut Vec::new());
+                self.output = output;
+                result?;
+            }
+            i += 1;
+        }
+
+        match conditions.last() {
+            Some(condition) => Err((condition.pos, "unterminated conditional".to_owned())),
+            None => Ok(()),
+        }
+    }
+
+    fn directive(
+        &mut self,
+        hash: &Token,
+        args: &[Token],
+        conditions: &mut Vec<Condition>,
+    ) -> Fallible<()> {
+        let (name, args) = match args.split_first() {
+            Some((
+                Token {
+                    tok: Tok::Ident(name),
+                    ..
+                },
+                args,
+            )) => (name.as_str(), args),
+            Some((token, _)) => {
+                return Err((
+                    token.pos,
+                    format!("expected directive, found {}", token.tok),
+                ));
+            }
+            // A lone `#` is a null directive.
+            None => return Ok(()),
+        };
+        let end = args.last().unwrap_or(hash).pos;
+
+        let active = conditions.iter().all(|c| c.active);
+        match name {
+            "if" | "ifdef" | "ifndef" => {
+                let taken = active
+                    && match name {
+                        "if" => self.condition(args, end)?,
+                        "ifdef" => self.is_defined(Self::macro_name(args, end)?),
+                        _ => !self.is_defined(Self::macro_name(args, end)?),
+                    };
+                conditions.push(Condition {
+                    pos: hash.pos,
+                    active: taken,
+                    // Inactive parents must not let any branch be taken.
+                    taken: taken || !active,
+                });
+            }
+            "elif" | "else" | "endif" => {
+                let condition = match conditions.last_mut() {
+                    Some(condition) => condition,
+                    None => return Err((hash.pos, format!("#{} without #if", name))),
+                };
+                match name {
+                    "endif" => {
+                        conditions.pop();
+                    }
+                    "else" => {
+                        condition.active = !condition.taken;
+                        condition.taken = true;
+                    }
+                    _ => {
+                        condition.active = false;
+                        if !condition.taken {
+                            let taken = self.condition(args, end)?;
+                            let condition = conditions.last_mut().unwrap();
+                            condition.active = taken;
+                            condition.taken = taken;
+                        }
+                    }
+                }
+            }
+            _ if !active => {}
+            "define" => self.define(args, end)?,
+            "undef" => {
+                let name = Self::macro_name(args, end)?;
+                self.macros.remove(name);
+            }
+            "include" => self.include(hash, args, end)?,
+            "error" => {
+                let message = args
+                    .iter()
+                    .map(|t| match &t.tok {
+                        Tok::Ident(name) => name.clone(),
+                        Tok::Str(s) => s.clone(),
+                        tok => tok.to_string().trim_matches('`').to_owned(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                return Err((hash.pos, format!("#error {}", message)));
+            }
+            "pragma" | "line" | "warning" => {}
+            _ => return Err((hash.pos, format!("unknown directive `#{}`", name))),
+        }
+
+        Ok(())
+    }
+
+    fn macro_name(args: &[Token], end: Pos) -> Fallible<&str> {
+        match args.first() {
+            Some(Token {
+                tok: Tok::Ident(name),
+                ..
+            }) => Ok(name),
+            Some(token) => Err((
+                token.pos,
+                format!("expected macro name, found {}", token.tok),
+            )),
+            None => Err((end, "expected macro name".to_owned())),
+        }
+    }
+
+    fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name) || symbols::lookup(name).is_some()
+    }
+
+    fn condition(&self, args: &[Token], end: Pos) -> Fallible<bool> {
+        // `defined` must be resolved before macros are expanded.
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < args.len() {
+            if args[i].tok == Tok::Ident("defined".to_owned()) {
+                let parens = args.get(i + 1).map(|t| &t.tok) == Some(&Tok::Punct("("));
+                let offset = if parens { 2 } else { 1 };
+                let name = Self::macro_name(&args[(i + offset).min(args.len())..], end)?;
+                if parens && args.get(i + 3).map(|t| &t.tok) != Some(&Tok::Punct(")")) {
+                    return Err((args[i].pos, "expected `)` after `defined(`".to_owned()));
+                }
+                tokens.push(Token {
+                    tok: Tok::Number(self.is_defined(name) as i64),
+                    pos: args[i].pos,
+                    space_before: true,
+                });
+                i += offset + 1 + parens as usize;
+            } else {
+                self.expand(args[i].clone(), &mut tokens, &mut Vec::new())?;
+                i += 1;
+            }
+        }
+
+        let mut cursor = Cursor {
+            tokens,
+            index: 0,
+            end,
+        };
+        // Identifiers that are not macros evaluate to zero in conditions.
+        let value = cursor.expr(&|name| Some(symbols::lookup(name).unwrap_or(0)))?;
+        cursor.expect_end()?;
+        Ok(value != 0)
+    }
+
+    fn define(&mut self, args: &[Token], end: Pos) -> Fallible<()> {
+        let name = Self::macro_name(args, end)?.to_owned();
+        let line = args[0].pos.line;
+        let function = matches!(
+            args.get(1),
+            Some(Token {
+                tok: Tok::Punct("("),
+                space_before: false,
+                ..
+            })
+        );
+
+        let body = if function {
+            match args.iter().position(|t| t.tok == Tok::Punct(")")) {
+                Some(close) => args[close + 1..].to_vec(),
+                None => return Err((args[1].pos, "expected `)` in macro parameters".to_owned())),
+            }
+        } else {
+            args[1..].to_vec()
+        };
+
+        // Macros are lazily evaluated, but resource headers virtually never depend on that.
+        let value = if function || body.is_empty() {
+            Ok(None)
+        } else {
+            let mut tokens = Vec::new();
+            for token in body.iter().cloned() {
+                self.expand(token, &mut tokens, &mut vec![name.clone()])
+                    .ok()
+                    .unwrap_or(());
+            }
+            let mut cursor = Cursor {
+                tokens,
+                index: 0,
+                end,
+            };
+            cursor
+                .expr(&symbols::lookup)
+                .and_then(|value| cursor.expect_end().map(|()| Some(value)))
+        };
+        let (value, error) = match value {
+            Ok(value) => (value, None),
+            Err((_, message)) => (None, Some(message)),
+        };
+
+        self.defines.push(Define {
+            name: name.clone(),
+            value,
+            error,
+            file: self.files[args[0].pos.file].clone(),
+            line,
+        });
+        self.macros.insert(name, Macro { body, function });
+        Ok(())
+    }
+
+    fn include(&mut self, hash: &Token, args: &[Token], end: Pos) -> Fallible<()> {
+        let (name, system) = match args.first().map(|t| &t.tok) {
+            Some(Tok::Str(name)) => (name.clone(), false),
+            Some(Tok::HeaderName(name)) => (name.clone(), true),
+            Some(tok) => return Err((args[0].pos, format!("expected file name, found {}", tok))),
+            None => return Err((end, "expected file name".to_owned())),
+        };
+
+        let path = if system {
+            None
+        } else {
+            self.files[hash.pos.file]
+                .as_ref()
+                .map(|current| match current.parent() {
+                    Some(parent) => parent.join(&name),
+                    None => PathBuf::from(&name),
+                })
+                .filter(|path| path.is_file())
+        };
+
+        match path {
+            Some(path) => {
+                self.includes.push(path.clone());
+                if self.depth >= MAX_INCLUDE_DEPTH {
+                    return Err((hash.pos, "too many nested includes".to_owned()));
+                }
+                let source = read_source(&path).map_err(|message| (args[0].pos, message))?;
+                let file = self.files.len();
+                self.files.push(Some(path));
+
+                self.depth += 1;
+                let result = self.run(&source, file);
+                self.depth -= 1;
+                result
+            }
+            None if symbols::is_system_header(&name) => {
+                self.includes.push(PathBuf::from(name));
+                Ok(())
+            }
+            None => Err((args[0].pos, format!("cannot open include file `{}`", name))),
+        }
+    }
+
+    /// Pushes the token into the output, replacing it with its definition if it's a macro.
+    fn expand(
+        &self,
+        token: Token,
+        output: &mut Vec<Token>,
+        active: &mut Vec<String>,
+    ) -> Fallible<()> {
+        if let Tok::Ident(name) = &token.tok {
+            if let Some(m) = self.macros.get(name) {
+                if !active.contains(name) {
+                    if m.function {
+                        return Err((
+                            token.pos,
+                            format!("function-like macro `{}` is not supported", name),
+                        ));
+                    }
+
+                    active.push(name.clone());
+                    for t in m.body.iter() {
+                        let t = Token {
+                            pos: token.pos,
+                            ..t.clone()
+                        };
+                        self.expand(t, output, active)?;
+                    }
+                    active.pop();
+                    return Ok(());
+                }
+            }
+        }
+
+        output.push(token);
+        Ok(())
+    }
+}
+
+impl Cursor {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.index).map(|t| &t.tok)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        if token.is_some() {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn here(&self) -> Pos {
+        self.tokens
+            .get(self.index)
+            .map(|t| t.pos)
+            .unwrap_or(self.end)
+    }
+
+    fn at_end(&self) -> bool {
+        self.index >= self.tokens.len()
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Fallible<T> {
+        match self.peek() {
+            Some(tok) => Err((self.here(), format!("expected {}, found {}", expected, tok))),
+            None => Err((
+                self.here(),
+                format!("expected {}, found end of file", expected),
+            )),
+        }
+    }
+
+    fn expect_end(&self) -> Fallible<()> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            self.unexpected("end of expression")
+        }
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Punct(p)) if *p == punct)
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if self.is_punct(punct) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Fallible<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", punct))
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Ident(name)) if name.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Evaluates an expression with C-like operator precedence.
+    fn expr(&mut self, resolve: &dyn Fn(&str) -> Option<i64>) -> Fallible<i64> {
+        self.binary(0, resolve)
+    }
+
+    fn binary(
+        &mut self,
+        min_precedence: u8,
+        resolve: &dyn Fn(&str) -> Option<i64>,
+    ) -> Fallible<i64> {
+        let mut lhs = self.unary(resolve)?;
+        while let Some((op, precedence)) = self.binary_operator() {
+            if precedence < min_precedence {
+                break;
+            }
+
+            let pos = self.here();
+            self.index += 1;
+            let rhs = self.binary(precedence + 1, resolve)?;
+            lhs = match op {
+                "||" => (lhs != 0 || rhs != 0) as i64,
+                "&&" => (lhs != 0 && rhs != 0) as i64,
+                "|" => lhs | rhs,
+                "^" => lhs ^ rhs,
+                "&" => lhs & rhs,
+                "==" => (lhs == rhs) as i64,
+                "!=" => (lhs != rhs) as i64,
+                "<" => (lhs < rhs) as i64,
+                ">" => (lhs > rhs) as i64,
+                "<=" => (lhs <= rhs) as i64,
+                ">=" => (lhs >= rhs) as i64,
+                "<<" => lhs.wrapping_shl(rhs as u32),
+                ">>" => lhs.wrapping_shr(rhs as u32),
+                "+" => lhs.wrapping_add(rhs),
+                "-" => lhs.wrapping_sub(rhs),
+                "*" => lhs.wrapping_mul(rhs),
+                _ if rhs == 0 => return Err((pos, "division by zero".to_owned())),
+                "/" => lhs.wrapping_div(rhs),
+                _ => lhs.wrapping_rem(rhs),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn binary_operator(&self) -> Option<(&'static str, u8)> {
+        let op = match self.peek() {
+            Some(Tok::Punct(op)) => *op,
+            _ => return None,
+        };
+        let precedence = match op {
+            "||" => 1,
+            "&&" => 2,
+            "|" => 3,
+            "^" => 4,
+            "&" => 5,
+            "==" | "!=" => 6,
+            "<" | ">" | "<=" | ">=" => 7,
+            "<<" | ">>" => 8,
+            "+" | "-" => 9,
+            "*" | "/" | "%" => 10,
+            _ => return None,
+        };
+        Some((op, precedence))
+    }
+
+    fn unary(&mut self, resolve: &dyn Fn(&str) -> Option<i64>) -> Fallible<i64> {
+        let pos = self.here();
+        match self.peek() {
+            Some(Tok::Number(_)) | Some(Tok::Ident(_)) => {}
+            Some(Tok::Punct(p)) if ["-", "+", "~", "!", "("].contains(p) => {}
+            _ => return self.unexpected("expression"),
+        }
+
+        match self.next().map(|t| t.tok) {
+            Some(Tok::Number(n)) => Ok(n),
+            Some(Tok::Punct("-")) => self.unary(resolve).map(i64::wrapping_neg),
+            Some(Tok::Punct("+")) => self.unary(resolve),
+            Some(Tok::Punct("~")) => self.unary(resolve).map(|n| !n),
+            Some(Tok::Punct("!")) => self.unary(resolve).map(|n| (n == 0) as i64),
+            Some(Tok::Punct("(")) => {
+                let value = self.expr(resolve)?;
+                self.expect_punct(")")?;
+                Ok(value)
+            }
+            Some(Tok::Ident(name)) if name.eq_ignore_ascii_case("NOT") => {
+                self.unary(resolve).map(|n| !n)
+            }
+            Some(Tok::Ident(name)) => {
+                resolve(&name).ok_or_else(|| (pos, format!("undefined symbol `{}`", name)))
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Parser<'_> {
+    fn script(mut self) -> Fallible<Script> {
+        while !self.cursor.at_end() {
+            if self.cursor.eat_keyword("LANGUAGE") {
+                self.language = self.language()?;
+                continue;
+            }
+
+            self.resource_language = None;
+            if self.cursor.eat_keyword("STRINGTABLE") {
+                let table = self.string_table()?;
+                self.script.string_tables.push(table);
+                continue;
+            }
+
+            let name = self.resource_name()?;
+            let pos = self.cursor.here();
+            let kind = self.ident("resource type")?;
+            let data = match kind.to_ascii_uppercase().as_str() {
+                "DIALOG" => Data::Dialog(self.dialog(false)?),
+                "DIALOGEX" => Data::Dialog(self.dialog(true)?),
+                "MENU" => Data::Menu(self.menu(false)?),
+                "MENUEX" => Data::Menu(self.menu(true)?),
+                "ICON" => Data::Icon(self.file_name()?),
+                "BITMAP" => Data::Bitmap(self.file_name()?),
+                "ACCELERATORS" => Data::Accelerators(self.accelerators()?),
+                "VERSIONINFO" => Data::Version(self.version_info()?),
+                _ => return Err((pos, format!("unsupported resource type `{}`", kind))),
+            };
+
+            self.script.resources.push(Resource {
+                name,
+                language: self.resource_language.unwrap_or(self.language),
+                data,
+            });
+        }
+
+        Ok(self.script)
+    }
+
+    fn ident(&mut self, expected: &str) -> Fallible<String> {
+        match self.cursor.peek() {
+            Some(Tok::Ident(name)) => {
+                let name = name.clone();
+                self.cursor.index += 1;
+                Ok(name)
+            }
+            _ => self.cursor.unexpected(expected),
+        }
+    }
+
+    fn string(&mut self) -> Fallible<String> {
+        match self.cursor.peek() {
+            Some(Tok::Str(s)) => {
+                let s = s.clone();
+                self.cursor.index += 1;
+                Ok(s)
+            }
+            _ => self.cursor.unexpected("string"),
+        }
+    }
+
+    fn number(&mut self) -> Fallible<i64> {
+        self.cursor.expr(&symbols::lookup)
+    }
+
+    fn ordinal(&mut self) -> Fallible<u16> {
+        let pos = self.cursor.here();
+        let value = self.number()?;
+        u16::try_from(value).map_err(|_| (pos, format!("identifier `{}` is out of range", value)))
+    }
+
+    fn coord(&mut self) -> Fallible<i16> {
+        let pos = self.cursor.here();
+        let value = self.number()?;
+        i16::try_from(value).map_err(|_| (pos, format!("coordinate `{}` is out of range", value)))
+    }
+
+    /// Commas between required arguments are optional.
+    fn comma(&mut self) {
+        self.cursor.eat_punct(",");
+    }
+
+    fn block_start(&mut self) -> bool {
+        self.cursor.eat_keyword("BEGIN") || self.cursor.eat_punct("{")
+    }
+
+    fn block_end(&mut self) -> Fallible<bool> {
+        if self.cursor.eat_keyword("END") || self.cursor.eat_punct("}") {
+            Ok(true)
+        } else if self.cursor.at_end() {
+            self.cursor.unexpected("`END`")
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect_block_start(&mut self) -> Fallible<()> {
+        if self.block_start() {
+            Ok(())
+        } else {
+            self.cursor.unexpected("`BEGIN`")
+        }
+    }
+
+    /// Consumes the next token if it's one of the given keywords, and returns its value.
+    fn eat_flag(&mut self, flags: &[(&str, u32)]) -> Option<u32> {
+        let flag = flags
+            .iter()
+            .find(|(keyword, _)| self.cursor.is_keyword(keyword))
+            .map(|(_, flag)| *flag);
+        if flag.is_some() {
+            self.cursor.index += 1;
+        }
+        flag
+    }
+
+    /// Parses the arguments after a comma, which may be left empty, such as in `1, , 3`.
+    fn optional_args(&mut self, count: usize) -> Fallible<Vec<Option<i64>>> {
+        let mut args = Vec::with_capacity(count);
+        while args.len() < count && self.cursor.eat_punct(",") {
+            if self.cursor.is_punct(",") {
+                args.push(None);
+            } else {
+                args.push(Some(self.number()?));
+            }
+        }
+        args.resize(count, None);
+        Ok(args)
+    }
+
+    /// Parses `term | term | NOT term...`, where `NOT` clears the bits from the style so far.
+    fn style(&mut self, default: u32) -> Fallible<u32> {
+        let mut style = default;
+        loop {
+            // Bind tighter than `|` so that it separates the terms.
+            if self.cursor.eat_keyword("NOT") {
+                style &= !(self.cursor.binary(4, &symbols::lookup)? as u32);
+            } else {
+                style |= self.cursor.binary(4, &symbols::lookup)? as u32;
+            }
+            if !self.cursor.eat_punct("|") {
+                break Ok(style);
+            }
+        }
+    }
+
+    fn language(&mut self) -> Fallible<u16> {
+        let primary = self.number()?;
+        self.comma();
+        let sub = self.number()?;
+        Ok(((sub << 10) | primary) as u16)
+    }
+
+    /// Memory options are ignored by 32-bit Windows, but are still accepted.
+    fn memory_options(&mut self) {
+        const OPTIONS: [&str; 9] = [
+            "PRELOAD",
+            "LOADONCALL",
+            "FIXED",
+            "MOVEABLE",
+            "DISCARDABLE",
+            "PURE",
+            "IMPURE",
+            "SHARED",
+            "NONSHARED",
+        ];
+        while OPTIONS.iter().any(|option| self.cursor.eat_keyword(option)) {}
+    }
+
+    /// Parses the `LANGUAGE`, `CHARACTERISTICS` and `VERSION` statements before a block.
+    fn optional_statements(&mut self) -> Fallible<()> {
+        loop {
+            if self.cursor.eat_keyword("LANGUAGE") {
+                self.resource_language = Some(self.language()?);
+            } else if self.cursor.eat_keyword("CHARACTERISTICS")
+                || self.cursor.eat_keyword("VERSION")
+            {
+                self.number()?;
+            } else {
+                break Ok(());
+            }
+        }
+    }
+
+    fn resource_name(&mut self) -> Fallible<Id> {
+        match self.cursor.peek() {
+            Some(Tok::Str(name)) => {
+                let name = name.to_uppercase();
+                self.cursor.index += 1;
+                Ok(Id::Name(name))
+            }
+            Some(Tok::Ident(name)) if symbols::lookup(name).is_none() => {
+                let name = name.to_uppercase();
+                self.cursor.index += 1;
+                Ok(Id::Name(name))
+            }
+            _ => self.ordinal().map(Id::Ordinal),
+        }
+    }
+
+    fn file_name(&mut self) -> Fallible<PathBuf> {
+        self.memory_options();
+        let pos = self.cursor.here();
+        let name = self.string()?;
+        Ok(
+            match self.files[pos.file].as_ref().and_then(|f| f.parent()) {
+                Some(dir) => dir.join(name),
+                None => PathBuf::from(name),
+            },
+        )
+    }
+
+    fn dialog(&mut self, extended: bool) -> Fallible<Dialog> {
+        self.memory_options();
+        let x = self.coord()?;
+        self.comma();
+        let y = self.coord()?;
+        self.comma();
+        let width = self.coord()?;
+        self.comma();
+        let height = self.coord()?;
+        let help_id = if extended && self.cursor.eat_punct(",") {
+            self.number()? as u32
+        } else {
+            0
+        };
+
+        let mut dialog = Dialog {
+            extended,
+            style: WS_POPUP | WS_BORDER | WS_SYSMENU,
+            extended_style: 0,
+            help_id,
+            x,
+            y,
+            width,
+            height,
+            caption: String::new(),
+            class: None,
+            menu: None,
+            font: None,
+            controls: Vec::new(),
+        };
+
+        let mut caption = false;
+        while !self.block_start() {
+            let pos = self.cursor.here();
+            let statement = self.ident("dialog statement or `BEGIN`")?;
+            match statement.to_ascii_uppercase().as_str() {
+                "STYLE" => dialog.style = self.style(0)?,
+                "EXSTYLE" => dialog.extended_style = self.style(0)?,
+                "CAPTION" => {
+                    dialog.caption = self.string()?;
+                    caption = true;
+                }
+                "CLASS" => {
+                    dialog.class = Some(match self.cursor.peek() {
+                        Some(Tok::Str(_)) => Id::Name(self.string()?),
+                        _ => Id::Ordinal(self.ordinal()?),
+                    })
+                }
+                "MENU" => dialog.menu = Some(self.resource_name()?),
+                "FONT" => dialog.font = Some(self.font(extended)?),
+                "LANGUAGE" => self.resource_language = Some(self.language()?),
+                "CHARACTERISTICS" | "VERSION" => {
+                    self.number()?;
+                }
+                _ => return Err((pos, format!("unexpected `{}` in dialog", statement))),
+            }
+        }
+
+        // The caption and font are only used if the style says so, which rc does automatically.
+        if caption {
+            dialog.style |= WS_CAPTION;
+        }
+        if dialog.font.is_some() {
+            dialog.style |= DS_SETFONT;
+        }
+
+        while !self.block_end()? {
+            let control = self.control(extended)?;
+            dialog.controls.push(control);
+        }
+
+        Ok(dialog)
+    }
+
+    fn font(&mut self, extended: bool) -> Fallible<Font> {
+        let pos = self.cursor.here();
+        let point_size = self.number()?;
+        let point_size = u16::try_from(point_size)
+            .map_err(|_| (pos, format!("font size `{}` is out of range", point_size)))?;
+        self.comma();
+        let face = self.string()?;
+
+        let mut font = Font {
+            point_size,
+            weight: 0,
+            italic: false,
+            charset: 1, // DEFAULT_CHARSET
+            face,
+        };
+        if extended {
+            let args = self.optional_args(3)?;
+            font.weight = args[0].unwrap_or(0) as u16;
+            font.italic = args[1].unwrap_or(0) != 0;
+            font.charset = args[2].unwrap_or(1) as u8;
+        }
+        Ok(font)
+    }
+
+    fn control(&mut self, extended: bool) -> Fallible<Control> {
+        use symbols::lookup as sym;
+
+        let pos = self.cursor.here();
+        let keyword = self.ident("control or `END`")?;
+        let (class, default_style, has_text) = match keyword.to_ascii_uppercase().as_str() {
+            "CONTROL" => return self.generic_control(extended),
+            "ICON" => return self.icon_control(extended),
+            "LTEXT" => (Control::STATIC, WS_GROUP, true),
+            "CTEXT" => (
+                Control::STATIC,
+                sym("SS_CENTER").unwrap() as u32 | WS_GROUP,
+                true,
+            ),
+            "RTEXT" => (
+                Control::STATIC,
+                sym("SS_RIGHT").unwrap() as u32 | WS_GROUP,
+                true,
+            ),
+            "PUSHBUTTON" => (Control::BUTTON, WS_TABSTOP, true),
+            "DEFPUSHBUTTON" => (
+                Control::BUTTON,
+                sym("BS_DEFPUSHBUTTON").unwrap() as u32 | WS_TABSTOP,
+                true,
+            ),
+            "PUSHBOX" => (
+                Control::BUTTON,
+                sym("BS_PUSHBOX").unwrap() as u32 | WS_TABSTOP,
+                true,
+            ),
+            "CHECKBOX" => (
+                Control::BUTTON,
+                sym("BS_CHECKBOX").unwrap() as u32 | WS_TABSTOP,
+                true,
+            ),
+            "AUTOCHECKBOX" => (
+                Control::BUTTON,
+                sym("BS_AUTOCHECKBOX").unwrap() as u32 | WS_TABSTOP,
+                true,
+            ),
+            "STATE3" => (
+                Control::BUTTON,
+                sym("BS_3STATE").unwrap() as u32 | WS_TABSTOP,
+                true,
+            ),
+            "AUTO3STATE" => (
+                Control::BUTTON,
+                sym("BS_AUTO3STATE").unwrap() as u32 | WS_TABSTOP,
+                true,
+            ),
+            "RADIOBUTTON" => (Control::BUTTON, sym("BS_RADIOBUTTON").unwrap() as u32, true),
+            "AUTORADIOBUTTON" => (
+                Control::BUTTON,
+                sym("BS_AUTORADIOBUTTON").unwrap() as u32,
+                true,
+            ),
+            "GROUPBOX" => (Control::BUTTON, sym("BS_GROUPBOX").unwrap() as u32, true),
+            "EDITTEXT" => (Control::EDIT, WS_BORDER | WS_TABSTOP, false),
+            "LISTBOX" => (
+                Control::LIST_BOX,
+                sym("LBS_NOTIFY").unwrap() as u32 | WS_BORDER,
+                false,
+            ),
+            "COMBOBOX" => (Control::COMBO_BOX, WS_TABSTOP, false),
+            "SCROLLBAR" => (Control::SCROLL_BAR, 0, false),
+            _ => return Err((pos, format!("unknown control `{}`", keyword))),
+        };
+
+        let text = if has_text {
+            let text = self.control_text()?;
+            self.comma();
+            text
+        } else {
+            Id::Name(String::new())
+        };
+        let id = self.number()? as u32;
+        self.comma();
+        let x = self.coord()?;
+        self.comma();
+        let y = self.coord()?;
+        self.comma();
+        let width = self.coord()?;
+        self.comma();
+        let height = self.coord()?;
+
+        let mut control = Control {
+            class: Id::Ordinal(class),
+            text,
+            id,
+            x,
+            y,
+            width,
+            height,
+            style: WS_CHILD | WS_VISIBLE | default_style,
+            extended_style: 0,
+            help_id: 0,
+        };
+        if self.cursor.eat_punct(",") {
+            control.style = self.style(control.style)?;
+            self.control_tail(&mut control, extended)?;
+        }
+        Ok(control)
+    }
+
+    fn icon_control(&mut self, extended: bool) -> Fallible<Control> {
+        let text = self.control_text()?;
+        self.comma();
+        let id = self.number()? as u32;
+        self.comma();
+        let x = self.coord()?;
+        self.comma();
+        let y = self.coord()?;
+
+        let mut control = Control {
+            class: Id::Ordinal(Control::STATIC),
+            text,
+            id,
+            x,
+            y,
+            width: 0,
+            height: 0,
+            style: WS_CHILD | WS_VISIBLE | symbols::lookup("SS_ICON").unwrap() as u32,
+            extended_style: 0,
+            help_id: 0,
+        };
+        if self.cursor.eat_punct(",") {
+            control.width = self.coord()?;
+            self.comma();
+            control.height = self.coord()?;
+            if self.cursor.eat_punct(",") {
+                control.style = self.style(control.style)?;
+                self.control_tail(&mut control, extended)?;
+            }
+        }
+        Ok(control)
+    }
+
+    fn generic_control(&mut self, extended: bool) -> Fallible<Control> {
+        let text = self.control_text()?;
+        self.comma();
+        let id = self.number()? as u32;
+        self.comma();
+        let class = self.control_class()?;
+        self.comma();
+        let style = self.style(WS_CHILD | WS_VISIBLE)?;
+        self.comma();
+        let x = self.coord()?;
+        self.comma();
+        let y = self.coord()?;
+        self.comma();
+        let width = self.coord()?;
+        self.comma();
+        let height = self.coord()?;
+
+        let mut control = Control {
+            class,
+            text,
+            id,
+            x,
+            y,
+            width,
+            height,
+            style,
+            extended_style: 0,
+            help_id: 0,
+        };
+        self.control_tail(&mut control, extended)?;
+        Ok(control)
+    }
+
+    /// Parses the optional extended style and help identifier at the end of a control.
+    fn control_tail(&mut self, control: &mut Control, extended: bool) -> Fallible<()> {
+        if self.cursor.eat_punct(",") {
+            control.extended_style = self.style(0)?;
+            if extended && self.cursor.eat_punct(",") {
+                control.help_id = self.number()? as u32;
+            }
+        }
+        Ok(())
+    }
+
+    fn control_text(&mut self) -> Fallible<Id> {
+        match self.cursor.peek() {
+            Some(Tok::Str(_)) => self.string().map(Id::Name),
+            // Used by icon controls to refer to the icon by name.
+            Some(Tok::Ident(name)) if symbols::lookup(name).is_none() => self.resource_name(),
+            _ => self.ordinal().map(Id::Ordinal),
+        }
+    }
+
+    fn control_class(&mut self) -> Fallible<Id> {
+        let name = match self.cursor.peek() {
+            Some(Tok::Str(name)) => name.clone(),
+            Some(Tok::Ident(name)) if symbols::lookup(name).is_none() => {
+                symbols::lookup_string(name)
+                    .map(String::from)
+                    .unwrap_or_else(|| name.clone())
+            }
+            _ => return self.ordinal().map(Id::Ordinal),
+        };
+        self.cursor.index += 1;
+
+        Ok(Id::Ordinal(match name.to_ascii_uppercase().as_str() {
+            "BUTTON" => Control::BUTTON,
+            "EDIT" => Control::EDIT,
+            "STATIC" => Control::STATIC,
+            "LISTBOX" => Control::LIST_BOX,
+            "SCROLLBAR" => Control::SCROLL_BAR,
+            "COMBOBOX" => Control::COMBO_BOX,
+            _ => return Ok(Id::Name(name)),
+        }))
+    }
+
+    fn menu(&mut self, extended: bool) -> Fallible<Menu> {
+        self.memory_options();
+        self.optional_statements()?;
+        let items = self.menu_items(extended)?;
+        Ok(Menu { extended, items })
+    }
+
+    fn menu_items(&mut self, extended: bool) -> Fallible<Vec<MenuItem>> {
+        self.expect_block_start()?;
+
+        let mut items = Vec::new();
+        while !self.block_end()? {
+            let pos = self.cursor.here();
+            let keyword = self.ident("`MENUITEM`, `POPUP` or `END`")?;
+            let popup = match keyword.to_ascii_uppercase().as_str() {
+                "MENUITEM" => false,
+                "POPUP" => true,
+                _ => return Err((pos, format!("unexpected `{}` in menu", keyword))),
+            };
+
+            let mut item = MenuItem {
+                text: String::new(),
+                id: 0,
+                flags: 0,
+                state: 0,
+                help_id: 0,
+                popup: None,
+            };
+
+            if !popup && self.cursor.eat_keyword("SEPARATOR") {
+                // `MFT_SEPARATOR` has the same value as `MF_SEPARATOR`.
+                item.flags = MF_SEPARATOR;
+                items.push(item);
+                continue;
+            }
+
+            item.text = self.string()?;
+            if extended {
+                let args = self.optional_args(if popup { 4 } else { 3 })?;
+                item.id = args[0].unwrap_or(0) as u32;
+                item.flags = args[1].unwrap_or(0) as u32;
+                item.state = args[2].unwrap_or(0) as u32;
+                item.help_id = args.get(3).copied().flatten().unwrap_or(0) as u32;
+            } else {
+                if !popup {
+                    self.comma();
+                    item.id = self.number()? as u32;
+                }
+                item.flags = self.menu_options()?;
+            }
+
+            if popup {
+                item.popup = Some(self.menu_items(extended)?);
+            }
+            items.push(item);
+        }
+
+        Ok(items)
+    }
+
+    fn menu_options(&mut self) -> Fallible<u32> {
+        const OPTIONS: [(&str, u32); 6] = [
+            ("CHECKED", MF_CHECKED),
+            ("GRAYED", MF_GRAYED),
+            ("HELP", MF_HELP),
+            ("INACTIVE", MF_DISABLED),
+            ("MENUBARBREAK", MF_MENUBARBREAK),
+            ("MENUBREAK", MF_MENUBREAK),
+        ];
+
+        let mut flags = 0;
+        loop {
+            let comma = self.cursor.eat_punct(",");
+            match self.eat_flag(&OPTIONS) {
+                Some(flag) => flags |= flag,
+                None if comma => return self.cursor.unexpected("menu option"),
+                None => break Ok(flags),
+            }
+        }
+    }
+
+    fn accelerators(&mut self) -> Fallible<Vec<Accelerator>> {
+        const OPTIONS: [(&str, u32); 6] = [
+            ("ASCII", 0),
+            ("VIRTKEY", FVIRTKEY as u32),
+            ("NOINVERT", FNOINVERT as u32),
+            ("SHIFT", FSHIFT as u32),
+            ("CONTROL", FCONTROL as u32),
+            ("ALT", FALT as u32),
+        ];
+
+        self.memory_options();
+        self.optional_statements()?;
+        self.expect_block_start()?;
+
+        let mut accelerators = Vec::new();
+        while !self.block_end()? {
+            let pos = self.cursor.here();
+            let event = match self.cursor.peek() {
+                Some(Tok::Str(_)) => Err(self.string()?),
+                _ => Ok(self.number()?),
+            };
+            self.comma();
+            let id = self.ordinal()?;
+
+            let mut flags = 0;
+            loop {
+                let comma = self.cursor.eat_punct(",");
+                match self.eat_flag(&OPTIONS) {
+                    Some(flag) => flags |= flag as u16,
+                    None if comma => return self.cursor.unexpected("accelerator option"),
+                    None => break,
+                }
+            }
+
+            let virtkey = flags & FVIRTKEY != 0;
+            if !virtkey && flags & (FSHIFT | FCONTROL | FALT) != 0 {
+                return Err((pos, "SHIFT, CONTROL and ALT require VIRTKEY".to_owned()));
+            }
+            let key = match event {
+                Ok(key) => key as u16,
+                Err(event) => match event.chars().collect::<Vec<_>>().as_slice() {
+                    ['^', _] if virtkey => {
+                        return Err((pos, "control characters cannot be VIRTKEY".to_owned()));
+                    }
+                    ['^', c] if c.is_ascii_alphabetic() => {
+                        (c.to_ascii_uppercase() as u16) - (b'A' as u16) + 1
+                    }
+                    [c] if (*c as u32) <= 0xFFFF => {
+                        if virtkey {
+                            c.to_ascii_uppercase() as u16
+                        } else {
+                            *c as u16
+                        }
+                    }
+                    _ => return Err((pos, format!("invalid accelerator {:?}", event))),
+                },
+            };
+
+            accelerators.push(Accelerator { flags, key, id });
+        }
+
+        Ok(accelerators)
+    }
+
+    fn string_table(&mut self) -> Fallible<StringTable> {
+        self.memory_options();
+        self.optional_statements()?;
+        let language = self.resource_language.unwrap_or(self.language);
+        self.expect_block_start()?;
+
+        let mut strings = Vec::new();
+        while !self.block_end()? {
+            let pos = self.cursor.here();
+            let id = self.ordinal()?;
+            self.comma();
+            let string = self.string()?;
+            if !self.strings.insert((language, id)) {
+                return Err((pos, format!("duplicate string identifier `{}`", id)));
+            }
+            strings.push((id, string));
+        }
+
+        Ok(StringTable { language, strings })
+    }
+
+    fn version_info(&mut self) -> Fallible<VersionInfo> {
+        let mut info = VersionInfo {
+            file_version: [0; 4],
+            product_version: [0; 4],
+            file_flags_mask: 0,
+            file_flags: 0,
+            file_os: 0,
+            file_type: 0,
+            file_subtype: 0,
+            entries: Vec::new(),
+        };
+
+        while !self.block_start() {
+            let pos = self.cursor.here();
+            let statement = self.ident("version statement or `BEGIN`")?;
+            match statement.to_ascii_uppercase().as_str() {
+                "FILEVERSION" => info.file_version = self.version()?,
+                "PRODUCTVERSION" => info.product_version = self.version()?,
+                "FILEFLAGSMASK" => info.file_flags_mask = self.number()? as u32,
+                "FILEFLAGS" => info.file_flags = self.number()? as u32,
+                "FILEOS" => info.file_os = self.number()? as u32,
+                "FILETYPE" => info.file_type = self.number()? as u32,
+                "FILESUBTYPE" => info.file_subtype = self.number()? as u32,
+                _ => return Err((pos, format!("unexpected `{}` in version info", statement))),
+            }
+        }
+
+        info.entries = self.version_entries()?;
+        Ok(info)
+    }
+
+    /// Parses up to four comma-separated version parts, where missing parts are zero.
+    fn version(&mut self) -> Fallible<[u16; 4]> {
+        let mut version = [0; 4];
+        version[0] = self.number()? as u16;
+        for part in version[1..].iter_mut() {
+            if !self.cursor.eat_punct(",") {
+                break;
+            }
+            *part = self.number()? as u16;
+        }
+        Ok(version)
+    }
+
+    fn version_entries(&mut self) -> Fallible<Vec<VersionEntry>> {
+        let mut entries = Vec::new();
+        while !self.block_end()? {
+            let pos = self.cursor.here();
+            let keyword = self.ident("`BLOCK`, `VALUE` or `END`")?;
+            let key = self.string()?;
+            match keyword.to_ascii_uppercase().as_str() {
+                "BLOCK" => {
+                    self.expect_block_start()?;
+                    let block_entries = self.version_entries()?;
+                    entries.push(VersionEntry::Block {
+                        key,
+                        entries: block_entries,
+                    });
+                }
+                "VALUE" => {
+                    let mut values = Vec::new();
+                    while self.cursor.eat_punct(",") {
+                        values.push(match self.cursor.peek() {
+                            Some(Tok::Str(_)) => VersionValue::String(self.string()?),
+                            // Numbers that don't fit in a word are stored as a double word.
+                            _ => {
+                                let value = self.number()?;
+                                match u16::try_from(value) {
+                                    Ok(word) => VersionValue::Word(word),
+                                    Err(_) => VersionValue::DoubleWord(value as u32),
+                                }
+                            }
+                        });
+                    }
+                    entries.push(VersionEntry::Value { key, values });
+                }
+                _ => return Err((pos, format!("unexpected `{}` in version info", keyword))),
+            }
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("examples")
+            .join(name)
+            .join(name)
+            .with_extension("rc")
+    }
+
+    fn parse_example(name: &str) -> Script {
+        parse_file(example(name)).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn dialog(script: &Script, index: usize) -> &Dialog {
+        match &script.resources[index].data {
+            Data::Dialog(dialog) => dialog,
+            data => panic!("expected dialog, found {:?}", data),
+        }
+    }
+
+    fn menu(script: &Script, index: usize) -> &Menu {
+        match &script.resources[index].data {
+            Data::Menu(menu) => menu,
+            data => panic!("expected menu, found {:?}", data),
+        }
+    }
+
+    fn parse_error(source: &str) -> Error {
+        parse(source).expect_err("script should not parse")
+    }
+
+    #[test]
+    fn every_example_parses() {
+        let examples = [
+            "bmp_one",
+            "ctl_combo",
+            "ctl_one",
+            "dlg_one",
+            "dlg_three",
+            "dlg_two",
+            "menu_one",
+        ];
+        for name in examples.iter() {
+            let script = parse_example(name);
+            assert!(!script.resources.is_empty(), "{} has no resources", name);
+            for resource in script.resources.iter() {
+                assert_eq!(resource.language, DEFAULT_LANGUAGE);
+            }
+        }
+    }
+
+    #[test]
+    fn bmp_one() {
+        let script = parse_example("bmp_one");
+        assert_eq!(
+            script.includes,
+            vec![example("bmp_one").with_file_name("resource.h")]
+        );
+        assert_eq!(script.resources.len(), 1);
+        assert_eq!(script.resources[0].name, Id::Ordinal(101));
+        assert_eq!(
+            script.resources[0].data,
+            Data::Bitmap(example("bmp_one").with_file_name("ball.bmp"))
+        );
+    }
+
+    #[test]
+    fn menu_one() {
+        let script = parse_example("menu_one");
+        assert_eq!(script.resources.len(), 2);
+
+        let menu = menu(&script, 0);
+        assert!(!menu.extended);
+        assert_eq!(menu.items.len(), 2);
+        assert_eq!(menu.items[0].text, "&File");
+        let file = menu.items[0].popup.as_ref().unwrap();
+        assert_eq!(file.len(), 1);
+        assert_eq!(file[0].text, "E&xit");
+        assert_eq!(file[0].id, 9001);
+
+        let stuff = menu.items[1].popup.as_ref().unwrap();
+        assert_eq!(stuff.len(), 2);
+        assert_eq!(stuff[1].text, "G&o somewhere else");
+        assert_eq!(stuff[1].id, 0);
+        assert_eq!(stuff[1].flags, MF_GRAYED);
+
+        assert_eq!(
+            script.resources[1].data,
+            Data::Icon(example("menu_one").with_file_name("menu_one.ico"))
+        );
+    }
+
+    #[test]
+    fn dlg_one() {
+        let script = parse_example("dlg_one");
+        assert_eq!(
+            script.includes,
+            vec![
+                PathBuf::from("windows.h"),
+                example("dlg_one").with_file_name("resource.h"),
+            ]
+        );
+        let defines = script
+            .defines
+            .iter()
+            .map(|d| (d.name.as_str(), d.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            defines,
+            vec![
+                ("IDC_STATIC", Some(-1)),
+                ("IDR_MYMENU", Some(101)),
+                ("IDD_ABOUT", Some(102)),
+                ("ID_FILE_EXIT", Some(40001)),
+                ("ID_HELP_ABOUT", Some(40002)),
+            ]
+        );
+
+        assert_eq!(script.resources[0].name, Id::Ordinal(101));
+        let items = &menu(&script, 0).items;
+        assert_eq!(items[1].popup.as_ref().unwrap()[0].id, 40002);
+
+        assert_eq!(script.resources[1].name, Id::Ordinal(102));
+        let dialog = dialog(&script, 1);
+        assert!(!dialog.extended);
+        assert_eq!(
+            (dialog.x, dialog.y, dialog.width, dialog.height),
+            (0, 0, 239, 66)
+        );
+        assert_eq!(dialog.caption, "My About Box");
+        assert_eq!(
+            dialog.font,
+            Some(Font {
+                point_size: 8,
+                weight: 0,
+                italic: false,
+                charset: 1,
+                face: "MS Sans Serif".to_owned(),
+            })
+        );
+        assert_ne!(dialog.style & DS_SETFONT, 0);
+        assert_ne!(dialog.style & WS_POPUP, 0);
+        assert_ne!(dialog.style & WS_CAPTION, 0);
+        assert_ne!(dialog.style & WS_SYSMENU, 0);
+
+        assert_eq!(dialog.controls.len(), 4);
+        let ok = &dialog.controls[0];
+        assert_eq!(ok.class, Id::Ordinal(Control::BUTTON));
+        assert_eq!(ok.text, Id::from("&OK"));
+        assert_eq!(ok.id, 1);
+        assert_eq!((ok.x, ok.y, ok.width, ok.height), (174, 18, 50, 14));
+        assert_ne!(ok.style & WS_CHILD, 0);
+        assert_ne!(ok.style & WS_VISIBLE, 0);
+        assert_ne!(ok.style & WS_TABSTOP, 0);
+
+        let text = &dialog.controls[3];
+        assert_eq!(text.class, Id::Ordinal(Control::STATIC));
+        assert_eq!(text.id as u16, 0xFFFF);
+        assert_eq!(
+            text.text,
+            Id::from(
+                "An example program showing how to use Dialog Boxes\r\n\r\nby Lonami (original by theForger)"
+            )
+        );
+    }
+
+    #[test]
+    fn dlg_two() {
+        let script = parse_example("dlg_two");
+        let dialog = dialog(&script, 1);
+        assert!(dialog.extended);
+        assert_eq!(dialog.extended_style, 0x0080); // WS_EX_TOOLWINDOW
+        assert_eq!(dialog.caption, "My Dialog Toolbar");
+        assert_eq!(dialog.controls.len(), 2);
+        assert_eq!(dialog.controls[1].text, Id::from("&Or This One"));
+        assert_eq!(dialog.controls[1].id, 1001);
+    }
+
+    #[test]
+    fn controls() {
+        for name in ["ctl_one", "ctl_combo"].iter() {
+            let script = parse_example(name);
+            assert_eq!(script.resources.len(), 1);
+            let dialog = dialog(&script, 0);
+            let classes = dialog
+                .controls
+                .iter()
+                .map(|c| c.class.clone())
+                .collect::<Vec<_>>();
+            let list = if *name == "ctl_one" {
+                Control::LIST_BOX
+            } else {
+                Control::COMBO_BOX
+            };
+            assert_eq!(
+                classes,
+                [
+                    Control::STATIC,
+                    Control::EDIT,
+                    Control::EDIT,
+                    Control::STATIC,
+                    list,
+                    Control::BUTTON,
+                    Control::BUTTON,
+                    Control::BUTTON,
+                    Control::STATIC,
+                    Control::STATIC,
+                    Control::STATIC,
+                ]
+                .iter()
+                .map(|&ordinal| Id::Ordinal(ordinal))
+                .collect::<Vec<_>>()
+            );
+            // Styles may continue on the next line after a `|`.
+            assert_ne!(dialog.controls[4].style & WS_TABSTOP, 0);
+            assert_ne!(dialog.controls[4].style & 0x0020_0000, 0); // WS_VSCROLL
+        }
+    }
+
+    #[test]
+    fn defines_and_conditionals() {
+        let script = parse(
+            "#define A 2\n\
+             #define B (A * 3 + 1)\n\
+             #if B > 6\n\
+             #define C 1\n\
+             #else\n\
+             #define C 2\n\
+             #endif\n\
+             #undef A\n\
+             #ifdef A\n\
+             #error A should be undefined\n\
+             #endif\n\
+             C ICON \"c.ico\"\n",
+        )
+        .unwrap();
+        let defines = script
+            .defines
+            .iter()
+            .map(|d| (d.name.as_str(), d.value, d.line))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            defines,
+            vec![("A", Some(2), 1), ("B", Some(7), 2), ("C", Some(1), 4)]
+        );
+        assert_eq!(script.resources[0].name, Id::Ordinal(1));
+
+        // Macros are expanded when used, so `B` can no longer be evaluated without `A`.
+        let error = parse_error("#define A 2\n#define B (A + 1)\n#undef A\nB ICON \"b.ico\"\n");
+        assert_eq!(error.message(), "undefined symbol `A`");
+        assert_eq!(error.line(), 4);
+    }
+
+    #[test]
+    fn string_table_and_accelerators() {
+        let script = parse(
+            "#include <windows.h>\n\
+             STRINGTABLE\n\
+             BEGIN\n\
+             \x20   1, \"One\"\n\
+             \x20   2 \"Two\"\n\
+             END\n\
+             1 ACCELERATORS\n\
+             BEGIN\n\
+             \x20   \"^C\", 10\n\
+             \x20   VK_F5, 11, VIRTKEY, CONTROL, SHIFT\n\
+             END\n",
+        )
+        .unwrap();
+        assert_eq!(
+            script.string_tables,
+            vec![StringTable {
+                language: DEFAULT_LANGUAGE,
+                strings: vec![(1, "One".to_owned()), (2, "Two".to_owned())],
+            }]
+        );
+        assert_eq!(
+            script.resources[0].data,
+            Data::Accelerators(vec![
+                Accelerator {
+                    flags: 0,
+                    key: 3,
+                    id: 10,
+                },
+                Accelerator {
+                    flags: FVIRTKEY | FCONTROL | FSHIFT,
+                    key: 0x74,
+                    id: 11,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn unterminated_string() {
+        let error = parse("1 ICON \"icon.ico\n");
+        let error = error.unwrap_err();
+        assert_eq!(error.message(), "unterminated string");
+        assert_eq!((error.line(), error.column()), (1, 8));
+        assert_eq!(error.file(), None);
+    }
+
+    #[test]
+    fn unknown_statement() {
+        let error = parse_error("\n1 FOO \"file\"\n");
+        assert_eq!(error.message(), "unsupported resource type `FOO`");
+        assert_eq!((error.line(), error.column()), (2, 3));
+
+        let error = parse_error("1 DIALOG 0, 0, 10, 10\nBOGUS 1\nBEGIN\nEND\n");
+        assert_eq!(error.message(), "unexpected `BOGUS` in dialog");
+        assert_eq!((error.line(), error.column()), (2, 1));
+
+        let error =
+            parse_error("1 DIALOG 0, 0, 10, 10\nBEGIN\n  FANCYBUTTON \"x\", 1, 0, 0, 1, 1\nEND\n");
+        assert_eq!(error.message(), "unknown control `FANCYBUTTON`");
+        assert_eq!((error.line(), error.column()), (3, 3));
+    }
+
+    #[test]
+    fn bad_include() {
+        let error = parse_error("#include \"resource.h\"\n");
+        assert_eq!(error.message(), "cannot open include file `resource.h`");
+        assert_eq!((error.line(), error.column()), (1, 10));
+
+        let error = parse_error("#include\n");
+        assert_eq!(error.message(), "expected file name");
+
+        let error = parse_error("#include 12\n");
+        assert_eq!(error.message(), "expected file name, found `12`");
+
+        let error = parse_error("#include <windows.h\n");
+        assert_eq!(error.message(), "unterminated header name");
+    }
+
+    #[test]
+    fn errors_in_included_files_point_at_them() {
+        let dir = std::env::temp_dir().join(format!("rc-include-error-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("script.rc");
+        let header = dir.join("bad.h");
+        fs::write(
+            &script,
+            "#define IDI_APP 1\n#include \"bad.h\"\nIDI_APP ICON \"a.ico\"\n",
+        )
+        .unwrap();
+        fs::write(&header, "#define IDC_OK 1\n\n#define 12\n").unwrap();
+        let result = parse_file(&script);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let error = result.expect_err("header should not parse");
+        assert_eq!(error.message(), "expected macro name, found `12`");
+        assert_eq!(error.file(), Some(header.as_path()));
+        assert_eq!((error.line(), error.column()), (3, 9));
+    }
+
+    #[test]
+    fn defines_point_at_their_header() {
+        let path = example("dlg_one").with_file_name("resource.h");
+        let source = format!("#include \"{}\"\n", path.display());
+        // Without a file to resolve against, even absolute paths are not read.
+        assert!(parse(&source).is_err());
+        let script = parse_file(example("dlg_one")).unwrap();
+        assert_eq!(script.defines[0].file.as_deref(), Some(path.as_path()));
+        assert_eq!(script.defines[0].line, 1);
+    }
+}
@@ -0,0 +1,171 @@
+//! Running futures on the thread of the message loop.
+//!
+//! The executor is single-threaded: futures given to `spawn_local` don't need to be `Send`, and
+//! are polled from within the message loop of the thread that spawned them. Waking a task posts a
+//! message through the thread's `Dispatcher`, so wakers can also be used from other threads.
+use crate::dispatch::Dispatcher;
+use crate::executor::{self, WakeSource};
+use crate::window::Window;
+use crate::{message, Error, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::ptr;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use winapi::shared::basetsd::UINT_PTR;
+use winapi::shared::minwindef::{DWORD, UINT};
+use winapi::shared::windef::HWND;
+use winapi::um::winuser::{KillTimer, SetTimer};
+
+pub use crate::executor::{spawn_blocking, Background};
+
+thread_local! {
+    static TIMERS: RefCell<HashMap<UINT_PTR, Rc<Shared<()>>>> = RefCell::new(HashMap::new());
+}
+
+/// State shared between a future and whatever completes it on the same thread.
+struct Shared<T> {
+    value: RefCell<Option<T>>,
+    waker: RefCell<Option<Waker>>,
+}
+
+/// Future that completes once the duration given to `sleep` has elapsed.
+pub struct Sleep {
+    timer_id: UINT_PTR,
+    shared: Rc<Shared<()>>,
+}
+
+/// Future that completes with the result of the modal dialog box started by `show_dialog`.
+pub struct DialogResult {
+    shared: Rc<Shared<Result<isize>>>,
+}
+
+// Tasks are polled from the message loop, by posting messages to the thread's hidden window.
+impl WakeSource for Dispatcher {
+    type Error = Error;
+
+    fn schedule(&self, f: Box<dyn FnOnce() + Send>) -> Result<()> {
+        self.invoke(f)
+    }
+}
+
+impl<T> Shared<T> {
+    fn new() -> Rc<Self> {
+        Rc::new(Self {
+            value: RefCell::new(None),
+            waker: RefCell::new(None),
+        })
+    }
+
+    fn complete(&self, value: T) {
+        *self.value.borrow_mut() = Some(value);
+        if let Some(waker) = self.waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+
+    fn poll(&self, cx: &mut Context) -> Poll<T> {
+        match self.value.borrow_mut().take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                *self.waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Spawns a future on the current thread. It will first be polled once the message loop runs,
+/// and then every time it's woken, until it completes.
+pub fn spawn_local<F>(future: F) -> Result<()>
+where
+    F: Future<Output = ()> + 'static,
+{
+    executor::spawn(Dispatcher::current()?, future)
+}
+
+/// Runs the function from the message loop of the current thread, rather than immediately.
+fn defer(f: Box<dyn FnOnce()>) -> Result<()> {
+    executor::defer(&Dispatcher::current()?, f)
+}
+
+/// Creates a future that completes after the given duration. Unlike `Window::set_timer`, the
+/// timer does not belong to any window, and it's killed if the future is dropped early.
+pub fn sleep(duration: Duration) -> Result<Sleep> {
+    // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-settimer
+    let timer_id = unsafe {
+        SetTimer(
+            ptr::null_mut(),
+            0,
+            duration.as_millis() as u32,
+            Some(timer_proc),
+        )
+    };
+    if timer_id == 0 {
+        return Err(Error::last_os_error());
+    }
+
+    let shared = Shared::new();
+    TIMERS.with(|timers| timers.borrow_mut().insert(timer_id, Rc::clone(&shared)));
+    Ok(Sleep { timer_id, shared })
+}
+
+// https://docs.microsoft.com/en-us/windows/win32/api/winuser/nc-winuser-timerproc
+unsafe extern "system" fn timer_proc(_: HWND, _: UINT, timer_id: UINT_PTR, _: DWORD) {
+    // Timers are periodic, but this one should only fire once.
+    KillTimer(ptr::null_mut(), timer_id);
+    if let Some(shared) = TIMERS.with(|timers| timers.borrow_mut().remove(&timer_id)) {
+        shared.complete(());
+    }
+}
+
+/// Creates a modal dialog box from a dialog box template resource, like `Window::show_dialog`,
+/// but the dialog box is shown from the message loop, and the returned future completes with
+/// its result once it's closed.
+pub fn show_dialog<F>(window: &Window, resource: u16, callback: F) -> Result<DialogResult>
+where
+    F: Fn(&Window, message::Message) -> isize + 'static,
+{
+    let hwnd = match window {
+        Window::Owned { hwnd, .. } | Window::Borrowed { hwnd } => *hwnd,
+    };
+
+    // Showing it right away would block the caller (usually a task being polled) until the
+    // dialog box is closed.
+    let shared = Shared::new();
+    let result = Rc::clone(&shared);
+    defer(Box::new(move || {
+        let window = Window::Borrowed { hwnd };
+        result.complete(window.show_dialog(resource, callback));
+    }))?;
+
+    Ok(DialogResult { shared })
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        self.shared.poll(cx)
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        let pending = TIMERS.with(|timers| timers.borrow_mut().remove(&self.timer_id));
+        if pending.is_some() {
+            unsafe { KillTimer(ptr::null_mut(), self.timer_id) };
+        }
+    }
+}
+
+impl Future for DialogResult {
+    type Output = Result<isize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<isize>> {
+        self.shared.poll(cx)
+    }
+}
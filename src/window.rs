@@ -1,43 +1,51 @@
+use crate::error::{control_result, ControlError};
 use crate::{
-    base_instance, class, dialog, font, gdi, icon, menu, message, non_null_or_err, ok_or_last_err,
-    rect, toolbar, DialogCallback, Error, MessageCallback, Result,
+    base_instance, class, control, dialog, font, from_wide, gdi, icon, menu, message,
+    non_null_or_err, ok_or_last_err, rect, to_wide, toolbar, DialogCallback, Error,
+    MessageCallback, Result,
 };
-use std::ffi::CString;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
 use std::ptr::{self, NonNull};
+use std::rc::Rc;
 use std::time::Duration;
+use widestring::U16CString;
 use winapi::ctypes::c_int;
 use winapi::shared::basetsd::INT_PTR;
-use winapi::shared::minwindef::{DWORD, LPARAM, TRUE, UINT, WPARAM};
+use winapi::shared::minwindef::{DWORD, LPARAM, LPVOID, TRUE, UINT, WPARAM};
 use winapi::shared::windef::{HMENU, HWND, HWND__, LPRECT, RECT};
 use winapi::um::commctrl::{
-    HINST_COMMCTRL, IDB_STD_SMALL_COLOR, SB_SETPARTS, SB_SETTEXTA, TBADDBITMAP, TBBUTTON,
-    TB_ADDBITMAP, TB_ADDBUTTONSA, TB_AUTOSIZE, TB_BUTTONSTRUCTSIZE,
+    HINST_COMMCTRL, IDB_STD_SMALL_COLOR, SB_SETPARTS, SB_SETTEXTW, TBADDBITMAP, TBBUTTON,
+    TB_ADDBITMAP, TB_ADDBUTTONSW, TB_AUTOSIZE, TB_BUTTONSTRUCTSIZE,
 };
-use winapi::um::winnt::LPCSTR;
+use winapi::um::errhandlingapi::SetLastError;
 use winapi::um::winuser::{
-    CreateDialogParamA, CreateWindowExA, DestroyWindow, DialogBoxParamA, EndDialog, GetClientRect,
-    GetDlgItem, KillTimer, PostMessageA, SendMessageA, SendMessageW, SetMenu, SetTimer,
-    SetWindowPos, ShowWindow, UpdateWindow, CW_USEDEFAULT, ES_AUTOHSCROLL, ES_AUTOVSCROLL,
-    ES_CENTER, ES_LOWERCASE, ES_MULTILINE, ES_NOHIDESEL, ES_NUMBER, ES_OEMCONVERT, ES_PASSWORD,
-    ES_READONLY, ES_RIGHT, ES_UPPERCASE, ES_WANTRETURN, ICON_BIG, ICON_SMALL, LB_ADDSTRING,
-    LB_DELETESTRING, LB_ERR, LB_ERRSPACE, LB_GETITEMDATA, LB_GETSELCOUNT, LB_GETSELITEMS,
-    LB_RESETCONTENT, LB_SETITEMDATA, MAKEINTRESOURCEA, SWP_NOZORDER, SW_FORCEMINIMIZE, SW_HIDE,
+    CreateDialogIndirectParamW, CreateDialogParamW, CreateWindowExW, DestroyWindow,
+    DialogBoxIndirectParamW, DialogBoxParamW, EndDialog, GetClientRect, GetDlgItem, KillTimer,
+    PostMessageW, SendMessageW, SetMenu, SetTimer, SetWindowLongPtrW, SetWindowPos, ShowWindow,
+    TrackMouseEvent, UpdateWindow, CW_USEDEFAULT, ES_AUTOHSCROLL, ES_AUTOVSCROLL, ES_CENTER,
+    ES_LOWERCASE, ES_MULTILINE, ES_NOHIDESEL, ES_NUMBER, ES_OEMCONVERT, ES_PASSWORD, ES_READONLY,
+    ES_RIGHT, ES_UPPERCASE, ES_WANTRETURN, HOVER_DEFAULT, ICON_BIG, ICON_SMALL, LB_ADDSTRING,
+    LB_DELETESTRING, LB_ERR, LB_GETITEMDATA, LB_GETSELCOUNT, LB_GETSELITEMS, LB_RESETCONTENT,
+    LB_SETITEMDATA, LPCDLGTEMPLATEW, MAKEINTRESOURCEW, SWP_NOZORDER, SW_FORCEMINIMIZE, SW_HIDE,
     SW_MAXIMIZE, SW_MINIMIZE, SW_RESTORE, SW_SHOW, SW_SHOWDEFAULT, SW_SHOWMINIMIZED,
-    SW_SHOWMINNOACTIVE, SW_SHOWNA, SW_SHOWNOACTIVATE, SW_SHOWNORMAL, WM_CLOSE, WM_GETTEXT,
-    WM_GETTEXTLENGTH, WM_INITDIALOG, WM_NCDESTROY, WM_SETFONT, WM_SETICON, WM_SETTEXT, WM_SIZE,
-    WS_BORDER, WS_CAPTION, WS_CHILD, WS_CLIPCHILDREN, WS_CLIPSIBLINGS, WS_DISABLED, WS_DLGFRAME,
-    WS_EX_ACCEPTFILES, WS_EX_APPWINDOW, WS_EX_CLIENTEDGE, WS_EX_COMPOSITED, WS_EX_CONTEXTHELP,
-    WS_EX_CONTROLPARENT, WS_EX_DLGMODALFRAME, WS_EX_LAYERED, WS_EX_LAYOUTRTL, WS_EX_LEFT,
-    WS_EX_LEFTSCROLLBAR, WS_EX_MDICHILD, WS_EX_NOACTIVATE, WS_EX_NOINHERITLAYOUT,
-    WS_EX_NOPARENTNOTIFY, WS_EX_NOREDIRECTIONBITMAP, WS_EX_OVERLAPPEDWINDOW, WS_EX_PALETTEWINDOW,
-    WS_EX_RIGHT, WS_EX_RTLREADING, WS_EX_STATICEDGE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST,
-    WS_EX_TRANSPARENT, WS_EX_WINDOWEDGE, WS_GROUP, WS_HSCROLL, WS_MAXIMIZE, WS_MINIMIZE,
-    WS_OVERLAPPED, WS_OVERLAPPEDWINDOW, WS_POPUP, WS_POPUPWINDOW, WS_SYSMENU, WS_TABSTOP,
-    WS_THICKFRAME, WS_VISIBLE, WS_VSCROLL,
+    SW_SHOWMINNOACTIVE, SW_SHOWNA, SW_SHOWNOACTIVATE, SW_SHOWNORMAL, TME_HOVER, TME_LEAVE,
+    TRACKMOUSEEVENT, WM_CLOSE, WM_GETTEXT, WM_GETTEXTLENGTH, WM_INITDIALOG, WM_NCDESTROY,
+    WM_SETFONT, WM_SETICON, WM_SETTEXT, WM_SIZE, WS_BORDER, WS_CAPTION, WS_CHILD, WS_CLIPCHILDREN,
+    WS_CLIPSIBLINGS, WS_DISABLED, WS_DLGFRAME, WS_EX_ACCEPTFILES, WS_EX_APPWINDOW,
+    WS_EX_CLIENTEDGE, WS_EX_COMPOSITED, WS_EX_CONTEXTHELP, WS_EX_CONTROLPARENT,
+    WS_EX_DLGMODALFRAME, WS_EX_LAYERED, WS_EX_LAYOUTRTL, WS_EX_LEFT, WS_EX_LEFTSCROLLBAR,
+    WS_EX_MDICHILD, WS_EX_NOACTIVATE, WS_EX_NOINHERITLAYOUT, WS_EX_NOPARENTNOTIFY,
+    WS_EX_NOREDIRECTIONBITMAP, WS_EX_OVERLAPPEDWINDOW, WS_EX_PALETTEWINDOW, WS_EX_RIGHT,
+    WS_EX_RTLREADING, WS_EX_STATICEDGE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT,
+    WS_EX_WINDOWEDGE, WS_GROUP, WS_HSCROLL, WS_MAXIMIZE, WS_MINIMIZE, WS_OVERLAPPED,
+    WS_OVERLAPPEDWINDOW, WS_POPUP, WS_POPUPWINDOW, WS_SYSMENU, WS_TABSTOP, WS_THICKFRAME,
+    WS_VISIBLE, WS_VSCROLL,
 };
 
+// um/WinUser.h
+const DWLP_MSGRESULT: c_int = 0;
+
 /// Extended window styles as defined in https://docs.microsoft.com/en-us/windows/win32/winmsg/extended-window-styles.
 #[repr(u32)]
 pub enum ExtendedStyle {
@@ -288,8 +296,8 @@ pub enum Style {
     /// after the control has been created, use SetWindowLong.
     WantReturn = ES_WANTRETURN,
     // TODO certain styles collide (such as these). Before they were removed but it seems more
-    //      and more will appear. Stop assigning the ID and create a method mapping variant to
-    //      the right bit flag, and potentially add back those that were removed.
+    //      and more will appear. The styles of each control now live in the `control` module
+    //      (added with `Builder::add_control_style`), so these should eventually be removed.
 
     /*
     /// The status bar control will include a sizing grip at the right end of the status bar.
@@ -357,7 +365,7 @@ pub struct Builder<'a> {
 #[derive(Debug)]
 pub enum Window<'a> {
     Owned {
-        _window_name: CString,
+        _window_name: U16CString,
         hwnd: NonNull<HWND__>,
         _marker: PhantomData<&'a HWND__>,
     },
@@ -373,34 +381,33 @@ pub unsafe extern "system" fn dlg_proc_wrapper(
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> INT_PTR {
-    if msg == WM_INITDIALOG {
-        // Find the dialog with handle 0 and assign it the right value.
-        let mut lock = crate::HWND_TO_DLG_CALLBACK.lock().unwrap();
+    if msg == WM_INITDIALOG && lparam != 0 {
+        // The callback is passed as the initialization parameter when creating the dialog.
+        crate::attach_callback(handle, lparam as *const DialogCallback);
+    }
 
-        let callback = lock
-            .remove(&0)
-            .expect("dialogs must start with a callback at 0");
+    if msg == WM_NCDESTROY {
+        // Modeless dialogs are registered on creation so the message loop can route their
+        // keyboard messages.
+        crate::unregister_dialog(handle);
+    }
 
-        lock.insert(handle as usize, callback);
+    // Requests of virtual list views and lazy tree views are answered without involving the
+    // callback. Dialogs return the result of a message through their window data.
+    if let Some(result) = control::intercept_parent_message(msg, wparam, lparam) {
+        SetWindowLongPtrW(handle, DWLP_MSGRESULT, result);
+        return TRUE as INT_PTR;
     }
 
     if let Some(hwnd) = NonNull::new(handle) {
-        let lock = crate::HWND_TO_DLG_CALLBACK.lock().unwrap();
-
-        if let Some(&callback) = lock.get(&(handle as usize)) {
+        if let Some(callback) = crate::window_callback::<DialogCallback>(handle) {
             let window = Window::Borrowed { hwnd };
             let message = message::Message::from_raw(msg, wparam, lparam);
-
-            drop(lock);
             let result = callback(&window, message);
 
             // No more messages will be sent to the callback, de-register it.
             if msg == WM_NCDESTROY {
-                crate::HWND_TO_DLG_CALLBACK
-                    .lock()
-                    .unwrap()
-                    .remove(&(handle as usize))
-                    .unwrap();
+                crate::detach_callback::<DialogCallback>(handle);
             }
 
             return result;
@@ -423,6 +430,13 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Adds a new style specific to the class of control being created, such as
+    /// `control::edit::Style`.
+    pub fn add_control_style<S: control::ControlStyle>(mut self, style: S) -> Self {
+        self.style |= style.bits();
+        self
+    }
+
     /// The initial horizontal position of the window. For an overlapped or pop-up window, the x parameter is the initial x-coordinate of the window's upper-left corner, in screen coordinates. For a child window, x is the x-coordinate of the upper-left corner of the window relative to the upper-left corner of the parent window's client area. If x is kept to its default value, the system selects the default position for the window's upper-left corner and ignores the y parameter. The default value is valid only for overlapped windows; if it is specified for a pop-up or child window, the x and y parameters are set to zero.
     pub fn x(mut self, x: i32) -> Self {
         self.x = x;
@@ -479,30 +493,34 @@ impl<'a> Builder<'a> {
         self
     }
 
-    /// Callback that will process messages sent to this window.
-    pub fn set_message_callback(mut self, callback: MessageCallback) -> Self {
-        self.callback = Some(callback);
+    /// Callback that will process messages sent to this window. The callback is owned by the
+    /// window, and dropped along with anything it captured when the window is destroyed.
+    pub fn set_message_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&Window, message::Message) -> Option<isize> + 'static,
+    {
+        self.callback = Some(Box::new(callback));
         self
     }
 
     /// Creates the window. If it has a parent, dropping the window won't destroy the control,
     /// because it will be unusable when the parent is dropped instead.
     pub fn create<'b>(self, class: &'b class::Class, name: &str) -> Result<Window<'b>> {
-        let window_name = CString::new(name)?;
+        let window_name = to_wide(name)?;
         let parent = self.parent.map(|w| w.hwnd_ptr()).unwrap_or(ptr::null_mut());
 
-        // Register to temporary callback under the special value 0 since hwnd is unknown but
-        // window creation produces messages.
-        if let Some(callback) = self.callback {
-            crate::HWND_TO_CALLBACK.lock().unwrap().insert(0, callback);
-        }
+        // The window procedure takes its own reference to the callback when the window is
+        // created. If it never does (because creation fails early, or the class doesn't use the
+        // wrapped window procedure), the callback is dropped when this function returns.
+        let callback = self.callback.map(Rc::new);
+        let params = callback.as_ref().map_or(ptr::null(), Rc::as_ptr);
 
         let hwnd = unsafe {
-            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createwindowexa
-            CreateWindowExA(
+            // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createwindowexw
+            CreateWindowExW(
                 self.extended_style,
                 class.class_name_ptr(),
-                window_name.as_ptr() as LPCSTR,
+                window_name.as_ptr(),
                 self.style,
                 self.x,
                 self.y,
@@ -511,17 +529,12 @@ impl<'a> Builder<'a> {
                 parent,
                 self.menu,
                 base_instance(),
-                ptr::null_mut(), // no creation data
+                params as LPVOID,
             )
         };
 
-        // Remove the temporary callback (inside a block to drop the lock immediately).
-        {
-            crate::HWND_TO_CALLBACK.lock().unwrap().remove(&0);
-        }
-
         non_null_or_err(hwnd).map(|hwnd| {
-            let window = if self.parent.is_none() {
+            if parent.is_null() {
                 Window::Owned {
                     _window_name: window_name,
                     hwnd,
@@ -529,16 +542,7 @@ impl<'a> Builder<'a> {
                 }
             } else {
                 Window::Borrowed { hwnd }
-            };
-
-            if let Some(callback) = self.callback {
-                crate::HWND_TO_CALLBACK
-                    .lock()
-                    .unwrap()
-                    .insert(hwnd.as_ptr() as usize, callback);
             }
-
-            window
         })
     }
 }
@@ -649,49 +653,60 @@ impl Window<'_> {
     /// Creates a modal dialog box from a dialog box template resource. The function does not
     /// return control until the specified callback function terminates the modal dialog box
     /// by calling the `Window::end_dialog` function.
-    pub fn show_dialog(&self, resource: u16, callback: DialogCallback) -> Result<isize> {
+    pub fn show_dialog<F>(&self, resource: u16, callback: F) -> Result<isize>
+    where
+        F: Fn(&Window, message::Message) -> isize + 'static,
+    {
         let hinstance = base_instance();
-        let resource = MAKEINTRESOURCEA(resource);
+        let resource = MAKEINTRESOURCEW(resource);
         let hwnd = self.hwnd_ptr();
 
-        // Can't know what the dialog's handle is beforehand. The special value 0 will be
-        // replaced with the right value as soon as the init dialog message arrives.
-        crate::HWND_TO_DLG_CALLBACK
-            .lock()
-            .unwrap()
-            .insert(0, callback);
+        // The dialog procedure takes its own reference to the callback when the init dialog
+        // message arrives, which is kept until the dialog is destroyed.
+        let callback: Rc<DialogCallback> = Rc::new(Box::new(callback));
 
-        let result =
-            unsafe { DialogBoxParamA(hinstance, resource, hwnd, Some(dlg_proc_wrapper), 0) };
+        let result = unsafe {
+            SetLastError(0);
+            DialogBoxParamW(
+                hinstance,
+                resource,
+                hwnd,
+                Some(dlg_proc_wrapper),
+                Rc::as_ptr(&callback) as LPARAM,
+            )
+        };
 
-        match result {
-            0 => panic!("invalid parent hwnd"),
-            -1 => Err(Error::last_os_error()),
-            n => Ok(n),
-        }
+        dialog::modal_result(result)
     }
 
     /// Creates a modeless dialog box from a dialog box template resource.
     /// An application can use this value to initialize dialog box controls.
-    pub fn create_dialog<'a, 'b>(
-        &'a self,
-        resource: u16,
-        callback: DialogCallback,
-    ) -> Result<Window<'b>> {
+    ///
+    /// The dialog box is registered with the message loop until it's destroyed, so that keyboard
+    /// navigation (such as Tab, Enter and Esc) works inside it.
+    pub fn create_dialog<'a, 'b, F>(&'a self, resource: u16, callback: F) -> Result<Window<'b>>
+    where
+        F: Fn(&Window, message::Message) -> isize + 'static,
+    {
         let hinstance = base_instance();
-        let resource = MAKEINTRESOURCEA(resource);
+        let resource = MAKEINTRESOURCEW(resource);
         let hwnd = self.hwnd_ptr();
 
-        crate::HWND_TO_DLG_CALLBACK
-            .lock()
-            .unwrap()
-            .insert(0, callback);
+        let callback: Rc<DialogCallback> = Rc::new(Box::new(callback));
 
         // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createdialoga
-        let result =
-            unsafe { CreateDialogParamA(hinstance, resource, hwnd, Some(dlg_proc_wrapper), 0) };
+        let result = unsafe {
+            CreateDialogParamW(
+                hinstance,
+                resource,
+                hwnd,
+                Some(dlg_proc_wrapper),
+                Rc::as_ptr(&callback) as LPARAM,
+            )
+        };
 
         non_null_or_err(result).map(|hwnd| {
+            crate::register_dialog(hwnd.as_ptr());
             // Ownership is a bit tricky here because on the callback we don't have the owned window.
             let window = Window::Borrowed { hwnd };
 
@@ -699,6 +714,68 @@ impl Window<'_> {
         })
     }
 
+    /// Creates a modal dialog box from a dialog box template built in memory. The function does
+    /// not return control until the specified callback function terminates the modal dialog box
+    /// by calling the `Window::end_dialog` function.
+    pub fn show_dialog_template<F>(&self, template: &dialog::Template, callback: F) -> Result<isize>
+    where
+        F: Fn(&Window, message::Message) -> isize + 'static,
+    {
+        let hinstance = base_instance();
+        let buffer = template.to_aligned();
+        let hwnd = self.hwnd_ptr();
+
+        let callback: Rc<DialogCallback> = Rc::new(Box::new(callback));
+
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-dialogboxindirectparama
+        let result = unsafe {
+            SetLastError(0);
+            DialogBoxIndirectParamW(
+                hinstance,
+                buffer.as_ptr() as LPCDLGTEMPLATEW,
+                hwnd,
+                Some(dlg_proc_wrapper),
+                Rc::as_ptr(&callback) as LPARAM,
+            )
+        };
+
+        dialog::modal_result(result)
+    }
+
+    /// Creates a modeless dialog box from a dialog box template built in memory. As with
+    /// `create_dialog`, the dialog box is registered with the message loop.
+    pub fn create_dialog_template<'b, F>(
+        &self,
+        template: &dialog::Template,
+        callback: F,
+    ) -> Result<Window<'b>>
+    where
+        F: Fn(&Window, message::Message) -> isize + 'static,
+    {
+        let hinstance = base_instance();
+        let buffer = template.to_aligned();
+        let hwnd = self.hwnd_ptr();
+
+        let callback: Rc<DialogCallback> = Rc::new(Box::new(callback));
+
+        // The template is no longer needed once the function returns.
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createdialogindirectparama
+        let result = unsafe {
+            CreateDialogIndirectParamW(
+                hinstance,
+                buffer.as_ptr() as LPCDLGTEMPLATEW,
+                hwnd,
+                Some(dlg_proc_wrapper),
+                Rc::as_ptr(&callback) as LPARAM,
+            )
+        };
+
+        non_null_or_err(result).map(|hwnd| {
+            crate::register_dialog(hwnd.as_ptr());
+            Window::Borrowed { hwnd }
+        })
+    }
+
     /// Builder to initialize an Open or Save As dialog box. After the user closes the dialog
     /// box, the system returns information about the user's selection.
     pub fn open_file<'a>(&'a self) -> dialog::OpenFileBuilder<'a> {
@@ -706,14 +783,14 @@ impl Window<'_> {
     }
 
     /// Updates the client area of the specified window by sending a `Paint` message to the window if the window's update region is not empty. The function sends a `Paint` message directly to the window procedure of the specified window, bypassing the application queue. If the update region is empty, no message is sent.
-    pub fn update(&self) -> std::result::Result<(), ()> {
+    pub fn update(&self) -> Result<()> {
         let result = unsafe { UpdateWindow(self.hwnd_ptr()) };
 
         // `UpdateWindow` doesn't actually set last OS error if it fails.
         if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(Error::Gdi("UpdateWindow"))
         }
     }
 
@@ -736,7 +813,7 @@ impl Window<'_> {
 
     /// Indicates to the system that a window or an application should terminate.
     pub fn close(&self) -> Result<()> {
-        let result = unsafe { PostMessageA(self.hwnd_ptr(), WM_CLOSE, 0, 0) };
+        let result = unsafe { PostMessageW(self.hwnd_ptr(), WM_CLOSE, 0, 0) };
         ok_or_last_err(result)
     }
 
@@ -766,9 +843,9 @@ impl Window<'_> {
     /// window has a title bar). If the specified window is a control, the function retrieves
     /// the length of the text within the control.
     pub fn get_text_len(&self) -> usize {
-        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowtextlengtha
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getwindowtextlengthw
         // https://docs.microsoft.com/en-us/windows/win32/winmsg/wm-gettextlength
-        let result = unsafe { SendMessageA(self.hwnd_ptr(), WM_GETTEXTLENGTH, 0, 0) };
+        let result = unsafe { SendMessageW(self.hwnd_ptr(), WM_GETTEXTLENGTH, 0, 0) };
         result as usize
     }
 
@@ -776,11 +853,11 @@ impl Window<'_> {
     pub fn get_text(&self) -> String {
         let len = self.get_text_len();
         // +1 for the NUL character
-        let mut buffer = vec![0u8; len + 1];
-        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getdlgitemtexta
+        let mut buffer = vec![0u16; len + 1];
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getdlgitemtextw
         // https://docs.microsoft.com/en-us/windows/win32/winmsg/wm-gettext
         let result = unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
                 WM_GETTEXT,
                 buffer.len(),
@@ -788,67 +865,63 @@ impl Window<'_> {
             )
         };
         buffer.truncate(result as usize);
-        String::from_utf8(buffer).unwrap()
+        from_wide(&buffer)
     }
 
     /// Gets the application-defined value associated with the specified list box item.
-    pub fn get_item_data(&self, index: usize) -> std::result::Result<isize, ()> {
+    pub fn get_item_data(&self, index: usize) -> Result<isize> {
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getitemdata
-        let result = unsafe { SendMessageA(self.hwnd_ptr(), LB_GETITEMDATA, index, 0) };
+        let result = unsafe { SendMessageW(self.hwnd_ptr(), LB_GETITEMDATA, index, 0) };
         if result == LB_ERR {
-            Err(())
+            Err(Error::Control(ControlError::Failed))
         } else {
             Ok(result)
         }
     }
 
-    /// Sets the title or text of a control in a dialog box.
+    /// Sets the title or text of a control in a dialog box. Fails if the text contains a null
+    /// character.
     pub fn set_text(&self, text: &str) -> bool {
-        let text = CString::new(text).unwrap();
-        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setdlgitemtexta
+        let text = match to_wide(text) {
+            Ok(text) => text,
+            Err(_) => return false,
+        };
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setdlgitemtextw
         // https://docs.microsoft.com/en-us/windows/win32/winmsg/wm-settext
         let result =
-            unsafe { SendMessageA(self.hwnd_ptr(), WM_SETTEXT, 0, text.as_ptr() as isize) };
+            unsafe { SendMessageW(self.hwnd_ptr(), WM_SETTEXT, 0, text.as_ptr() as isize) };
         result == TRUE as isize
     }
 
     /// Gets the total number of selected items in a multiple-selection list box.
-    pub fn get_selection_count(&self) -> std::result::Result<usize, ()> {
+    pub fn get_selection_count(&self) -> Result<usize> {
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getselcount
-        let result = unsafe { SendMessageA(self.hwnd_ptr(), LB_GETSELCOUNT, 0, 0) };
-        if result == LB_ERR {
-            Err(())
-        } else {
-            Ok(result as usize)
-        }
+        let result = unsafe { SendMessageW(self.hwnd_ptr(), LB_GETSELCOUNT, 0, 0) };
+        control_result(result)
     }
 
     /// Fills a buffer with an array of integers that specify the item numbers of selected items
     /// in a multiple-selection list box.
-    pub fn get_selected_items(&self) -> std::result::Result<Vec<u32>, ()> {
+    pub fn get_selected_items(&self) -> Result<Vec<u32>> {
         let count = self.get_selection_count()?;
         let mut buffer = vec![0; count];
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-getselitems
         let result = unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
                 LB_GETSELITEMS,
                 buffer.len(),
                 buffer.as_mut_ptr() as isize,
             )
         };
-        if result == LB_ERR {
-            Err(())
-        } else {
-            buffer.truncate(result as usize);
-            Ok(buffer)
-        }
+        buffer.truncate(control_result(result)?);
+        Ok(buffer)
     }
 
     /// Adds one or more buttons to a toolbar.
-    pub fn add_toolbar_buttons(&self, buttons: &[toolbar::Button]) -> std::result::Result<(), ()> {
+    pub fn add_toolbar_buttons(&self, buttons: &[toolbar::Button]) -> Result<()> {
         unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
                 TB_BUTTONSTRUCTSIZE,
                 std::mem::size_of::<TBBUTTON>(),
@@ -861,7 +934,7 @@ impl Window<'_> {
             nID: IDB_STD_SMALL_COLOR,
         };
         let result = unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
                 TB_ADDBITMAP,
                 0,
@@ -869,13 +942,13 @@ impl Window<'_> {
             )
         };
         if result == -1 {
-            return Err(());
+            return Err(Error::Control(ControlError::Failed));
         }
 
         let result = unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
-                TB_ADDBUTTONSA,
+                TB_ADDBUTTONSW,
                 buttons.len(),
                 buttons.as_ptr() as isize,
             )
@@ -884,7 +957,7 @@ impl Window<'_> {
         if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(Error::Control(ControlError::Failed))
         }
     }
 
@@ -892,9 +965,9 @@ impl Window<'_> {
     /// part. Each element specifies the position, in client coordinates, of the right edge of the
     /// corresponding part. If an element is -1, the right edge of the corresponding part extends
     /// to the border of the window.
-    pub fn set_toolbar_parts(&self, parts: &[i32]) -> std::result::Result<(), ()> {
+    pub fn set_toolbar_parts(&self, parts: &[i32]) -> Result<()> {
         let result = unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
                 SB_SETPARTS,
                 parts.len(),
@@ -905,17 +978,17 @@ impl Window<'_> {
         if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(Error::Control(ControlError::Failed))
         }
     }
 
     /// Sets the text in the specified part of a status window.
-    pub fn set_toolbar_text(&self, index: u8, text: &str) -> std::result::Result<(), ()> {
-        let text = CString::new(text).unwrap();
+    pub fn set_toolbar_text(&self, index: u8, text: &str) -> Result<()> {
+        let text = to_wide(text)?;
         let result = unsafe {
-            SendMessageA(
+            SendMessageW(
                 self.hwnd_ptr(),
-                SB_SETTEXTA,
+                SB_SETTEXTW,
                 index as usize,
                 text.as_ptr() as isize,
             )
@@ -924,71 +997,58 @@ impl Window<'_> {
         if result != 0 {
             Ok(())
         } else {
-            Err(())
+            Err(Error::Control(ControlError::Failed))
         }
     }
 
     /// Adds a string to a list box. If the list box does not have the `Sort` style, the string
     /// is added to the end of the list. Otherwise, the string is inserted into the list and the
     /// list is sorted.
-    pub fn add_string_item(&self, text: &str) -> std::result::Result<usize, ()> {
-        let text = CString::new(text).map_err(drop)?;
+    pub fn add_string_item(&self, text: &str) -> Result<usize> {
+        let text = to_wide(text)?;
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-addstring
         let result =
-            unsafe { SendMessageA(self.hwnd_ptr(), LB_ADDSTRING, 0, text.as_ptr() as isize) };
-
-        if result == LB_ERR || result == LB_ERRSPACE {
-            Err(())
-        } else {
-            Ok(result as usize)
-        }
+            unsafe { SendMessageW(self.hwnd_ptr(), LB_ADDSTRING, 0, text.as_ptr() as isize) };
+        control_result(result)
     }
 
     /// Sets a value associated with the specified item in a list box.
-    pub fn set_item_data(&self, index: usize, data: isize) -> std::result::Result<(), ()> {
+    pub fn set_item_data(&self, index: usize, data: isize) -> Result<()> {
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-setitemdata
-        let result = unsafe { SendMessageA(self.hwnd_ptr(), LB_SETITEMDATA, index, data) };
-        if result == LB_ERR {
-            Err(())
-        } else {
-            Ok(())
-        }
+        let result = unsafe { SendMessageW(self.hwnd_ptr(), LB_SETITEMDATA, index, data) };
+        control_result(result).map(drop)
     }
 
     /// Deletes a string in a list box.
-    pub fn delete_string_item(&self, index: u32) -> std::result::Result<(), ()> {
+    pub fn delete_string_item(&self, index: u32) -> Result<()> {
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-deletestring
-        let result = unsafe { SendMessageA(self.hwnd_ptr(), LB_DELETESTRING, index as usize, 0) };
-        if result == LB_ERR {
-            Err(())
-        } else {
-            Ok(())
-        }
+        let result = unsafe { SendMessageW(self.hwnd_ptr(), LB_DELETESTRING, index as usize, 0) };
+        control_result(result).map(drop)
     }
 
     pub fn clear_content(&self) {
         // https://docs.microsoft.com/en-us/windows/win32/controls/lb-resetcontent
-        let _result = unsafe { SendMessageA(self.hwnd_ptr(), LB_RESETCONTENT, 0, 0) };
+        let _result = unsafe { SendMessageW(self.hwnd_ptr(), LB_RESETCONTENT, 0, 0) };
     }
 
     /// Causes the toolbar to be resized.
     pub fn auto_size_toolbar(&self) {
-        let _result = unsafe { SendMessageA(self.hwnd_ptr(), TB_AUTOSIZE, 0, 0) };
+        let _result = unsafe { SendMessageW(self.hwnd_ptr(), TB_AUTOSIZE, 0, 0) };
     }
 
     /// Causes the window to be resized with width and height of 0.
     pub fn restore(&self) {
-        let _result = unsafe { SendMessageA(self.hwnd_ptr(), WM_SIZE, 0, 0) };
+        let _result = unsafe { SendMessageW(self.hwnd_ptr(), WM_SIZE, 0, 0) };
     }
 
     /// Prepares the window for painting and returns an object that can be used to perform paint
     /// operations.
-    pub fn paint<'w>(&'w self) -> std::result::Result<gdi::Canvas<'w, 'w>, ()> {
+    pub fn paint<'w>(&'w self) -> Result<gdi::Canvas<'w, 'w>> {
         gdi::Canvas::from_window(self)
     }
 
     /// Like [`Self::paint`] but can be used outside of [`Message::Paint`] events.
-    pub fn repaint<'w>(&'w self) -> std::result::Result<gdi::Canvas<'w, 'w>, ()> {
+    pub fn repaint<'w>(&'w self) -> Result<gdi::Canvas<'w, 'w>> {
         gdi::Canvas::from_window_settings(self)
     }
 
@@ -1014,6 +1074,26 @@ impl Window<'_> {
         let result = unsafe { KillTimer(self.hwnd_ptr(), timer_id.get()) };
         ok_or_last_err(result)
     }
+
+    /// Requests a `MouseLeave` message to be sent when the mouse cursor leaves the window, and
+    /// a `MouseHover` message if `hover` is `true` and the cursor stays still over the window
+    /// for the system's hover time. Tracking stops once either message is sent, so it's usually
+    /// requested again on the next `MouseMove`.
+    pub fn track_mouse(&self, hover: bool) -> Result<()> {
+        // https://docs.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-trackmouseevent
+        let mut event = TRACKMOUSEEVENT {
+            cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+            dwFlags: if hover {
+                TME_LEAVE | TME_HOVER
+            } else {
+                TME_LEAVE
+            },
+            hwndTrack: self.hwnd_ptr(),
+            dwHoverTime: HOVER_DEFAULT,
+        };
+        let result = unsafe { TrackMouseEvent(&mut event) };
+        ok_or_last_err(result)
+    }
 }
 
 impl Drop for Window<'_> {